@@ -0,0 +1,63 @@
+/*
+ * A C interface to the solutions to Matt Parker's Maths Puzzles.
+ *
+ * The library is built with the `ffi` feature:
+ *
+ *     cargo build --release --features ffi
+ *
+ * Each `mpmp_solve_` function stores the solution in `*solution` as a JSON string
+ * and returns MPMP_OK, or stores an error message and returns MPMP_ERROR.
+ * Either way, the string must be freed with `mpmp_free_string`.
+ * If `solution` is null, nothing is stored and MPMP_ERROR is returned.
+ */
+
+#ifndef MPMP_H
+#define MPMP_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MPMP_OK 0
+#define MPMP_ERROR 1
+
+struct MpmpSpinningTableParameters {
+    /* The number of seats at the table. */
+    uint32_t number_of_seats;
+
+    /* The largest number of solutions to find, or 0 to find them all. */
+    uint32_t max_solutions;
+
+    /* Whether redundant solutions are included in the result. */
+    bool include_redundant_solutions;
+};
+
+struct MpmpScrabbleParameters {
+    /* The number of tiles in a hand. */
+    uint32_t hand_size;
+
+    /* The score of the hands to count. */
+    uint32_t target_score;
+};
+
+struct MpmpCardParameters {
+    /* The number of cards to play with. */
+    uint8_t number_of_cards;
+};
+
+int mpmp_solve_spinning_table(struct MpmpSpinningTableParameters parameters, char **solution);
+
+int mpmp_solve_scrabble(struct MpmpScrabbleParameters parameters, char **solution);
+
+int mpmp_solve_card(struct MpmpCardParameters parameters, char **solution);
+
+void mpmp_free_string(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
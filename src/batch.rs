@@ -0,0 +1,170 @@
+//! The `batch` subcommand, which solves the puzzles described on stdin.
+//!
+//! Each line of the input describes a puzzle to solve,
+//! either with the same arguments as the program:
+//!
+//! ```text
+//! scrabble --hand-size 8 --target-score 50
+//! ```
+//!
+//! or as a JSON object with the puzzle's name and its options,
+//! named like the long options or like the parameters in the JSON output:
+//!
+//! ```text
+//! {"puzzle": "scrabble", "hand_size": 8, "target_score": 50}
+//! ```
+//!
+//! Each result is written on its own line as a JSON object
+//! with the puzzle's name, the parameters and the solution,
+//! or with the error if the line couldn't be solved.
+//! Empty lines and lines starting with `#` are skipped.
+
+use std::{
+    ffi::OsString,
+    io::{self, BufRead, Write},
+    iter,
+};
+
+use clap::{CommandFactory, Parser};
+
+use serde::Serialize;
+
+use serde_json::{json, Value};
+
+use matt_parkers_maths_puzzles::{
+    card::Card, scrabble::Scrabble, spinning_table::SpinningTable, Error, Solver,
+};
+
+use crate::{config, params_json, Context, Options, Puzzle};
+
+/// Solves the puzzles described by the lines of stdin,
+/// writing one result per line to `out`.
+pub fn run_batch(context: &Context, out: &mut impl Write) -> Result<(), Error> {
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let result = solve_line(line, context).unwrap_or_else(|err| json!({ "error": err }));
+        writeln!(out, "{}", result)?;
+
+        // Show each result as soon as it is ready.
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Solves the puzzle described by a line of the input,
+/// whose searches share `context`.
+fn solve_line(line: &str, context: &Context) -> Result<Value, String> {
+    let args = if line.starts_with('{') {
+        json_to_args(line)?
+    } else {
+        shell_words::split(line).map_err(|err| err.to_string())?
+    };
+
+    let args = iter::once(env!("CARGO_PKG_NAME").to_string())
+        .chain(args)
+        .map(OsString::from)
+        .collect();
+    let mut options = params_json::apply(args)
+        .and_then(config::apply)
+        .and_then(Options::try_parse_from)
+        .map_err(|err| clap_error_message(&err))?;
+
+    context.share(&mut options.puzzle);
+    let result = match options.puzzle {
+        Puzzle::SpinningTable(mut parameters) => {
+            parameters.set_max_solutions(options.max_solutions);
+            solve::<SpinningTable>(parameters)
+        }
+        Puzzle::Scrabble(mut parameters) => {
+            parameters.set_max_solutions(options.max_solutions);
+            solve::<Scrabble>(parameters)
+        }
+        Puzzle::Card(parameters) => solve::<Card>(parameters),
+        _ => {
+            return Err(String::from(
+                "each line must solve `spinning-table`, `scrabble` or `card`",
+            ))
+        }
+    };
+
+    result.map_err(|err| err.to_string())
+}
+
+/// Solves a puzzle and returns the JSON object that describes the result.
+fn solve<S>(parameters: S::Parameters) -> Result<Value, Error>
+where
+    S: Solver,
+    S::Parameters: Serialize,
+    S::Output: Serialize,
+{
+    let echoed = json!(parameters);
+    let solution = S::solve(parameters)?;
+    Ok(json!({
+        "puzzle": S::NAME,
+        "parameters": echoed,
+        "solution": solution,
+    }))
+}
+
+/// Converts a JSON object with the puzzle's name and its options to arguments.
+///
+/// `true` gives a flag, `false` and `null` leave it out,
+/// and an array gives an option once for each element.
+/// The global options, such as `max_solutions`, go before the puzzle's name.
+fn json_to_args(line: &str) -> Result<Vec<String>, String> {
+    let command = Options::command();
+    let object = match serde_json::from_str(line).map_err(|err| err.to_string())? {
+        Value::Object(object) => object,
+        _ => return Err(String::from("expected a JSON object")),
+    };
+
+    let mut puzzle = None;
+    let mut global_options = vec![];
+    let mut options = vec![];
+    for (key, value) in object {
+        if key == "puzzle" {
+            match value {
+                Value::String(name) => puzzle = Some(name),
+                _ => return Err(String::from("`puzzle` must be a string")),
+            }
+
+            continue;
+        }
+
+        let key = key.replace('_', "-");
+        let is_global = command
+            .get_arguments()
+            .any(|arg| arg.get_long() == Some(key.as_str()));
+        let options = if is_global {
+            &mut global_options
+        } else {
+            &mut options
+        };
+
+        options.extend(params_json::value_to_args(&key, value)?);
+    }
+
+    let puzzle = puzzle.ok_or_else(|| String::from("missing `puzzle`"))?;
+    Ok(global_options
+        .into_iter()
+        .chain(iter::once(puzzle))
+        .chain(options)
+        .collect())
+}
+
+/// Returns the first line of a command-line error, without its `error: ` prefix.
+fn clap_error_message(err: &clap::Error) -> String {
+    let rendered = err.render().to_string();
+    let first_line = rendered.lines().next().unwrap_or_default();
+    first_line
+        .strip_prefix("error: ")
+        .unwrap_or(first_line)
+        .to_string()
+}
@@ -0,0 +1,23 @@
+use quick_error::quick_error;
+
+quick_error! {
+    /// An error that can be returned when building parameters
+    /// with a combination of options that the command line would reject.
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum BuildError {
+        /// Two options that cannot be used together were both set.
+        Conflict(option: &'static str, other: &'static str) {
+            display("`{}` cannot be used with `{}`", option, other)
+        }
+
+        /// An option was set without another option that it requires.
+        MissingRequirement(option: &'static str, required: &'static str) {
+            display("`{}` requires `{}`", option, required)
+        }
+
+        /// An option was set to a value larger than the command line accepts.
+        TooLarge(option: &'static str, max: u32) {
+            display("`{}` must be at most {}", option, max)
+        }
+    }
+}
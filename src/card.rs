@@ -1,12 +1,36 @@
 use std::{
+    collections::BTreeMap,
     convert::{TryFrom, TryInto},
-    num::ParseIntError,
+    fmt::{self, Display, Formatter},
+    io::{self, BufWriter, Write},
+    iter,
+    num::{NonZeroUsize, ParseIntError},
     str::FromStr,
 };
 
+use itertools::{Either, Itertools};
+
 use quick_error::quick_error;
 
-use structopt::StructOpt;
+use rand::Rng;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, Serializer};
+
+#[cfg(feature = "clap")]
+use clap::{builder::TypedValueParser, value_parser, Parser, ValueEnum};
+
+use tracing::{debug, debug_span};
+
+#[cfg(feature = "clap")]
+use crate::value_parser::non_zero_usize;
+
+use crate::{
+    builder::BuildError,
+    color::{self, Color},
+    progress::Progress,
+    Error, Solver,
+};
 
 /// Produces a solution to the card puzzle.
 ///
@@ -17,7 +41,165 @@ use structopt::StructOpt;
 /// that guarantees all four cards
 /// will eventually be face down
 /// given any starting position.
-pub fn card(parameters: Parameters) -> Box<[u8]> {
+pub fn card(parameters: Parameters) -> Result<Output, Error> {
+    let _span = debug_span!(
+        "card",
+        number_of_cards = parameters.number_of_cards.number_of_cards,
+        method = ?parameters.method,
+    )
+    .entered();
+
+    let solution = match parameters.method {
+        Method::Recurrence => card_recurrence(&parameters.number_of_cards),
+        Method::ClosedForm => card_closed_form(&parameters.number_of_cards),
+    };
+    debug!(flips = solution.len(), "found the sequence of flips");
+
+    // Each flip of the solution is a step of the construction.
+    parameters.progress.explore(solution.len() as u64);
+    parameters.progress.find(1);
+
+    let flips = if parameters.round_trip {
+        // Playing the flips in reverse order
+        // visits the same states in reverse order,
+        // which brings the cards back to their starting position.
+        // (The solution is a palindrome,
+        // so this is really the same sequence of flips again.)
+        solution
+            .iter()
+            .chain(solution.iter().rev())
+            .cloned()
+            .collect()
+    } else {
+        solution
+    };
+
+    let number_of_cards = parameters.number_of_cards.number_of_cards;
+    let start = match &parameters.start {
+        Some(start) => start.cards(number_of_cards)?,
+        None => vec![false; number_of_cards as usize],
+    };
+
+    let flip_histogram = if parameters.flip_histogram {
+        Some(FlipHistogram::new(&flips))
+    } else {
+        None
+    };
+
+    Ok(Output {
+        flips,
+        start,
+        flip_histogram,
+        presentation: Presentation {
+            narrate: parameters.narrate,
+            group: parameters.group,
+            output: parameters.output,
+            color: parameters.color,
+        },
+    })
+}
+
+/// Returns an iterator over the sequence of flips,
+/// including the return trip if `--round-trip` is set.
+///
+/// The flips are computed as the iterator is advanced,
+/// like with `--method closed-form`,
+/// so they don't need to be held in memory.
+/// The presentation options are ignored.
+pub fn iter_flips(parameters: &Parameters) -> impl Iterator<Item = u8> {
+    let positions = 1..parameters.number_of_cards.number_of_card_states;
+
+    // The solution is a palindrome,
+    // so the return trip is the same sequence of flips again.
+    let return_trip = if parameters.round_trip {
+        Either::Left(positions.clone())
+    } else {
+        Either::Right(iter::empty())
+    };
+
+    positions.chain(return_trip).map(card_to_flip)
+}
+
+/// Writes the sequence of flips to `out` like the list output,
+/// computing each flip as it is written with `iter_flips`.
+///
+/// `out` is buffered by this function.
+pub fn write_flips(parameters: &Parameters, out: impl io::Write) -> Result<(), Error> {
+    let mut out = BufWriter::new(out);
+    write!(out, "[")?;
+    for (index, card_number) in iter_flips(parameters).enumerate() {
+        write!(
+            out,
+            "{}{}",
+            flip_separator(index, parameters.group),
+            paint_card(card_number, parameters.color)
+        )?;
+    }
+    writeln!(out, "]")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Deals the cards in a random starting position `samples` times
+/// and counts the flips of the solution it takes
+/// for all the cards to be face down.
+///
+/// The presentation options are ignored.
+pub fn simulate(parameters: &Parameters, samples: NonZeroUsize, rng: &mut impl Rng) -> Simulation {
+    let number_of_states = parameters.number_of_cards.number_of_card_states as u64;
+    let mut simulation = Simulation {
+        samples: samples.get(),
+        total_flips: 0,
+        most_flips: 0,
+        solution_flips: number_of_states - 1,
+    };
+    for _ in 0..samples.get() {
+        // Every state of the cards is equally likely.
+        let face_down = rng.gen::<u64>() & (number_of_states - 1);
+        let flips = flips_until_face_down(face_down, number_of_states);
+        simulation.total_flips += flips as u128;
+        simulation.most_flips = simulation.most_flips.max(flips);
+    }
+
+    simulation
+}
+
+/// Returns the number of flips of the solution
+/// that bring the cards from the given state to all face down,
+/// where bit `i` of `face_down` is set if card `i + 1` is face down.
+///
+/// After `k` flips, the solution has flipped the cards
+/// whose bits are set in the Gray code of `k`,
+/// so the answer is the inverse Gray code of the cards that are face up.
+fn flips_until_face_down(face_down: u64, number_of_states: u64) -> u64 {
+    let face_up = !face_down & (number_of_states - 1);
+    let mut flips = face_up;
+    let mut shifted = face_up >> 1;
+    while shifted != 0 {
+        flips ^= shifted;
+        shifted >>= 1;
+    }
+
+    flips
+}
+
+/// The card puzzle.
+#[derive(Clone, Copy, Debug)]
+pub struct Card;
+
+impl Solver for Card {
+    const NAME: &'static str = "card";
+    type Parameters = Parameters;
+    type Output = Output;
+
+    fn solve(parameters: Parameters) -> Result<Output, Error> {
+        card(parameters)
+    }
+}
+
+/// Produces a solution to the card puzzle
+/// by building the sequence of flips from the solution for fewer cards.
+fn card_recurrence(number_of_cards: &NumberOfCards) -> Box<[u8]> {
     // The general idea is to generate a sequence of flips
     // that will visit every possible state of the set of cards.
     //
@@ -38,7 +220,7 @@ pub fn card(parameters: Parameters) -> Box<[u8]> {
     // for all dimensions m from 1 to n.
 
     // The optimal number of flips for n cards is 2**n - 1.
-    let capacity = parameters.number_of_cards.number_of_card_states - 1;
+    let capacity = number_of_cards.number_of_card_states - 1;
     let mut solution = vec![0; capacity].into_boxed_slice();
     let mut solution_len = 0;
 
@@ -58,7 +240,7 @@ pub fn card(parameters: Parameters) -> Box<[u8]> {
     // This ensures that we don't visit the same state twice.
     //
     // Incrementally solve for one more card at a time.
-    for m in 1..=parameters.number_of_cards.number_of_cards {
+    for m in 1..=number_of_cards.number_of_cards {
         let partial_solution_len = solution_len;
 
         // Insert a flip of the nth card.
@@ -76,15 +258,829 @@ pub fn card(parameters: Parameters) -> Box<[u8]> {
     solution
 }
 
+/// Produces a solution to the card puzzle
+/// by computing each flip directly from its position in the sequence.
+fn card_closed_form(number_of_cards: &NumberOfCards) -> Box<[u8]> {
+    // Unrolling the recurrence used by `card_recurrence`,
+    // card m is flipped at every position i (starting from 1)
+    // that is an odd multiple of 2**(m - 1).
+    // In other words, the card to flip at position i
+    // is one more than the number of trailing zeros of i.
+    // This is the sequence of bits that change
+    // when counting with a Gray code.
+    (1..number_of_cards.number_of_card_states)
+        .map(card_to_flip)
+        .collect()
+}
+
+/// Returns the card to flip at the given position (starting from 1)
+/// in the sequence of flips.
+fn card_to_flip(position: usize) -> u8 {
+    position.trailing_zeros() as u8 + 1
+}
+
 /// Parameters for solving variants of the card puzzle.
-#[derive(StructOpt)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Parameters {
     /// The number of cards to play with.
-    #[structopt(short = "n", long, default_value = "4")]
+    #[cfg_attr(feature = "clap", arg(short = 'n', long, default_value = "4", value_parser = value_parser!(u8).try_map(NumberOfCards::try_from)))]
     number_of_cards: NumberOfCards,
+
+    /// How the sequence of flips is computed.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = Method::Recurrence))]
+    method: Method,
+
+    /// If set, the sequence of flips is followed by the flips
+    /// that visit every state again in reverse order,
+    /// returning the cards to their starting position.
+    #[cfg_attr(feature = "clap", arg(long))]
+    round_trip: bool,
+
+    /// If set, the state of the cards is shown after each flip,
+    /// starting with all cards face up (`U`) and flipping them face down (`D`).
+    #[cfg_attr(feature = "clap", arg(long))]
+    narrate: bool,
+
+    /// If set, separators are inserted every `group` cards
+    /// when showing the state of the cards
+    /// and every `group` flips when showing the sequence of flips.
+    #[cfg_attr(feature = "clap", arg(long, value_parser = non_zero_usize()))]
+    group: Option<NonZeroUsize>,
+
+    /// If set, the number of times each card is flipped is shown
+    /// instead of the sequence of flips.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with = "narrate"))]
+    flip_histogram: bool,
+
+    /// The state of the cards before the first flip when narrating,
+    /// instead of all cards face up.
+    ///
+    /// The state is either a board such as `UUDU`,
+    /// listing the cards from card 1 as face up (`U`) or face down (`D`),
+    /// or a number (`0x`, `0b` or decimal)
+    /// whose binary digits list the cards the same way,
+    /// with a 1 for a face down card.
+    #[cfg_attr(feature = "clap", arg(long, requires = "narrate"))]
+    start: Option<StartState>,
+
+    /// How the sequence of flips is presented.
+    ///
+    /// `rle` compresses the sequence using the solutions for fewer cards:
+    /// `S(m)` stands for the solution for `m` cards,
+    /// which is defined after `where`.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = OutputFormat::List, conflicts_with_all = ["narrate", "flip_histogram"]))]
+    output: OutputFormat,
+
+    /// Whether the card numbers in the sequence of flips are colored.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    color: bool,
+
+    /// Records the statistics of the solution.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    progress: Progress,
+}
+
+/// Returns the defaults of the command line.
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            number_of_cards: NumberOfCards::try_from(4).unwrap(),
+            method: Method::Recurrence,
+            round_trip: false,
+            narrate: false,
+            group: None,
+            flip_histogram: false,
+            start: None,
+            output: OutputFormat::List,
+            color: false,
+            progress: Progress::default(),
+        }
+    }
+}
+
+impl Parameters {
+    /// Starts building parameters from the defaults of the command line.
+    pub fn builder() -> ParametersBuilder {
+        ParametersBuilder {
+            parameters: Parameters::default(),
+        }
+    }
+
+    /// Sets whether the card numbers in the sequence of flips are colored.
+    ///
+    /// This is the global `--color` option of the command line.
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
+    /// Sets the counters that report the statistics of the solution,
+    /// which `--stats` reads on the command line.
+    pub fn set_progress(&mut self, progress: Progress) {
+        self.progress = progress;
+    }
+
+    /// Returns the number of cards to play with.
+    pub fn number_of_cards(&self) -> u8 {
+        self.number_of_cards.number_of_cards
+    }
+
+    /// Returns the number of flips in the solution,
+    /// including the return trip if there is one.
+    pub fn number_of_flips(&self) -> usize {
+        let flips = self.number_of_cards.number_of_card_states - 1;
+        if self.round_trip {
+            flips * 2
+        } else {
+            flips
+        }
+    }
+}
+
+/// Builds `Parameters` without going through the command line.
+///
+/// Each method corresponds to the command-line option with the same name.
+#[derive(Debug)]
+pub struct ParametersBuilder {
+    /// The parameters built so far.
+    parameters: Parameters,
+}
+
+impl ParametersBuilder {
+    /// Sets the number of cards to play with.
+    pub fn number_of_cards(mut self, number_of_cards: NumberOfCards) -> Self {
+        self.parameters.number_of_cards = number_of_cards;
+        self
+    }
+
+    /// Sets how the sequence of flips is computed.
+    pub fn method(mut self, method: Method) -> Self {
+        self.parameters.method = method;
+        self
+    }
+
+    /// Sets whether the flips that return the cards to their starting position are included.
+    pub fn round_trip(mut self, round_trip: bool) -> Self {
+        self.parameters.round_trip = round_trip;
+        self
+    }
+
+    /// Sets whether the state of the cards is shown after each flip.
+    pub fn narrate(mut self, narrate: bool) -> Self {
+        self.parameters.narrate = narrate;
+        self
+    }
+
+    /// Sets the number of cards or flips between separators.
+    pub fn group(mut self, group: NonZeroUsize) -> Self {
+        self.parameters.group = Some(group);
+        self
+    }
+
+    /// Sets whether the number of times each card is flipped is shown.
+    pub fn flip_histogram(mut self, flip_histogram: bool) -> Self {
+        self.parameters.flip_histogram = flip_histogram;
+        self
+    }
+
+    /// Sets the state of the cards before the first flip when narrating.
+    pub fn start(mut self, start: StartState) -> Self {
+        self.parameters.start = Some(start);
+        self
+    }
+
+    /// Sets how the sequence of flips is presented.
+    pub fn output(mut self, output: OutputFormat) -> Self {
+        self.parameters.output = output;
+        self
+    }
+
+    /// Checks that the options can be used together
+    /// and returns the parameters.
+    /// Sets whether the card numbers in the sequence of flips are colored.
+    pub fn color(mut self, color: bool) -> Self {
+        self.parameters.color = color;
+        self
+    }
+
+    pub fn build(self) -> Result<Parameters, BuildError> {
+        let parameters = self.parameters;
+        if parameters.flip_histogram && parameters.narrate {
+            return Err(BuildError::Conflict("flip_histogram", "narrate"));
+        }
+
+        if parameters.start.is_some() && !parameters.narrate {
+            return Err(BuildError::MissingRequirement("start", "narrate"));
+        }
+
+        if let OutputFormat::Rle = parameters.output {
+            if parameters.narrate {
+                return Err(BuildError::Conflict("output", "narrate"));
+            }
+
+            if parameters.flip_histogram {
+                return Err(BuildError::Conflict("output", "flip_histogram"));
+            }
+        }
+
+        Ok(parameters)
+    }
+}
+
+/// A solution to the card puzzle, along with how to present it.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Output {
+    /// The sequence of card numbers to flip.
+    pub flips: Box<[u8]>,
+
+    /// The state of the cards before the first flip,
+    /// where `true` means the card is face down.
+    start: Vec<bool>,
+
+    /// The number of times each card is flipped,
+    /// if it is shown instead of the sequence of flips.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    flip_histogram: Option<FlipHistogram>,
+
+    /// How the solution is presented.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    presentation: Presentation,
+}
+
+impl Output {
+    /// Returns the number of times each card is flipped, by card number.
+    pub fn flip_histogram(&self) -> FlipHistogram {
+        FlipHistogram::new(&self.flips)
+    }
+
+    /// Returns the state of the cards before the first flip and after each flip,
+    /// where `true` means the card is face down.
+    pub fn states(&self) -> impl Iterator<Item = Vec<bool>> + '_ {
+        iter::once(self.start.clone()).chain(self.flips.iter().scan(
+            self.start.clone(),
+            |cards, &card_number| {
+                let card = &mut cards[card_number as usize - 1];
+                *card = !*card;
+                Some(cards.clone())
+            },
+        ))
+    }
+
+    /// Returns whether the solution is displayed on several lines,
+    /// with one line for each state of the cards when narrating
+    /// or for each card with `--flip-histogram`.
+    pub fn is_multiline(&self) -> bool {
+        self.presentation.narrate || self.flip_histogram.is_some()
+    }
+
+    /// Returns the sequence of flips,
+    /// to be displayed with the same separators as the list output.
+    pub fn solution(&self) -> CardSolution<'_> {
+        CardSolution {
+            flips: &self.flips,
+            group: self.presentation.group,
+            color: self.presentation.color,
+        }
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let group = self.presentation.group;
+        if let Some(flip_histogram) = &self.flip_histogram {
+            write!(fmt, "{}", flip_histogram)?;
+        } else if let OutputFormat::Rle = self.presentation.output {
+            write!(fmt, "{}", encode_rle(&self.flips))?;
+        } else if self.presentation.narrate {
+            let mut states = self.states();
+            if let Some(cards) = states.next() {
+                write!(fmt, "start: {}", render_cards(&cards, group))?;
+            }
+
+            for (&card_number, cards) in self.flips.iter().zip(states) {
+                writeln!(fmt)?;
+                write!(fmt, "flip {}: {}", card_number, render_cards(&cards, group))?;
+            }
+        } else {
+            write!(fmt, "{}", self.solution())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How a solution to the card puzzle is presented.
+#[derive(Debug, Default)]
+struct Presentation {
+    /// Whether the state of the cards is shown after each flip.
+    narrate: bool,
+
+    /// The number of cards or flips between separators.
+    group: Option<NonZeroUsize>,
+
+    /// How the sequence of flips is presented.
+    output: OutputFormat,
+
+    /// Whether the card numbers in the sequence of flips are colored.
+    color: bool,
+}
+
+/// A sequence of flips that solves the card puzzle,
+/// displayed as a list of card numbers.
+#[derive(Clone, Copy, Debug)]
+pub struct CardSolution<'a> {
+    /// The sequence of card numbers to flip.
+    flips: &'a [u8],
+
+    /// The number of flips between separators.
+    group: Option<NonZeroUsize>,
+
+    /// Whether each card number is shown in its own color.
+    color: bool,
+}
+
+impl<'a> CardSolution<'a> {
+    /// Wraps a sequence of flips for display.
+    pub fn new(flips: &'a [u8]) -> Self {
+        Self {
+            flips,
+            group: None,
+            color: false,
+        }
+    }
+}
+
+impl Display for CardSolution<'_> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "[")?;
+        for (index, card_number) in self.flips.iter().enumerate() {
+            write!(
+                fmt,
+                "{}{}",
+                flip_separator(index, self.group),
+                paint_card(*card_number, self.color)
+            )?;
+        }
+        write!(fmt, "]")
+    }
+}
+
+/// The colors of the cards, starting with card 1,
+/// which are reused in the same order for the cards after the last one.
+const CARD_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Wraps a card number so that each card is shown in its own color if `color` is set.
+fn paint_card(card_number: u8, color: bool) -> impl Display {
+    color::paint(
+        color.then(|| CARD_COLORS[(card_number as usize - 1) % CARD_COLORS.len()]),
+        card_number,
+    )
+}
+
+/// Returns the separator to write before the flip at the given index
+/// in the list of flips.
+fn flip_separator(index: usize, group: Option<NonZeroUsize>) -> &'static str {
+    let group = group.map_or(usize::MAX, NonZeroUsize::get);
+    if index == 0 {
+        ""
+    } else if index.is_multiple_of(group) {
+        " | "
+    } else {
+        ", "
+    }
+}
+
+/// The number of times each card is flipped in a solution to the card puzzle.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct FlipHistogram {
+    /// The number of flips, by card number.
+    counts: BTreeMap<u8, usize>,
+}
+
+impl FlipHistogram {
+    /// Counts the flips of each card in a sequence of flips.
+    fn new(flips: &[u8]) -> Self {
+        Self {
+            counts: flips.iter().fold(BTreeMap::new(), |mut map, &card_number| {
+                *map.entry(card_number).or_insert(0) += 1;
+                map
+            }),
+        }
+    }
+
+    /// Returns the number of flips, by card number.
+    pub fn counts(&self) -> &BTreeMap<u8, usize> {
+        &self.counts
+    }
+}
+
+impl Display for FlipHistogram {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        for (index, (card_number, count)) in self.counts.iter().enumerate() {
+            if index != 0 {
+                writeln!(fmt)?;
+            }
+
+            let plural = if *count == 1 { "" } else { "s" };
+            write!(fmt, "card {}: {} flip{}", card_number, count, plural)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The result of dealing random starting positions with `simulate`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Simulation {
+    /// The number of random starting positions that were dealt.
+    samples: usize,
+
+    /// The number of flips it took for all the cards to be face down,
+    /// summed over the samples.
+    total_flips: u128,
+
+    /// The largest number of flips it took for all the cards to be face down.
+    most_flips: u64,
+
+    /// The number of flips in the solution without the return trip.
+    solution_flips: u64,
+}
+
+impl Simulation {
+    /// Returns the mean number of flips it took for all the cards to be face down.
+    pub fn mean_flips(&self) -> f64 {
+        self.total_flips as f64 / self.samples as f64
+    }
+
+    /// Returns the mean number of flips over all the starting positions,
+    /// each of which is reached after a different number of flips, from 0 to the last flip.
+    pub fn exact_mean_flips(&self) -> f64 {
+        self.solution_flips as f64 / 2.0
+    }
+}
+
+impl Display for Simulation {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "samples: {}", self.samples)?;
+        writeln!(
+            fmt,
+            "mean flips until all cards are face down: {:.2} (exact: {:.2})",
+            self.mean_flips(),
+            self.exact_mean_flips()
+        )?;
+        write!(
+            fmt,
+            "most flips: {} of {}",
+            self.most_flips, self.solution_flips
+        )
+    }
+}
+
+/// Renders the state of the cards as a string of `U` (face up) and `D` (face down),
+/// inserting a `|` every `group` cards.
+fn render_cards(cards: &[bool], group: Option<NonZeroUsize>) -> String {
+    let group = group.map_or(cards.len(), NonZeroUsize::get);
+    let mut rendered = String::with_capacity(cards.len() + cards.len() / group);
+    for (index, &face_down) in cards.iter().enumerate() {
+        if index != 0 && index % group == 0 {
+            rendered.push('|');
+        }
+
+        rendered.push(if face_down { 'D' } else { 'U' });
+    }
+
+    rendered
+}
+
+/// Encodes a sequence of flips
+/// by replacing the runs of flips that match the solution for `m` cards
+/// (for the largest possible `m`, and at least 2) with `S(m)`,
+/// followed by the definitions of the `S(m)` that were used.
+///
+/// For example, the solution for 3 cards is encoded as
+/// `S(3) where S(2) = 1, 2, 1; S(3) = S(2), 3, S(2)`.
+pub fn encode_rle(flips: &[u8]) -> String {
+    // The flip at position i (starting from 1) in the solution for m cards
+    // is one more than the number of trailing zeros of i
+    // (see `card_closed_form`).
+    let solution_flip = |position: usize| position.trailing_zeros() as u8 + 1;
+
+    let mut tokens = vec![];
+    let mut largest_block = 1;
+    let mut index = 0;
+    while index < flips.len() {
+        // The solution for m cards is a prefix of the solution for m + 1 cards,
+        // so find the longest prefix of the remaining flips
+        // that matches the start of a solution.
+        let matching_len = (1..=flips.len() - index)
+            .take_while(|&position| flips[index + position - 1] == solution_flip(position))
+            .count();
+        // The solution for m cards has 2**m - 1 flips.
+        let block = (usize::BITS - (matching_len + 1).leading_zeros() - 1) as u8;
+
+        if block >= 2 {
+            tokens.push(format!("S({})", block));
+            largest_block = largest_block.max(block);
+            index += (1 << block) - 1;
+        } else {
+            tokens.push(flips[index].to_string());
+            index += 1;
+        }
+    }
+
+    let mut encoded = tokens.join(", ");
+    if largest_block >= 2 {
+        encoded.push_str(" where ");
+        encoded.push_str(
+            &(2..=largest_block)
+                .map(|block| {
+                    if block == 2 {
+                        String::from("S(2) = 1, 2, 1")
+                    } else {
+                        format!("S({0}) = S({1}), {0}, S({1})", block, block - 1)
+                    }
+                })
+                .join("; "),
+        );
+    }
+
+    debug_assert_eq!(decode_rle(&encoded).ok().as_deref(), Some(flips));
+
+    encoded
+}
+
+/// Decodes a sequence of flips encoded by `encode_rle`,
+/// such as the output of `--output rle`.
+///
+/// The definitions after `where` may use any sequence of flips,
+/// not only the solutions for fewer cards.
+pub fn decode_rle(encoded: &str) -> Result<Box<[u8]>, RleError> {
+    let (body, definitions) = match encoded.find(" where ") {
+        Some(index) => (&encoded[..index], &encoded[index + " where ".len()..]),
+        None => (encoded, ""),
+    };
+
+    let mut blocks = BTreeMap::new();
+    for definition in definitions.split(';').filter(|d| !d.trim().is_empty()) {
+        let mut parts = definition.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(tokens)) => match parse_rle_token(name)? {
+                RleToken::Block(block) => {
+                    blocks.insert(block, parse_rle_tokens(tokens)?);
+                }
+                RleToken::Flip(_) => {
+                    return Err(RleError::InvalidDefinition(definition.trim().to_owned()))
+                }
+            },
+            _ => return Err(RleError::InvalidDefinition(definition.trim().to_owned())),
+        }
+    }
+
+    let mut flips = vec![];
+    for token in parse_rle_tokens(body)? {
+        expand_rle_token(token, &blocks, &mut flips, 0)?;
+    }
+
+    Ok(flips.into_boxed_slice())
+}
+
+/// Appends the flips that a token stands for to `flips`.
+///
+/// `depth` is the number of blocks being expanded,
+/// to detect blocks that are defined in terms of themselves.
+fn expand_rle_token(
+    token: RleToken,
+    blocks: &BTreeMap<u8, Vec<RleToken>>,
+    flips: &mut Vec<u8>,
+    depth: usize,
+) -> Result<(), RleError> {
+    match token {
+        RleToken::Flip(card_number) => flips.push(card_number),
+        RleToken::Block(block) => {
+            if depth > blocks.len() {
+                return Err(RleError::RecursiveBlock(block));
+            }
+
+            for &token in blocks.get(&block).ok_or(RleError::UndefinedBlock(block))? {
+                expand_rle_token(token, blocks, flips, depth + 1)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a comma-separated list of tokens.
+fn parse_rle_tokens(s: &str) -> Result<Vec<RleToken>, RleError> {
+    s.split(',')
+        .filter(|token| !token.trim().is_empty())
+        .map(parse_rle_token)
+        .collect()
+}
+
+/// Parses a card number or an `S(m)` block.
+fn parse_rle_token(s: &str) -> Result<RleToken, RleError> {
+    let s = s.trim();
+    let invalid_token = || RleError::InvalidToken(s.to_owned());
+    if let Some(block) = s.strip_prefix("S(").and_then(|s| s.strip_suffix(')')) {
+        Ok(RleToken::Block(block.parse().map_err(|_| invalid_token())?))
+    } else {
+        Ok(RleToken::Flip(s.parse().map_err(|_| invalid_token())?))
+    }
+}
+
+/// An element of a sequence of flips encoded by `encode_rle`.
+#[derive(Clone, Copy, Debug)]
+enum RleToken {
+    /// A flip of the card with the given number.
+    Flip(u8),
+
+    /// The solution for the given number of cards.
+    Block(u8),
+}
+
+quick_error! {
+    /// An error that can be returned when decoding a sequence of flips.
+    #[derive(Debug)]
+    pub enum RleError {
+        /// A token is neither a card number nor `S(m)`.
+        InvalidToken(token: String) {
+            display("expected a card number or `S(m)`, found `{}`", token)
+        }
+
+        /// A definition is not of the form `S(m) = ...`.
+        InvalidDefinition(definition: String) {
+            display("expected `S(m) = ...`, found `{}`", definition)
+        }
+
+        /// A block is used but not defined.
+        UndefinedBlock(block: u8) {
+            display("S({}) is not defined", block)
+        }
+
+        /// A block is defined in terms of itself.
+        RecursiveBlock(block: u8) {
+            display("S({}) is defined in terms of itself", block)
+        }
+    }
+}
+
+/// Choices for how the sequence of flips should be presented.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OutputFormat {
+    /// List every flip.
+    #[default]
+    List,
+
+    /// Replace the solutions for `m` cards with `S(m)`.
+    Rle,
+}
+
+/// Choices for how the sequence of flips should be computed.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Method {
+    /// Build the sequence from the solution for one card fewer.
+    Recurrence,
+
+    /// Compute each flip directly from its position in the sequence.
+    ClosedForm,
+}
+
+/// A state of the cards given on the command line.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct StartState {
+    /// The state of the cards as a binary number,
+    /// where a 1 means the card is face down
+    /// and the most significant digit is card 1.
+    face_down: u64,
+
+    /// The number of cards on the board,
+    /// if the state was given as a board rather than as a number.
+    board_len: Option<usize>,
+}
+
+impl StartState {
+    /// Returns the state of each card,
+    /// where `true` means the card is face down.
+    fn cards(&self, number_of_cards: u8) -> Result<Vec<bool>, StartStateError> {
+        let number_of_cards = number_of_cards as u32;
+        if let Some(board_len) = self.board_len {
+            if board_len != number_of_cards as usize {
+                return Err(StartStateError::WrongNumberOfCards(
+                    board_len,
+                    number_of_cards,
+                ));
+            }
+        }
+
+        if self.face_down.checked_shr(number_of_cards).unwrap_or(0) != 0 {
+            return Err(StartStateError::TooLarge(self.face_down, number_of_cards));
+        }
+
+        Ok((1..=number_of_cards)
+            .map(|card_number| (self.face_down >> (number_of_cards - card_number)) & 1 != 0)
+            .collect())
+    }
+}
+
+impl FromStr for StartState {
+    type Err = StartStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let face_down = if let Some(digits) = s.strip_prefix("0x") {
+            u64::from_str_radix(digits, 16)?
+        } else if let Some(digits) = s.strip_prefix("0b") {
+            u64::from_str_radix(digits, 2)?
+        } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+            s.parse()?
+        } else {
+            // Parse a board as rendered by the narration,
+            // ignoring the separators inserted by `--group`.
+            let mut face_down = 0u64;
+            let mut board_len = 0;
+            for letter in s.chars().filter(|&c| c != '|') {
+                let card = match letter.to_ascii_uppercase() {
+                    'U' => 0,
+                    'D' => 1,
+                    _ => return Err(StartStateError::InvalidLetter(letter)),
+                };
+                face_down = face_down
+                    .checked_mul(2)
+                    .ok_or(StartStateError::TooManyCards)?
+                    | card;
+                board_len += 1;
+            }
+
+            return Ok(Self {
+                face_down,
+                board_len: Some(board_len),
+            });
+        };
+
+        Ok(Self {
+            face_down,
+            board_len: None,
+        })
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when parsing or validating a state of the cards.
+    #[derive(Debug)]
+    pub enum StartStateError {
+        /// The state is not a valid number.
+        ParseIntError(err: ParseIntError) {
+            cause(err)
+            display("{}", err)
+            from()
+        }
+
+        /// A letter on the board is neither `U` nor `D`.
+        InvalidLetter(letter: char) {
+            display("expected `U` or `D`, found `{}`", letter)
+        }
+
+        /// The board has more cards than can be played with.
+        TooManyCards {
+            display("the board has too many cards")
+        }
+
+        /// The board doesn't have one letter per card.
+        WrongNumberOfCards(board_len: usize, number_of_cards: u32) {
+            display("the board has {} cards, but there are {} cards", board_len, number_of_cards)
+        }
+
+        /// The number has more binary digits than there are cards.
+        TooLarge(face_down: u64, number_of_cards: u32) {
+            display("the state {} doesn't fit in {} cards", face_down, number_of_cards)
+        }
+    }
 }
 
 /// A validated number of cards parameter.
+#[derive(Clone, Copy, Debug)]
 pub struct NumberOfCards {
     /// The number of cards to play with.
     number_of_cards: u8,
@@ -108,6 +1104,15 @@ impl TryFrom<u8> for NumberOfCards {
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for NumberOfCards {
+    /// Serializes the number of cards only,
+    /// since the number of states is derived from it.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.number_of_cards)
+    }
+}
+
 impl FromStr for NumberOfCards {
     type Err = NumberOfCardsError;
 
@@ -137,16 +1142,22 @@ quick_error! {
 
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
+    /// Returns the parameters of the command line without any options,
+    /// to be changed with the struct update syntax.
+    fn default_parameters() -> Parameters {
+        Parameters::builder().build().unwrap()
+    }
+
     #[test]
     fn solve() {
-        let solution = card(Parameters {
-            number_of_cards: 4.try_into().unwrap(),
-        });
+        let solution = card(default_parameters()).unwrap();
 
         assert_eq!(
-            solution,
+            solution.flips,
             vec![1, 2, 1, 3, 1, 2, 1, 4, 1, 2, 1, 3, 1, 2, 1].into_boxed_slice()
         );
     }
@@ -156,11 +1167,13 @@ mod tests {
         let number_of_cards = 4;
         let solution = card(Parameters {
             number_of_cards: number_of_cards.try_into().unwrap(),
-        });
+            ..default_parameters()
+        })
+        .unwrap();
 
         for card_state in 0..(1u64 << number_of_cards) {
             assert!(
-                check_state(&solution, card_state),
+                check_state(&solution.flips, card_state),
                 "the solution doesn't verify for card state {:01$b}",
                 card_state,
                 number_of_cards as usize
@@ -168,6 +1181,298 @@ mod tests {
         }
     }
 
+    #[test]
+    fn closed_form_matches_recurrence() {
+        for number_of_cards in 0..=16u8 {
+            let recurrence = card(Parameters {
+                number_of_cards: number_of_cards.try_into().unwrap(),
+                ..default_parameters()
+            })
+            .unwrap();
+            let closed_form = card(Parameters {
+                number_of_cards: number_of_cards.try_into().unwrap(),
+                method: Method::ClosedForm,
+                ..default_parameters()
+            })
+            .unwrap();
+
+            assert_eq!(
+                recurrence.flips, closed_form.flips,
+                "the methods disagree for {} cards",
+                number_of_cards
+            );
+        }
+    }
+
+    #[test]
+    fn flips_until_face_down() {
+        let number_of_cards = 4.try_into().unwrap();
+        let flips = card_recurrence(&number_of_cards);
+        for start in 0..16 {
+            // Play the flips until all the cards are face down.
+            let mut card_state = start;
+            let mut played = 0;
+            while card_state != 0b1111 {
+                card_state ^= 1 << (flips[played] - 1);
+                played += 1;
+            }
+
+            assert_eq!(
+                super::flips_until_face_down(start, 16),
+                played as u64,
+                "wrong number of flips from {:04b}",
+                start
+            );
+        }
+    }
+
+    #[test]
+    fn simulation() {
+        let parameters = Parameters::builder().build().unwrap();
+        let simulation = simulate(
+            &parameters,
+            NonZeroUsize::new(10_000).unwrap(),
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        assert_eq!(simulation.samples, 10_000);
+        assert_eq!(simulation.most_flips, 15);
+        assert!((simulation.mean_flips() - simulation.exact_mean_flips()).abs() < 0.5);
+    }
+
+    #[test]
+    fn round_trip() {
+        let number_of_cards = 3;
+        let solution = card(Parameters {
+            number_of_cards: number_of_cards.try_into().unwrap(),
+            round_trip: true,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(solution.flips.len(), 2 * ((1 << number_of_cards) - 1));
+
+        // Play the flips from a given starting position
+        // and count how many times each state is visited.
+        let mut card_state = 0u64;
+        let mut visits = vec![0; 1 << number_of_cards];
+        visits[card_state as usize] += 1;
+        for &card_number in solution.flips.iter() {
+            card_state ^= 1 << (card_number - 1);
+            visits[card_state as usize] += 1;
+        }
+
+        // The cards are back in their starting position.
+        assert_eq!(card_state, 0);
+
+        // Every state is visited once on the way there and once on the way back,
+        // except the turnaround state, which is visited only once.
+        let turnaround_state = 1 << (number_of_cards - 1);
+        for (state, &count) in visits.iter().enumerate() {
+            let expected_count = if state == turnaround_state { 1 } else { 2 };
+            assert_eq!(count, expected_count, "state {:03b}", state);
+        }
+    }
+
+    #[test]
+    fn grouped() {
+        let group = NonZeroUsize::new(4);
+        let mut cards = vec![false; 8];
+
+        assert_eq!(render_cards(&cards, group), "UUUU|UUUU");
+
+        cards[0] = true;
+        cards[5] = true;
+        assert_eq!(render_cards(&cards, group), "DUUU|UDUU");
+
+        let solution = card(Parameters {
+            number_of_cards: 8.try_into().unwrap(),
+            group,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(solution.flips.len(), 255);
+        assert!(solution
+            .to_string()
+            .starts_with("[1, 2, 1, 3 | 1, 2, 1, 4 | 1, 2, 1, 3 | 1, 2, 1, 5 | "));
+    }
+
+    #[test]
+    fn narrated() {
+        let solution = card(Parameters {
+            number_of_cards: 2.try_into().unwrap(),
+            narrate: true,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(
+            solution.to_string(),
+            "start: UU\nflip 1: DU\nflip 2: DD\nflip 1: UD"
+        );
+    }
+
+    #[test]
+    fn flip_histogram() {
+        let solution = card(Parameters {
+            flip_histogram: true,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        let expected: BTreeMap<_, _> = vec![(1, 8), (2, 4), (3, 2), (4, 1)].into_iter().collect();
+        assert_eq!(solution.flip_histogram().counts(), &expected);
+        assert_eq!(
+            solution.to_string(),
+            "card 1: 8 flips\ncard 2: 4 flips\ncard 3: 2 flips\ncard 4: 1 flip"
+        );
+    }
+
+    #[test]
+    fn parse_start_state() {
+        let expected = vec![true, false, true, false];
+        for s in &["0b1010", "10", "0xa", "DUDU", "du|du"] {
+            let start: StartState = s.parse().unwrap();
+            assert_eq!(start.cards(4).unwrap(), expected, "{}", s);
+        }
+
+        let start: StartState = "0b10000".parse().unwrap();
+        assert!(matches!(
+            start.cards(4),
+            Err(StartStateError::TooLarge(16, 4))
+        ));
+
+        let start: StartState = "DUD".parse().unwrap();
+        assert!(matches!(
+            start.cards(4),
+            Err(StartStateError::WrongNumberOfCards(3, 4))
+        ));
+
+        assert!(matches!(
+            "DUXU".parse::<StartState>(),
+            Err(StartStateError::InvalidLetter('X'))
+        ));
+    }
+
+    #[test]
+    fn narrated_from_start_state() {
+        let solution = card(Parameters {
+            number_of_cards: 2.try_into().unwrap(),
+            narrate: true,
+            start: Some("UD".parse().unwrap()),
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(
+            solution.to_string(),
+            "start: UD\nflip 1: DD\nflip 2: DU\nflip 1: UU"
+        );
+    }
+
+    #[test]
+    fn card_solution() {
+        let flips: Vec<_> = super::iter_flips(&Parameters::builder().build().unwrap()).collect();
+        assert_eq!(
+            CardSolution::new(&flips).to_string(),
+            "[1, 2, 1, 3, 1, 2, 1, 4, 1, 2, 1, 3, 1, 2, 1]"
+        );
+    }
+
+    #[test]
+    fn write_flips() {
+        let parameters = Parameters::builder()
+            .number_of_cards(4.try_into().unwrap())
+            .group(NonZeroUsize::new(4).unwrap())
+            .build()
+            .unwrap();
+        let mut written = vec![];
+        super::write_flips(&parameters, &mut written).unwrap();
+
+        let expected = format!("{}\n", card(parameters).unwrap());
+        assert_eq!(String::from_utf8(written).unwrap(), expected);
+    }
+
+    #[test]
+    fn iter_flips() {
+        for &round_trip in &[false, true] {
+            let parameters = || {
+                Parameters::builder()
+                    .number_of_cards(6.try_into().unwrap())
+                    .round_trip(round_trip)
+                    .build()
+                    .unwrap()
+            };
+            let flips: Vec<_> = super::iter_flips(&parameters()).collect();
+            assert_eq!(&flips[..], &card(parameters()).unwrap().flips[..]);
+        }
+    }
+
+    #[test]
+    fn builder() {
+        let parameters = Parameters::builder()
+            .number_of_cards(3.try_into().unwrap())
+            .round_trip(true)
+            .build()
+            .unwrap();
+        assert_eq!(card(parameters).unwrap().flips.len(), 14);
+
+        assert_eq!(
+            Parameters::builder()
+                .narrate(true)
+                .flip_histogram(true)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("flip_histogram", "narrate")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .start("UD".parse().unwrap())
+                .build()
+                .unwrap_err(),
+            BuildError::MissingRequirement("start", "narrate")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .output(OutputFormat::Rle)
+                .flip_histogram(true)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("output", "flip_histogram")
+        );
+    }
+
+    #[test]
+    fn rle() {
+        let solution = card(Parameters {
+            number_of_cards: 6.try_into().unwrap(),
+            output: OutputFormat::Rle,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        let encoded = solution.to_string();
+        assert!(encoded.starts_with("S(6) where S(2) = 1, 2, 1; S(3) = S(2), 3, S(2);"));
+        assert!(encoded.len() < format!("{:?}", solution.flips).len());
+        assert_eq!(decode_rle(&encoded).unwrap(), solution.flips);
+
+        let round_trip = [1, 2, 1, 1, 2, 1, 3];
+        assert_eq!(
+            encode_rle(&round_trip),
+            "S(2), S(2), 3 where S(2) = 1, 2, 1"
+        );
+
+        assert!(matches!(
+            decode_rle("S(2) where S(2) = S(2)"),
+            Err(RleError::RecursiveBlock(2))
+        ));
+        assert!(matches!(
+            decode_rle("S(3)"),
+            Err(RleError::UndefinedBlock(3))
+        ));
+    }
+
     fn check_state(solution: &[u8], mut card_state: u64) -> bool {
         let mut solution_iter = solution.iter().cloned();
         loop {
@@ -0,0 +1,96 @@
+//! The JSON catalog of the puzzles that `list` prints with `--format json`.
+//!
+//! Besides the information about each puzzle,
+//! the catalog describes the options of the puzzle's subcommand,
+//! taken from the command line's definition,
+//! so that a user interface can build a form for each puzzle.
+
+use clap::{Arg, ArgAction, CommandFactory};
+
+use serde_json::{json, Value};
+
+use matt_parkers_maths_puzzles::puzzle_info;
+
+use crate::Options;
+
+/// Builds the catalog of the puzzles.
+pub fn catalog() -> Value {
+    let command = Options::command();
+    let puzzles = puzzle_info::puzzles()
+        .iter()
+        .map(|puzzle| {
+            let subcommand = command
+                .find_subcommand(puzzle.name)
+                .expect("every puzzle has a subcommand");
+            let parameters: Vec<_> = subcommand
+                .get_arguments()
+                .filter(|arg| {
+                    !arg.is_hide_set()
+                        && !matches!(
+                            arg.get_action(),
+                            ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong
+                        )
+                })
+                .map(parameter)
+                .collect();
+
+            json!({
+                "name": puzzle.name,
+                "url": puzzle.url,
+                "description": puzzle.description,
+                "answer": puzzle.answer,
+                "parameters": parameters,
+            })
+        })
+        .collect();
+
+    Value::Array(puzzles)
+}
+
+/// Describes an option of a puzzle's subcommand.
+///
+/// The name is the one of the parameter in the JSON output and in `batch`.
+fn parameter(arg: &Arg) -> Value {
+    // A flag is only given or not, which is `true` or `false` in the JSON output.
+    let flag = matches!(arg.get_action(), ArgAction::SetTrue);
+    if flag {
+        return json!({
+            "name": arg.get_id().as_str(),
+            "long": arg.get_long(),
+            "short": arg.get_short(),
+            "help": arg.get_help().map(ToString::to_string),
+            "flag": true,
+            "multiple": false,
+            "required": false,
+            "default": false,
+            "values": [],
+        });
+    }
+
+    let default = match arg.get_default_values() {
+        [] => Value::Null,
+        [value] => json!(value.to_string_lossy()),
+        values => json!(values
+            .iter()
+            .map(|value| value.to_string_lossy())
+            .collect::<Vec<_>>()),
+    };
+    let values: Vec<_> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_string())
+        .collect();
+
+    json!({
+        "name": arg.get_id().as_str(),
+        "long": arg.get_long(),
+        "short": arg.get_short(),
+        "help": arg.get_help().map(ToString::to_string),
+        "flag": false,
+        "multiple": matches!(arg.get_action(), ArgAction::Append),
+        "required": arg.is_required_set(),
+        "default": default,
+        "values": values,
+    })
+}
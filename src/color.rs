@@ -0,0 +1,54 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A color of the text in a terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)] // Each puzzle only uses some of the colors.
+pub(crate) enum Color {
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    /// Returns the parameter of the ANSI escape sequence that selects the color.
+    fn code(self) -> u8 {
+        match self {
+            Color::Dim => 2,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+/// Wraps `text` so that it is displayed in `color`,
+/// or as is if `color` is `None`.
+pub(crate) fn paint<T: Display>(color: Option<Color>, text: T) -> Painted<T> {
+    Painted { color, text }
+}
+
+/// Text displayed in a color, returned by `paint`.
+#[derive(Debug)]
+pub(crate) struct Painted<T> {
+    /// The color of the text, if any.
+    color: Option<Color>,
+
+    /// The text to display.
+    text: T,
+}
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self.color {
+            Some(color) => write!(fmt, "\x1b[{}m{}\x1b[0m", color.code(), self.text),
+            None => write!(fmt, "{}", self.text),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Default options read from a configuration file.
+//!
+//! The configuration file is written in TOML.
+//! The keys at the top level set the global options,
+//! and the keys in the `[spinning-table]`, `[scrabble]` and `[card]` tables
+//! set the options of these puzzles.
+//! Each key is the long name of an option, without the dashes:
+//!
+//! ```toml
+//! format = "json"
+//!
+//! [scrabble]
+//! hand-size = 8
+//! target-score = 50
+//!
+//! [spinning-table]
+//! number-of-seats = 9
+//! names = ["Alice", "Bob", "Carol"]
+//! ```
+//!
+//! `true` gives a flag, `false` leaves it out,
+//! and an array gives an option once for each element.
+//! The options given on the command line take precedence,
+//! and the options that conflict with them are left out.
+//!
+//! The `MPMP_CONFIG` environment variable gives another file to read by default,
+//! or no file at all if it is empty.
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{error::ErrorKind, parser::ValueSource, Arg, ArgMatches, Command, CommandFactory};
+
+use crate::Options;
+
+/// The subcommands whose options can be set in the configuration file.
+const PUZZLES: &[&str] = &["spinning-table", "scrabble", "card"];
+
+/// The environment variable that gives the configuration file to read by default,
+/// or no configuration file if it is empty.
+const CONFIG_VAR: &str = "MPMP_CONFIG";
+
+/// Returns the path of the configuration file that is read by default,
+/// `mpmp/config.toml` in the user's configuration directory.
+fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|directory| directory.join("mpmp").join("config.toml"))
+}
+
+/// Adds the options set in the configuration file to `args`,
+/// except the ones that `args` already gives.
+///
+/// The configuration file is the one given with `--config`,
+/// then the one given by `MPMP_CONFIG`,
+/// or the default one if it exists.
+pub fn apply(mut args: Vec<OsString>) -> clap::error::Result<Vec<OsString>> {
+    let command = Options::command();
+    let matches = command.clone().try_get_matches_from(&args)?;
+    let path = match (
+        matches.get_one::<PathBuf>("config"),
+        env::var_os(CONFIG_VAR),
+    ) {
+        (Some(path), _) => path.clone(),
+        (None, Some(path)) if path.is_empty() => return Ok(args),
+        (None, Some(path)) => PathBuf::from(path),
+        (None, None) => match default_path() {
+            Some(path) if path.is_file() => path,
+            _ => return Ok(args),
+        },
+    };
+
+    let contents = fs::read_to_string(&path).map_err(|err| {
+        clap::Error::raw(
+            ErrorKind::Io,
+            format!("could not read `{}`: {}\n", path.display(), err),
+        )
+    })?;
+    let table: toml::Table = toml::from_str(&contents).map_err(|err| {
+        clap::Error::raw(
+            ErrorKind::InvalidValue,
+            format!("could not parse `{}`: {}\n", path.display(), err),
+        )
+    })?;
+
+    let mut global_args = vec![];
+    for (key, value) in &table {
+        match value {
+            toml::Value::Table(puzzle_table) => {
+                if !PUZZLES.contains(&key.as_str()) {
+                    return Err(invalid_key(&path, key));
+                }
+
+                // The puzzle's options only apply when that puzzle is solved.
+                // The puzzles take no positional arguments,
+                // so their options can go at the end.
+                if let Some(puzzle_matches) = matches.subcommand_matches(key) {
+                    let puzzle_command = command
+                        .find_subcommand(key)
+                        .expect("the puzzles are subcommands");
+                    for (key, value) in puzzle_table {
+                        args.extend(to_args(&path, puzzle_command, puzzle_matches, key, value)?);
+                    }
+                }
+            }
+            value => global_args.extend(to_args(&path, &command, &matches, key, value)?),
+        }
+    }
+
+    // The global options go before the subcommand.
+    args.splice(1..1, global_args);
+    Ok(args)
+}
+
+/// Converts an option of `command` set in the configuration file to arguments,
+/// or to nothing if the command line already gives it
+/// or gives an option that conflicts with it.
+fn to_args(
+    path: &Path,
+    command: &Command,
+    matches: &ArgMatches,
+    key: &str,
+    value: &toml::Value,
+) -> clap::error::Result<Vec<OsString>> {
+    let arg = command
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(key))
+        .ok_or_else(|| invalid_key(path, key))?;
+    let given =
+        |arg: &Arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine);
+    // The conflicts are only declared on one of the two options.
+    let conflicts = |arg: &Arg, other: &Arg| {
+        command
+            .get_arg_conflicts_with(arg)
+            .iter()
+            .any(|conflict| conflict.get_id() == other.get_id())
+    };
+    if given(arg)
+        || command
+            .get_arguments()
+            .any(|other| given(other) && (conflicts(arg, other) || conflicts(other, arg)))
+    {
+        return Ok(vec![]);
+    }
+
+    let values = match value {
+        toml::Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let arg = match value {
+                toml::Value::Boolean(true) => format!("--{}", key),
+                toml::Value::Boolean(false) => return None,
+                toml::Value::String(value) => format!("--{}={}", key, value),
+                toml::Value::Integer(value) => format!("--{}={}", key, value),
+                toml::Value::Float(value) => format!("--{}={}", key, value),
+                _ => return Some(Err(invalid_key(path, key))),
+            };
+            Some(Ok(arg.into()))
+        })
+        .collect()
+}
+
+/// Returns the error for a key of the configuration file that can't be used.
+fn invalid_key(path: &Path, key: &str) -> clap::Error {
+    clap::Error::raw(
+        ErrorKind::InvalidValue,
+        format!("`{}` can't be set in `{}`\n", key, path.display()),
+    )
+}
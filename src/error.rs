@@ -0,0 +1,163 @@
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+use crate::builder::BuildError;
+
+#[cfg(feature = "puzzle-card")]
+use crate::card::StartStateError;
+
+#[cfg(feature = "puzzle-scrabble")]
+use crate::{
+    scrabble::{ParameterError, TilesError},
+    wordlist::WordlistError,
+};
+
+#[cfg(feature = "puzzle-spinning-table")]
+use crate::spinning_table::{CheckpointError, FixError};
+
+/// An error that can be returned when solving any of the puzzles.
+///
+/// The variants for each puzzle only exist when the puzzle's feature is enabled,
+/// which is why this error is not defined with `quick_error!`.
+///
+/// Each puzzle's module keeps its own errors, such as `StartStateError`,
+/// and this error wraps them:
+/// they are also the `FromStr` errors of the options that the command line parses,
+/// which can fail before there is anything to solve,
+/// and they stay matchable by callers that handle a single puzzle.
+#[derive(Debug)]
+pub enum Error {
+    /// The investors of the spinning table puzzle cannot be pinned or named as requested.
+    #[cfg(feature = "puzzle-spinning-table")]
+    Fix(FixError),
+
+    /// The spinning table search could not be saved to or resumed from a checkpoint.
+    #[cfg(feature = "puzzle-spinning-table")]
+    Checkpoint(CheckpointError),
+
+    /// The tile distribution of the Scrabble® puzzle could not be loaded.
+    #[cfg(feature = "puzzle-scrabble")]
+    Tiles(TilesError),
+
+    /// The word list of the Scrabble® puzzle could not be loaded.
+    #[cfg(feature = "puzzle-scrabble")]
+    Wordlist(WordlistError),
+
+    /// The parameters of the Scrabble® puzzle are not consistent with the tile distribution.
+    #[cfg(feature = "puzzle-scrabble")]
+    Parameters(ParameterError),
+
+    /// The hands in the list output take up more than `--max-list-bytes`.
+    #[cfg(feature = "puzzle-scrabble")]
+    ListTooLarge(usize),
+
+    /// The starting state of the card puzzle doesn't fit the cards.
+    #[cfg(feature = "puzzle-card")]
+    StartState(StartStateError),
+
+    /// The options given to a parameters builder cannot be used together.
+    Build(BuildError),
+
+    /// The solutions could not be written.
+    Io(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "puzzle-spinning-table")]
+            Self::Fix(err) => write!(fmt, "{}", err),
+
+            #[cfg(feature = "puzzle-spinning-table")]
+            Self::Checkpoint(err) => write!(fmt, "{}", err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Tiles(err) => write!(fmt, "{}", err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Wordlist(err) => write!(fmt, "{}", err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Parameters(err) => write!(fmt, "{}", err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::ListTooLarge(max_list_bytes) => write!(
+                fmt,
+                "the list of hands takes up more than {} bytes; use `--output count` to count the hands instead",
+                max_list_bytes
+            ),
+
+            #[cfg(feature = "puzzle-card")]
+            Self::StartState(err) => write!(fmt, "{}", err),
+
+            Self::Build(err) => write!(fmt, "{}", err),
+
+            Self::Io(err) => write!(fmt, "could not write the solutions: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "puzzle-spinning-table")]
+            Self::Fix(err) => Some(err),
+
+            #[cfg(feature = "puzzle-spinning-table")]
+            Self::Checkpoint(err) => Some(err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Tiles(err) => Some(err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Wordlist(err) => Some(err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::Parameters(err) => Some(err),
+
+            #[cfg(feature = "puzzle-scrabble")]
+            Self::ListTooLarge(_) => None,
+
+            #[cfg(feature = "puzzle-card")]
+            Self::StartState(err) => Some(err),
+
+            Self::Build(err) => Some(err),
+
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+/// Implements `From` for the variants of `Error` that wrap another error.
+macro_rules! impl_from {
+    ($($(#[$attr:meta])* $variant:ident($err:ty),)*) => {
+        $(
+            $(#[$attr])*
+            impl From<$err> for Error {
+                fn from(err: $err) -> Self {
+                    Self::$variant(err)
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    #[cfg(feature = "puzzle-spinning-table")]
+    Fix(FixError),
+    #[cfg(feature = "puzzle-spinning-table")]
+    Checkpoint(CheckpointError),
+    #[cfg(feature = "puzzle-scrabble")]
+    Tiles(TilesError),
+    #[cfg(feature = "puzzle-scrabble")]
+    Wordlist(WordlistError),
+    #[cfg(feature = "puzzle-scrabble")]
+    Parameters(ParameterError),
+    #[cfg(feature = "puzzle-card")]
+    StartState(StartStateError),
+    Build(BuildError),
+    Io(io::Error),
+}
@@ -0,0 +1,176 @@
+//! The `--estimate` option, which reports how big a search would be
+//! instead of running it.
+//!
+//! The spinning table and Scrabble® searches report the size of their search space
+//! through their progress counters as soon as they start,
+//! so they are run for a short time to read it
+//! and to measure how fast they go through it.
+//! The card puzzle's solution always has 2^n − 1 flips,
+//! so its time is measured on a smaller number of cards
+//! when the actual one would take too long.
+
+use std::{
+    convert::TryInto,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use serde_json::json;
+
+use matt_parkers_maths_puzzles::{
+    card::{self, Card},
+    scrabble::Scrabble,
+    spinning_table::SpinningTable,
+    Error, Solver,
+};
+
+use crate::{with_time_limit, write_json, Context, Puzzle, Verbosity};
+
+/// How long the searches are run to measure their speed.
+const CALIBRATION_TIME: Duration = Duration::from_millis(200);
+
+/// The largest number of cards whose solution is computed to measure the time.
+const CALIBRATION_CARDS: u8 = 20;
+
+/// The size of a search and how long it should take.
+#[derive(Debug, Serialize)]
+struct Estimate {
+    /// The number of steps of the search,
+    /// or `u64::MAX` if there are more than that.
+    search_space: u64,
+
+    /// What a step of the search is.
+    unit: &'static str,
+
+    /// The predicted time to run the whole search, in seconds,
+    /// or `None` if it couldn't be predicted.
+    predicted_seconds: Option<f64>,
+}
+
+/// Estimates the size of the search for `puzzle` and how long it would take,
+/// writing the estimate to `out`.
+///
+/// `Options::validate` only lets the puzzles through,
+/// whose parameters share `context`.
+pub fn estimate(
+    puzzle: Puzzle,
+    context: &Context,
+    json: bool,
+    verbosity: Verbosity,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    let (name, echoed, estimate) = match puzzle {
+        Puzzle::SpinningTable(parameters) => (
+            SpinningTable::NAME,
+            json!(parameters),
+            calibrate::<SpinningTable>(parameters, context, "arrangements")?,
+        ),
+        Puzzle::Scrabble(parameters) => (
+            Scrabble::NAME,
+            json!(parameters),
+            calibrate::<Scrabble>(parameters, context, "combinations of tile values")?,
+        ),
+        Puzzle::Card(parameters) => (Card::NAME, json!(parameters), estimate_card(parameters)?),
+        _ => unreachable!("`--estimate` is only accepted for the puzzles"),
+    };
+
+    if json {
+        return write_json(out, name, echoed, json!(estimate));
+    }
+
+    let more_than = if estimate.search_space == u64::MAX {
+        "more than "
+    } else {
+        ""
+    };
+    let predicted_time = estimate.predicted_seconds.map_or_else(
+        || String::from("unknown"),
+        |seconds| format!("{:?}", Duration::from_secs_f64(seconds)),
+    );
+    if verbosity == Verbosity::Quiet {
+        writeln!(
+            out,
+            "{}{} {}",
+            more_than, estimate.search_space, estimate.unit
+        )?;
+        writeln!(out, "{}", predicted_time)?;
+    } else {
+        writeln!(
+            out,
+            "search space: {}{} {}",
+            more_than, estimate.search_space, estimate.unit
+        )?;
+        writeln!(out, "predicted time: {}", predicted_time)?;
+    }
+
+    Ok(())
+}
+
+/// Runs a search for a short time to read the size of its search space
+/// and to measure how fast it goes through it.
+///
+/// The search is stopped through the interrupt of `context`
+/// and reports its progress to the counters of `context`,
+/// which `parameters` share.
+fn calibrate<S: Solver>(
+    parameters: S::Parameters,
+    context: &Context,
+    unit: &'static str,
+) -> Result<Estimate, Error> {
+    let progress = &context.progress;
+    let start = Instant::now();
+    let result = with_time_limit(Some(CALIBRATION_TIME), &context.interrupt, || {
+        S::solve(parameters)
+    });
+    let elapsed = start.elapsed();
+    let stopped = context.interrupt.is_triggered();
+    result?;
+
+    let search_space = progress.length();
+    let predicted_seconds = if !stopped {
+        Some(elapsed.as_secs_f64())
+    } else if progress.position() == 0 {
+        None
+    } else {
+        let ratio = search_space as f64 / progress.position() as f64;
+        Some(elapsed.as_secs_f64() * ratio).filter(|seconds| seconds.is_finite())
+    };
+
+    Ok(Estimate {
+        search_space,
+        unit,
+        predicted_seconds,
+    })
+}
+
+/// Counts the flips of the card puzzle's solution
+/// and measures how long it takes to compute,
+/// scaling the time of a smaller puzzle if there are too many cards.
+fn estimate_card(parameters: card::Parameters) -> Result<Estimate, Error> {
+    let flips = parameters.number_of_flips();
+    let calibration = if parameters.number_of_cards() <= CALIBRATION_CARDS {
+        parameters
+    } else {
+        card::Parameters::builder()
+            .number_of_cards(
+                CALIBRATION_CARDS
+                    .try_into()
+                    .expect("the calibration uses a valid number of cards"),
+            )
+            .build()?
+    };
+    let calibration_flips = calibration.number_of_flips();
+
+    let start = Instant::now();
+    Card::solve(calibration)?;
+    let elapsed = start.elapsed();
+
+    let ratio = flips as f64 / calibration_flips as f64;
+    Ok(Estimate {
+        search_space: flips as u64,
+        unit: "flips",
+        predicted_seconds: Some(elapsed.as_secs_f64() * ratio),
+    })
+}
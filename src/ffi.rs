@@ -0,0 +1,302 @@
+//! A C interface to the solvers, for embedding them in programs written in other languages.
+//!
+//! The library is built as a shared library (`cdylib`) with the `ffi` feature.
+//! The declarations are in `include/mpmp.h`.
+//!
+//! Each puzzle has a function that takes the main parameters of the puzzle in a plain struct
+//! and stores the solution in `*solution` as a JSON string,
+//! shaped like the `solution` of the JSON output:
+//!
+//! ```c
+//! struct MpmpScrabbleParameters parameters = { .hand_size = 7, .target_score = 46 };
+//! char *solution;
+//! if (mpmp_solve_scrabble(parameters, &solution) == MPMP_OK) {
+//!     puts(solution); // {"Count":138}
+//! }
+//! mpmp_free_string(solution);
+//! ```
+//!
+//! When the puzzle cannot be solved,
+//! the function returns `MPMP_ERROR` and stores the error message instead.
+//! Either way, the string belongs to the caller,
+//! who frees it with `mpmp_free_string`.
+
+use std::{
+    error::Error,
+    ffi::CString,
+    os::raw::{c_char, c_int},
+    panic::{self, AssertUnwindSafe},
+};
+
+use serde::Serialize;
+
+#[cfg(feature = "puzzle-card")]
+use std::convert::TryInto;
+
+#[cfg(feature = "puzzle-spinning-table")]
+use std::num::NonZeroUsize;
+
+#[cfg(feature = "puzzle-card")]
+use crate::card;
+
+#[cfg(feature = "puzzle-scrabble")]
+use crate::scrabble;
+
+#[cfg(feature = "puzzle-spinning-table")]
+use crate::spinning_table;
+
+/// The solution was stored.
+pub const MPMP_OK: c_int = 0;
+
+/// The error message was stored,
+/// or nothing was stored because `solution` is null.
+pub const MPMP_ERROR: c_int = 1;
+
+/// The parameters of the spinning table puzzle.
+#[cfg(feature = "puzzle-spinning-table")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct MpmpSpinningTableParameters {
+    /// The number of seats at the table.
+    pub number_of_seats: u32,
+
+    /// The largest number of solutions to find, or 0 to find them all.
+    pub max_solutions: u32,
+
+    /// Whether redundant solutions are included in the result.
+    pub include_redundant_solutions: bool,
+}
+
+/// The parameters of the Scrabble® puzzle.
+#[cfg(feature = "puzzle-scrabble")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct MpmpScrabbleParameters {
+    /// The number of tiles in a hand.
+    pub hand_size: u32,
+
+    /// The score of the hands to count.
+    pub target_score: u32,
+}
+
+/// The parameters of the card puzzle.
+#[cfg(feature = "puzzle-card")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct MpmpCardParameters {
+    /// The number of cards to play with.
+    pub number_of_cards: u8,
+}
+
+/// Solves the spinning table puzzle.
+///
+/// # Safety
+///
+/// `solution` must be null or point to memory where a pointer can be written.
+#[cfg(feature = "puzzle-spinning-table")]
+#[no_mangle]
+pub unsafe extern "C" fn mpmp_solve_spinning_table(
+    parameters: MpmpSpinningTableParameters,
+    solution: *mut *mut c_char,
+) -> c_int {
+    store(solution, || {
+        let number_of_seats = NonZeroUsize::new(parameters.number_of_seats as usize)
+            .ok_or("the number of seats must be at least 1")?;
+        let mut builder = spinning_table::Parameters::builder()
+            .number_of_seats(number_of_seats)
+            .include_redundant_solutions(parameters.include_redundant_solutions);
+        if let Some(max_solutions) = NonZeroUsize::new(parameters.max_solutions as usize) {
+            builder = builder.max_solutions(max_solutions);
+        }
+
+        Ok(spinning_table::spinning_table(builder.build()?)?)
+    })
+}
+
+/// Counts the hands of the Scrabble® puzzle.
+///
+/// # Safety
+///
+/// `solution` must be null or point to memory where a pointer can be written.
+#[cfg(feature = "puzzle-scrabble")]
+#[no_mangle]
+pub unsafe extern "C" fn mpmp_solve_scrabble(
+    parameters: MpmpScrabbleParameters,
+    solution: *mut *mut c_char,
+) -> c_int {
+    store(solution, || {
+        let parameters = scrabble::Parameters::builder()
+            .hand_size(parameters.hand_size)
+            .target_score(parameters.target_score)
+            .build()?;
+
+        Ok(scrabble::scrabble(parameters)?)
+    })
+}
+
+/// Finds the sequence of flips of the card puzzle.
+///
+/// # Safety
+///
+/// `solution` must be null or point to memory where a pointer can be written.
+#[cfg(feature = "puzzle-card")]
+#[no_mangle]
+pub unsafe extern "C" fn mpmp_solve_card(
+    parameters: MpmpCardParameters,
+    solution: *mut *mut c_char,
+) -> c_int {
+    store(solution, || {
+        let parameters = card::Parameters::builder()
+            .number_of_cards(parameters.number_of_cards.try_into()?)
+            .build()?;
+
+        Ok(card::card(parameters)?)
+    })
+}
+
+/// Frees a string stored by one of the `mpmp_solve_` functions.
+///
+/// # Safety
+///
+/// `string` must be null or a string stored by one of the `mpmp_solve_` functions
+/// that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn mpmp_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Calls `solve` and stores its solution as JSON, or its error message, in `*solution`.
+///
+/// A panic in the solver is reported as an error,
+/// since it must not unwind into the caller.
+#[cfg_attr(
+    not(any(
+        feature = "puzzle-spinning-table",
+        feature = "puzzle-scrabble",
+        feature = "puzzle-card"
+    )),
+    allow(dead_code)
+)]
+unsafe fn store<T>(
+    solution: *mut *mut c_char,
+    solve: impl FnOnce() -> Result<T, Box<dyn Error>>,
+) -> c_int
+where
+    T: Serialize,
+{
+    if solution.is_null() {
+        return MPMP_ERROR;
+    }
+
+    let (status, text) = match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(Ok(output)) => match serde_json::to_string(&output) {
+            Ok(json) => (MPMP_OK, json),
+            Err(err) => (MPMP_ERROR, err.to_string()),
+        },
+        Ok(Err(err)) => (MPMP_ERROR, err.to_string()),
+        Err(_) => (MPMP_ERROR, "the solver panicked".to_owned()),
+    };
+
+    // The JSON escapes its NUL bytes, but an error message could contain one.
+    let text = CString::new(text.replace('\0', "")).expect("the NUL bytes were removed");
+    *solution = text.into_raw();
+    status
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "puzzle-spinning-table",
+        feature = "puzzle-scrabble",
+        feature = "puzzle-card"
+    )
+))]
+mod tests {
+    use std::{ffi::CStr, os::raw::c_char, ptr};
+
+    use super::*;
+
+    /// Takes the string stored by a `mpmp_solve_` function.
+    fn take(solution: *mut c_char) -> String {
+        let text = unsafe { CStr::from_ptr(solution) }
+            .to_str()
+            .unwrap()
+            .to_owned();
+        unsafe { mpmp_free_string(solution) };
+        text
+    }
+
+    #[test]
+    #[cfg(feature = "puzzle-scrabble")]
+    fn scrabble() {
+        let mut solution = ptr::null_mut();
+        let parameters = MpmpScrabbleParameters {
+            hand_size: 7,
+            target_score: 46,
+        };
+        assert_eq!(
+            unsafe { mpmp_solve_scrabble(parameters, &mut solution) },
+            MPMP_OK
+        );
+        assert_eq!(take(solution), r#"{"Count":138}"#);
+
+        assert_eq!(
+            unsafe { mpmp_solve_scrabble(parameters, ptr::null_mut()) },
+            MPMP_ERROR
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "puzzle-spinning-table")]
+    fn spinning_table() {
+        let mut solution = ptr::null_mut();
+        let parameters = MpmpSpinningTableParameters {
+            number_of_seats: 5,
+            max_solutions: 0,
+            include_redundant_solutions: false,
+        };
+        assert_eq!(
+            unsafe { mpmp_solve_spinning_table(parameters, &mut solution) },
+            MPMP_OK
+        );
+        let json: serde_json::Value = serde_json::from_str(&take(solution)).unwrap();
+        assert_eq!(json["number_of_solutions"], 3);
+
+        let parameters = MpmpSpinningTableParameters {
+            number_of_seats: 0,
+            ..parameters
+        };
+        assert_eq!(
+            unsafe { mpmp_solve_spinning_table(parameters, &mut solution) },
+            MPMP_ERROR
+        );
+        assert_eq!(take(solution), "the number of seats must be at least 1");
+    }
+
+    #[test]
+    #[cfg(feature = "puzzle-card")]
+    fn card() {
+        let mut solution = ptr::null_mut();
+        let parameters = MpmpCardParameters { number_of_cards: 3 };
+        assert_eq!(
+            unsafe { mpmp_solve_card(parameters, &mut solution) },
+            MPMP_OK
+        );
+        let json: serde_json::Value = serde_json::from_str(&take(solution)).unwrap();
+        assert_eq!(json["flips"], serde_json::json!([1, 2, 1, 3, 1, 2, 1]));
+
+        let parameters = MpmpCardParameters {
+            number_of_cards: 200,
+        };
+        assert_eq!(
+            unsafe { mpmp_solve_card(parameters, &mut solution) },
+            MPMP_ERROR
+        );
+        assert_eq!(
+            take(solution),
+            "the number of flips required for 200 cards is too large!"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag that tells a search to stop and return what it found so far.
+///
+/// The clones of an `Interrupt` share the same flag,
+/// so the search can be stopped from another thread.
+/// The default flag is only set by `trigger`.
+#[derive(Clone, Debug, Default)]
+pub struct Interrupt {
+    flag: Arc<AtomicBool>,
+
+    /// The flag that this one was derived from, which also stops the search.
+    parent: Option<Box<Interrupt>>,
+}
+
+impl Interrupt {
+    /// Creates a flag that is only set by `trigger`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a flag that is set by its own `trigger` and by the `trigger` of `self`,
+    /// which isn't set when the new flag is triggered.
+    pub fn child(&self) -> Self {
+        Self {
+            flag: Arc::default(),
+            parent: Some(Box::new(self.clone())),
+        }
+    }
+
+    /// Sets the flag, which stops the searches that check it.
+    pub fn trigger(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    /// Determines whether the search should stop.
+    pub fn is_triggered(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.is_triggered())
+    }
+
+    /// Clears the flag, so that the next search runs to completion
+    /// unless the flag this one was derived from is set.
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Makes Ctrl-C set `interrupt` instead of terminating the program.
+///
+/// Pressing Ctrl-C a second time terminates the program.
+/// Only the first call has an effect,
+/// so the same flag should be given to every call.
+#[cfg(all(feature = "ctrlc", not(target_arch = "wasm32")))]
+pub fn handle_ctrl_c(interrupt: &Interrupt) -> Result<(), ctrlc::Error> {
+    let interrupt = interrupt.clone();
+    let result = ctrlc::set_handler(move || {
+        if interrupt.flag.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+    });
+
+    match result {
+        // The handler was set by an earlier call.
+        Err(ctrlc::Error::MultipleHandlers) => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interrupt;
+
+    #[test]
+    fn child() {
+        let parent = Interrupt::new();
+        let child = parent.child();
+
+        // Stopping the child leaves the parent and its other children running.
+        child.trigger();
+        assert!(child.is_triggered());
+        assert!(!parent.is_triggered());
+        assert!(!parent.child().is_triggered());
+
+        child.reset();
+        assert!(!child.is_triggered());
+
+        parent.trigger();
+        assert!(child.is_triggered());
+        assert!(child.clone().is_triggered());
+    }
+}
@@ -0,0 +1,110 @@
+//! Solutions to Matt Parker's Math Puzzles.
+//!
+//! Each puzzle has its own module with a solver function,
+//! the `Parameters` it takes and the `Output` it produces.
+//! The `Parameters` can be built with `Parameters::builder()`,
+//! or parsed from command-line arguments with `clap::Parser`
+//! when the `cli` feature is enabled.
+//!
+//! Each puzzle module is enabled by a Cargo feature
+//! (`puzzle-spinning-table`, `puzzle-scrabble` and `puzzle-card`),
+//! all of which are enabled by default.
+//! The `wasm` feature adds entry points for solving the puzzles in a web page,
+//! and the `ffi` feature adds a C interface to the solvers.
+
+#![warn(clippy::all)]
+
+use std::io;
+
+/// [Puzzle 1 - Spinning table](http://www.think-maths.co.uk/table-puzzle)
+#[cfg(feature = "puzzle-spinning-table")]
+pub mod spinning_table;
+
+/// [Puzzle 3 - Scrabble®](http://www.think-maths.co.uk/scrabble-puzzle)
+#[cfg(feature = "puzzle-scrabble")]
+pub mod scrabble;
+
+/// [Puzzle 4 - Card](http://www.think-maths.co.uk/card-puzzle)
+#[cfg(feature = "puzzle-card")]
+pub mod card;
+
+/// Metadata about the puzzles.
+pub mod puzzle_info;
+
+/// Stopping long searches early.
+pub mod interrupt;
+
+/// Reporting the progress and the statistics of the searches.
+#[cfg(any(
+    feature = "puzzle-spinning-table",
+    feature = "puzzle-scrabble",
+    feature = "puzzle-card"
+))]
+pub mod progress;
+
+/// Splitting the searches into parts that can run on different machines.
+#[cfg(any(feature = "puzzle-spinning-table", feature = "puzzle-scrabble"))]
+pub mod shard;
+
+/// Checking which Scrabble® hands can form the words of a word list.
+#[cfg(feature = "puzzle-scrabble")]
+pub mod wordlist;
+
+/// Building the parameters of the puzzles without the command line.
+pub mod builder;
+
+/// Coloring the text output of the puzzles.
+#[cfg(any(
+    feature = "puzzle-spinning-table",
+    feature = "puzzle-scrabble",
+    feature = "puzzle-card"
+))]
+mod color;
+
+/// Parsing the values of the command-line options.
+#[cfg(feature = "clap")]
+pub mod value_parser;
+
+/// The errors returned by the solvers.
+mod error;
+
+/// Calling the solvers from other languages through a C interface.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Solving the puzzles in a web page.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
+
+/// A puzzle that can be solved.
+///
+/// Each puzzle module has a type that implements this trait
+/// by calling the module's solver function,
+/// so that generic code can handle all the puzzles in the same way.
+pub trait Solver {
+    /// The name of the puzzle on the command line.
+    const NAME: &'static str;
+
+    /// The parameters of a variant of the puzzle.
+    type Parameters;
+
+    /// The solution to the puzzle.
+    type Output;
+
+    /// Solves the puzzle.
+    fn solve(parameters: Self::Parameters) -> Result<Self::Output, Error>;
+
+    /// Solves the puzzle,
+    /// writing the solutions to `out` as they are found
+    /// if the parameters ask for it.
+    ///
+    /// By default, nothing is written to `out`.
+    fn solve_to(
+        parameters: Self::Parameters,
+        _out: &mut dyn io::Write,
+    ) -> Result<Self::Output, Error> {
+        Self::solve(parameters)
+    }
+}
@@ -1,38 +1,1734 @@
 #![warn(clippy::all)]
 
-use structopt::StructOpt;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fmt::{self, Debug, Display, Formatter},
+    fs::{self, File},
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+    iter,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-/// [Puzzle 1 - Spinning table](http://www.think-maths.co.uk/table-puzzle)
-mod spinning_table;
+use indicatif::{ProgressBar, ProgressStyle};
 
-/// [Puzzle 3 - Scrabble®](http://www.think-maths.co.uk/scrabble-puzzle)
-mod scrabble;
+use tracing::{info, Level};
 
-/// [Puzzle 4 - Card](http://www.think-maths.co.uk/card-puzzle)
-mod card;
+use serde::Serialize;
+
+use serde_json::json;
+
+use clap::{error::ErrorKind, ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use clap_complete::Shell;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use matt_parkers_maths_puzzles::{
+    card::{self, Card},
+    interrupt::{self, Interrupt},
+    progress::Progress,
+    puzzle_info,
+    scrabble::{self, Scrabble},
+    spinning_table::{self, SpinningTable},
+    value_parser::non_zero_usize,
+    Error, Solver,
+};
+
+mod batch;
+mod catalog;
+mod config;
+mod estimate;
+mod params_json;
+mod selftest;
+mod tui;
 
 /// Entry point.
 fn main() {
-    match Puzzle::from_args() {
+    let options = params_json::apply(env::args_os().collect())
+        .and_then(config::apply)
+        .and_then(Options::try_parse_from)
+        .unwrap_or_else(|err| err.exit());
+    if let Some(level) = options.log_level() {
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(io::stderr)
+            .without_time()
+            .with_ansi(io::stderr().is_terminal())
+            .init();
+    }
+
+    options.validate().unwrap_or_else(|err| err.exit());
+    let silent = options.verbosity() == Verbosity::Silent;
+    let stats = options.stats;
+    let explain_pruning = matches!(
+        &options.puzzle,
+        Puzzle::Scrabble(parameters) if parameters.explains_pruning()
+    );
+    let json = options.format == Format::Json;
+
+    if let Some(threads) = options.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.get())
+            .build_global()
+            .expect("the thread pool is only configured once");
+    }
+
+    // Buffer the output,
+    // because writing the solutions line by line to stdout is slow.
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let context = Context::new();
+    ALLOCATOR.reset_peak();
+    let start = Instant::now();
+    let result = execute(options, &context, &mut out);
+    let wall_time = start.elapsed();
+
+    // Write the output produced before an error, if any.
+    match result.and(out.flush().map_err(Error::from)) {
+        Ok(()) => {}
+
+        // The output stopped being read (e.g. `--stream | head`),
+        // which only means that no more solutions were wanted.
+        Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => {}
+
+        Err(err) => {
+            eprintln!("error: {}", err);
+
+            // With `-qq`, 1 means that there are no solutions.
+            process::exit(if silent { 2 } else { 1 });
+        }
+    }
+
+    if explain_pruning {
+        let pruning = PruningStats::collect(&context.progress);
+        if json {
+            eprintln!("{:#}", pruning.to_json());
+        } else {
+            eprint!("{}", pruning);
+        }
+    }
+
+    if stats {
+        let stats = RunStats::collect(wall_time, &context.progress);
+        if json {
+            eprintln!("{:#}", stats.to_json());
+        } else {
+            eprint!("{}", stats);
+        }
+    }
+
+    if silent && NO_SOLUTIONS.load(Ordering::SeqCst) {
+        process::exit(1);
+    }
+
+    if SELFTEST_FAILED.load(Ordering::SeqCst) {
+        process::exit(1);
+    }
+}
+
+/// Set when a puzzle solved with `-qq` has no solutions.
+static NO_SOLUTIONS: AtomicBool = AtomicBool::new(false);
+
+/// Set when `selftest` finds a wrong answer.
+static SELFTEST_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// The flags and counters that a run shares with its searches.
+#[derive(Clone, Debug)]
+struct Context {
+    /// Set when the user presses Ctrl-C or asks the terminal interface to stop.
+    ctrl_c: Interrupt,
+
+    /// Stops the searches of the current command,
+    /// which is set by `ctrl_c` and when `--time-limit` is reached.
+    interrupt: Interrupt,
+
+    /// The progress of the current search and the statistics of the run.
+    progress: Progress,
+}
+
+impl Context {
+    /// Creates the flags and counters of a run.
+    fn new() -> Self {
+        let ctrl_c = Interrupt::new();
+        Self {
+            interrupt: ctrl_c.child(),
+            ctrl_c,
+            progress: Progress::new(),
+        }
+    }
+
+    /// Returns the context of a command,
+    /// whose searches can be stopped without stopping the next commands.
+    fn for_command(&self) -> Self {
+        Self {
+            interrupt: self.ctrl_c.child(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the default parameters of a puzzle, sharing this context.
+    fn default_parameters<P: Shared + Default>(&self) -> P {
+        let mut parameters = P::default();
+        parameters.share(self);
+        parameters
+    }
+
+    /// Lets the searches of `puzzle` be stopped and followed through this context.
+    fn share(&self, puzzle: &mut Puzzle) {
+        match puzzle {
+            Puzzle::SpinningTable(parameters)
+            | Puzzle::Bench(Bench {
+                puzzle: BenchPuzzle::SpinningTable(parameters),
+                ..
+            })
+            | Puzzle::Simulate(Simulate {
+                puzzle: SimulatedPuzzle::SpinningTable(parameters),
+                ..
+            }) => parameters.share(self),
+            Puzzle::Scrabble(parameters)
+            | Puzzle::Bench(Bench {
+                puzzle: BenchPuzzle::Scrabble(parameters),
+                ..
+            })
+            | Puzzle::Simulate(Simulate {
+                puzzle: SimulatedPuzzle::Scrabble(parameters),
+                ..
+            }) => parameters.share(self),
+            Puzzle::Analyze(Analysis::Scrabble(parameters)) => parameters.share(self),
+            Puzzle::Card(parameters)
+            | Puzzle::Bench(Bench {
+                puzzle: BenchPuzzle::Card(parameters),
+                ..
+            })
+            | Puzzle::Simulate(Simulate {
+                puzzle: SimulatedPuzzle::Card(parameters),
+                ..
+            }) => parameters.share(self),
+            _ => {}
+        }
+    }
+}
+
+/// The parameters of a puzzle whose searches can share a `Context`.
+trait Shared {
+    /// Makes the searches stop with the context's interrupt
+    /// and report their progress to the context's counters.
+    fn share(&mut self, context: &Context);
+}
+
+impl Shared for spinning_table::Parameters {
+    fn share(&mut self, context: &Context) {
+        self.set_interrupt(context.interrupt.clone());
+        self.set_progress(context.progress.clone());
+    }
+}
+
+impl Shared for scrabble::Parameters {
+    fn share(&mut self, context: &Context) {
+        self.set_interrupt(context.interrupt.clone());
+        self.set_progress(context.progress.clone());
+    }
+}
+
+impl Shared for card::Parameters {
+    fn share(&mut self, context: &Context) {
+        self.set_progress(context.progress.clone());
+    }
+}
+
+/// Runs `run`, showing the progress of the searches on stderr if `show` is set.
+fn with_progress_bar<T>(show: bool, progress: &Progress, run: impl FnOnce() -> T) -> T {
+    if !show {
+        return run();
+    }
+
+    let done = AtomicBool::new(false);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            // The bar only appears once a search has started.
+            let mut bar = None;
+            while !done.load(Ordering::Relaxed) {
+                if progress.length() > 0 {
+                    let bar = bar.get_or_insert_with(|| {
+                        ProgressBar::new(0).with_style(
+                            ProgressStyle::with_template(
+                                "{elapsed_precise} [{wide_bar}] {percent}% (ETA {eta})",
+                            )
+                            .expect("the progress bar template is valid"),
+                        )
+                    });
+                    bar.set_length(progress.length());
+                    bar.set_position(progress.position());
+                }
+
+                thread::sleep(Duration::from_millis(100));
+            }
+
+            if let Some(bar) = bar {
+                bar.finish_and_clear();
+            }
+        });
+
+        let result = run();
+        done.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+/// Runs `run`, triggering `interrupt` to stop the searches
+/// if it takes longer than `limit`.
+fn with_time_limit<T>(
+    limit: Option<Duration>,
+    interrupt: &Interrupt,
+    run: impl FnOnce() -> T,
+) -> T {
+    let limit = match limit {
+        Some(limit) => limit,
+        None => return run(),
+    };
+
+    let (done, wait) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            // The sender is dropped when `run` returns, which ends the wait early.
+            if wait.recv_timeout(limit) == Err(RecvTimeoutError::Timeout) {
+                info!("the time limit of {:?} was reached", limit);
+                interrupt.trigger();
+            }
+        });
+
+        let result = run();
+        drop(done);
+        result
+    })
+}
+
+/// Parses a duration such as `500ms`, `30s`, `2m` or `1h`.
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let unit_start = duration
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(duration.len());
+    let (amount, unit) = duration.split_at(unit_start);
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("`{}` doesn't start with a number", duration))?;
+    let seconds_per_unit = match unit {
+        "ms" => 0.001,
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        _ => {
+            return Err(format!(
+                "`{}` doesn't end with `ms`, `s`, `m` or `h`",
+                duration
+            ))
+        }
+    };
+
+    Duration::try_from_secs_f64(amount * seconds_per_unit).map_err(|err| err.to_string())
+}
+
+/// Runs the command with the progress bar and the output file, if requested,
+/// writing its output to `out` if there is no output file.
+fn execute(options: Options, context: &Context, out: &mut impl Write) -> Result<(), Error> {
+    let context = context.for_command();
+    with_progress_bar(options.progress, &context.progress, || {
+        with_time_limit(options.time_limit, &context.interrupt, || {
+            match options.output.clone() {
+                Some(path) => run_to_file(options, &context, &path),
+                None => run(options, &context, out),
+            }
+        })
+    })
+}
+
+/// Runs the command, writing its output to the file at `path`.
+///
+/// The output is first written to a temporary file next to `path`,
+/// which replaces `path` only once the command has succeeded,
+/// so that `path` never holds partial output.
+fn run_to_file(options: Options, context: &Context, path: &Path) -> Result<(), Error> {
+    let mut temporary_name = OsString::from(".");
+    temporary_name.push(path.file_name().unwrap_or_else(|| "output".as_ref()));
+    temporary_name.push(".tmp");
+    let temporary_path = path.with_file_name(temporary_name);
+
+    let result = File::create(&temporary_path)
+        .map_err(Error::from)
+        .and_then(|file| {
+            let mut out = BufWriter::new(file);
+            run(options, context, &mut out)?;
+            out.into_inner()
+                .map_err(|err| err.into_error())?
+                .sync_all()?;
+            fs::rename(&temporary_path, path)?;
+            Ok(())
+        });
+
+    if result.is_err() {
+        // The file may not have been created; there is nothing else to do then.
+        let _ = fs::remove_file(&temporary_path);
+    }
+
+    result
+}
+
+/// Runs the command, writing its output to `out`.
+fn run(mut options: Options, context: &Context, out: &mut impl Write) -> Result<(), Error> {
+    if options.tui && io::stderr().is_terminal() {
+        return tui::run_with_tui(options, context, out);
+    }
+
+    context.share(&mut options.puzzle);
+
+    // Finding one solution is enough to know that there are some.
+    if options.verbosity() == Verbosity::Silent && options.max_solutions.is_none() {
+        options.max_solutions = NonZeroUsize::new(1);
+    }
+
+    let max_solutions = options.max_solutions;
+    let color = options.use_color();
+    match &mut options.puzzle {
+        Puzzle::SpinningTable(parameters) => {
+            parameters.set_max_solutions(max_solutions);
+            parameters.set_color(color);
+            parameters.set_ndjson(options.format == Format::Ndjson);
+        }
+        Puzzle::Scrabble(parameters) => {
+            parameters.set_max_solutions(max_solutions);
+            parameters.set_color(color);
+            if options.format != Format::Text {
+                parameters.set_stream(false);
+            }
+
+            parameters
+                .set_structured(options.format == Format::Json || options.format == Format::Ndjson);
+        }
+        Puzzle::Card(parameters) => parameters.set_color(color),
+        _ => {}
+    }
+
+    if let Puzzle::Scrabble(parameters) = &options.puzzle {
+        if let Some(report) = parameters.check_distribution()? {
+            eprintln!("{}", report);
+        }
+    }
+
+    let verbosity = options.verbosity();
+    let json = options.format == Format::Json;
+    let table = options.format == Format::Csv || options.format == Format::Markdown;
+    let ndjson = options.format == Format::Ndjson;
+    let format = options.format;
+
+    if options.estimate {
+        return estimate::estimate(options.puzzle, context, json, verbosity, out);
+    }
+
+    if verbosity == Verbosity::Silent {
+        let found = match options.puzzle {
+            Puzzle::SpinningTable(parameters) => {
+                solve_puzzle::<SpinningTable>(parameters, &mut io::sink())?.number_of_solutions > 0
+            }
+            Puzzle::Scrabble(parameters) => {
+                match solve_puzzle::<Scrabble>(parameters, &mut io::sink())? {
+                    scrabble::Output::Count(count) => count > 0,
+                    scrabble::Output::Sequences(sequences) => sequences > 0,
+                    scrabble::Output::Probability(probability) => probability.hands > 0,
+                    scrabble::Output::Unreachable(gap) => gap.score <= gap.highest_score,
+                    scrabble::Output::Bingos(words) => !words.is_empty(),
+                    scrabble::Output::Deals(deals) => !deals.is_empty(),
+                    scrabble::Output::Top(hands) => !hands.is_empty(),
+                    scrabble::Output::ByValues(groups) => !groups.is_empty(),
+                    scrabble::Output::Statistics(statistics) => statistics.draws > 0,
+                    scrabble::Output::Distribution(distribution) => distribution
+                        .values()
+                        .any(|probability| probability.hands > 0),
+                    scrabble::Output::Editions(counts) => {
+                        counts.iter().any(|(_, count)| *count > 0)
+                    }
+                    scrabble::Output::ByHandSize(counts) => counts.values().any(|&count| count > 0),
+                    output => number_of_listed_hands(&output) > 0,
+                }
+            }
+            // Every number of cards can be solved.
+            Puzzle::Card(parameters) => {
+                solve_puzzle::<Card>(parameters, &mut io::sink())?;
+                true
+            }
+            _ => unreachable!("`-qq` is only accepted for the puzzles"),
+        };
+
+        NO_SOLUTIONS.store(!found, Ordering::SeqCst);
+        return Ok(());
+    }
+
+    match options.puzzle {
+        Puzzle::SpinningTable(parameters) if json => {
+            solve_puzzle_json::<SpinningTable>(parameters, context, out)?;
+        }
+
+        Puzzle::SpinningTable(parameters) if ndjson => {
+            handle_ctrl_c(context);
+            let output = solve_puzzle::<SpinningTable>(parameters, out)?;
+            report_max_solutions(max_solutions, output.number_of_solutions);
+            // With `--shuffle`, the solutions can only be written once they are all found.
+            if !output.streamed && !output.solutions.is_empty() {
+                writeln!(out, "{}", output)?;
+            }
+
+            report_interruption(context);
+        }
+
+        Puzzle::Scrabble(parameters) if ndjson && parameters.is_list() => {
+            handle_ctrl_c(context);
+            let letter_values = scrabble::LetterValues::new(&parameters)?;
+            let mut count = 0;
+            for hand in solve(parameters, scrabble::iter_hands)? {
+                writeln!(out, "{}", json!(letter_values.hand_tiles(&hand)))?;
+                // Let the consumers process each hand as soon as it is found.
+                out.flush()?;
+                count += 1;
+            }
+
+            report_max_solutions(max_solutions, count);
+            report_interruption(context);
+        }
+
+        Puzzle::Scrabble(parameters) if ndjson => {
+            handle_ctrl_c(context);
+            let letter_values = scrabble::LetterValues::new(&parameters)?;
+            match solve_puzzle::<Scrabble>(parameters, &mut io::sink())? {
+                scrabble::Output::Count(count) => writeln!(out, "{}", json!({ "hands": count }))?,
+                scrabble::Output::Sequences(sequences) => {
+                    writeln!(out, "{}", json!({ "sequences": sequences }))?
+                }
+                scrabble::Output::Probability(probability) => {
+                    writeln!(out, "{}", json!(probability))?
+                }
+                scrabble::Output::Unreachable(gap) => writeln!(out, "{}", json!(gap))?,
+                scrabble::Output::Distribution(distribution) => {
+                    for (score, probability) in distribution {
+                        writeln!(
+                            out,
+                            "{}",
+                            json!({
+                                "score": score,
+                                "hands": probability.hands,
+                                "draws": probability.draws,
+                                "total_draws": probability.total_draws,
+                            })
+                        )?;
+                    }
+                }
+                scrabble::Output::List(_) => unreachable!("the hands are listed as they are found"),
+                scrabble::Output::Bingos(words) => {
+                    for word in words {
+                        writeln!(out, "{}", json!({ "word": word }))?;
+                    }
+                }
+                scrabble::Output::Deals(deals) => {
+                    for hands in deals {
+                        let hands: Vec<_> = hands
+                            .iter()
+                            .map(|hand| letter_values.hand_tiles(hand))
+                            .collect();
+                        writeln!(out, "{}", json!({ "hands": hands }))?;
+                    }
+                }
+                scrabble::Output::ByValues(groups) => {
+                    for group in groups {
+                        writeln!(out, "{}", json!(group))?;
+                    }
+                }
+                scrabble::Output::Statistics(statistics) => writeln!(
+                    out,
+                    "{}",
+                    json!({
+                        "draws": statistics.draws,
+                        "mean": statistics.mean(),
+                        "variance": statistics.variance(),
+                        "standard_deviation": statistics.standard_deviation(),
+                        "target_score": statistics.target_score,
+                        "z_score": statistics.z_score(),
+                    })
+                )?,
+                scrabble::Output::Top(hands) => {
+                    for (_, hand) in hands {
+                        writeln!(out, "{}", json!(letter_values.hand_tiles(&hand)))?;
+                    }
+                }
+                scrabble::Output::ByScore(hands_by_score) => {
+                    for hand in hands_by_score.values().flatten() {
+                        writeln!(out, "{}", json!(letter_values.hand_tiles(hand)))?;
+                    }
+                }
+                scrabble::Output::Editions(counts) => {
+                    for (edition, count) in counts {
+                        writeln!(out, "{}", json!({ "edition": edition, "hands": count }))?;
+                    }
+                }
+                scrabble::Output::ByHandSize(counts) => {
+                    for (hand_size, count) in counts {
+                        writeln!(out, "{}", json!({ "hand_size": hand_size, "hands": count }))?;
+                    }
+                }
+            }
+            report_interruption(context);
+        }
+
+        Puzzle::Card(parameters) if ndjson => {
+            let solution = solve_puzzle::<Card>(parameters, &mut io::sink())?;
+            writeln!(out, "{}", json!({ "flips": solution.flips }))?;
+        }
+
+        Puzzle::List if ndjson => {
+            for puzzle in catalog::catalog().as_array().into_iter().flatten() {
+                writeln!(out, "{}", puzzle)?;
+            }
+        }
+
+        Puzzle::Scrabble(parameters) if json => {
+            handle_ctrl_c(context);
+            let letter_values = scrabble::LetterValues::new(&parameters)?;
+            let echoed = json!(parameters);
+            let output = solve_puzzle::<Scrabble>(parameters, &mut io::sink())?;
+            let solution = scrabble_json(output, &letter_values);
+            write_json(out, Scrabble::NAME, echoed, solution)?;
+            report_interruption(context);
+        }
+
+        Puzzle::Card(parameters) if json => {
+            solve_puzzle_json::<Card>(parameters, context, out)?;
+        }
+
+        Puzzle::List if json => {
+            writeln!(out, "{:#}", catalog::catalog())?;
+        }
+
+        Puzzle::Analyze(Analysis::SpinningTable(parameters)) if json => {
+            let echoed = json!(parameters);
+            let counts = solve(parameters, spinning_table::dihedral_count);
+            write_json(out, SpinningTable::NAME, echoed, json!(counts))?;
+        }
+
+        Puzzle::Analyze(Analysis::Scrabble(parameters)) if json => {
+            let echoed = json!(parameters);
+            let analysis = solve(*parameters, scrabble::analyze)?;
+            write_json(out, Scrabble::NAME, echoed, json!(analysis))?;
+        }
+
+        Puzzle::SpinningTable(parameters) if table => {
+            handle_ctrl_c(context);
+            let caption = markdown_caption(SpinningTable::NAME, json!(parameters));
+            let header = (1..=parameters.number_of_seats()).map(|seat| format!("seat {}", seat));
+            let output = solve_puzzle::<SpinningTable>(parameters, &mut io::sink())?;
+            report_max_solutions(max_solutions, output.number_of_solutions);
+            let rows = output
+                .arrangements()
+                .map(|arrangement| arrangement.investors().collect());
+            write_table(out, format, &caption, header, rows)?;
+            report_interruption(context);
+        }
+
+        Puzzle::Scrabble(parameters) if table => {
+            handle_ctrl_c(context);
+            let caption = markdown_caption(Scrabble::NAME, json!(parameters));
+            let output = solve_puzzle::<Scrabble>(parameters, &mut io::sink())?;
+            report_max_solutions(max_solutions, number_of_listed_hands(&output));
+            match output {
+                scrabble::Output::Count(count) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["hands"],
+                    vec![vec![count.to_string()]],
+                )?,
+                scrabble::Output::Sequences(sequences) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["sequences"],
+                    vec![vec![sequences.to_string()]],
+                )?,
+                scrabble::Output::Probability(probability) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["hands", "draws", "total draws", "probability"],
+                    vec![vec![
+                        probability.hands.to_string(),
+                        probability.draws.to_string(),
+                        probability.total_draws.to_string(),
+                        probability.probability().to_string(),
+                    ]],
+                )?,
+                scrabble::Output::Unreachable(gap) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec![
+                        "smallest unreachable score",
+                        "lowest score",
+                        "highest score",
+                    ],
+                    vec![vec![
+                        gap.score.to_string(),
+                        gap.lowest_score.to_string(),
+                        gap.highest_score.to_string(),
+                    ]],
+                )?,
+                scrabble::Output::Distribution(distribution) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["score", "hands", "draws", "total draws", "probability"],
+                    distribution.into_iter().map(|(score, probability)| {
+                        vec![
+                            score.to_string(),
+                            probability.hands.to_string(),
+                            probability.draws.to_string(),
+                            probability.total_draws.to_string(),
+                            probability.probability().to_string(),
+                        ]
+                    }),
+                )?,
+                scrabble::Output::List(list) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["hand"],
+                    list.into_iter().map(|hand| vec![hand]),
+                )?,
+                scrabble::Output::Deals(deals) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    (1..=deals.first().map_or(0, Vec::len))
+                        .map(|player| format!("hand {}", player)),
+                    deals,
+                )?,
+                scrabble::Output::Bingos(words) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["word"],
+                    words.into_iter().map(|word| vec![word]),
+                )?,
+                scrabble::Output::Statistics(statistics) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec![
+                        "draws",
+                        "mean",
+                        "variance",
+                        "standard deviation",
+                        "target score",
+                        "z-score",
+                    ],
+                    vec![vec![
+                        statistics.draws.to_string(),
+                        statistics.mean().to_string(),
+                        statistics.variance().to_string(),
+                        statistics.standard_deviation().to_string(),
+                        statistics.target_score.to_string(),
+                        statistics.z_score().to_string(),
+                    ]],
+                )?,
+                scrabble::Output::ByValues(groups) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["values", "hands"],
+                    groups.into_iter().map(|group| {
+                        let values: Vec<_> = group.values.iter().map(u32::to_string).collect();
+                        vec![values.join("+"), group.hands.to_string()]
+                    }),
+                )?,
+                scrabble::Output::Top(hands) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["score", "hand"],
+                    hands
+                        .into_iter()
+                        .map(|(score, hand)| vec![score.to_string(), hand]),
+                )?,
+                scrabble::Output::ByScore(hands_by_score) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["score", "hand"],
+                    hands_by_score.into_iter().flat_map(|(score, hands)| {
+                        hands
+                            .into_iter()
+                            .map(move |hand| vec![score.to_string(), hand])
+                    }),
+                )?,
+                scrabble::Output::Editions(counts) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["edition", "hands"],
+                    counts
+                        .into_iter()
+                        .map(|(edition, count)| vec![edition.into_owned(), count.to_string()]),
+                )?,
+                scrabble::Output::ByHandSize(counts) => write_table(
+                    out,
+                    format,
+                    &caption,
+                    vec!["hand size", "hands"],
+                    counts
+                        .into_iter()
+                        .map(|(hand_size, count)| vec![hand_size.to_string(), count.to_string()]),
+                )?,
+            }
+            report_interruption(context);
+        }
+
+        Puzzle::Card(parameters) if table => {
+            let caption = markdown_caption(Card::NAME, json!(parameters));
+            let solution = solve_puzzle::<Card>(parameters, &mut io::sink())?;
+            let header = (1..=solution.flips.len()).map(|flip| format!("flip {}", flip));
+            let row = solution.flips.iter().map(u8::to_string).collect();
+            write_table(out, format, &caption, header, vec![row])?;
+        }
+
+        Puzzle::List if table => {
+            write_table(
+                out,
+                format,
+                "**puzzles**",
+                vec!["name", "description", "url"],
+                puzzle_info::puzzles().iter().map(|puzzle| {
+                    vec![
+                        puzzle.name.to_string(),
+                        puzzle.description.to_string(),
+                        puzzle.url.to_string(),
+                    ]
+                }),
+            )?;
+        }
+
         Puzzle::SpinningTable(parameters) => {
-            for solution in spinning_table::spinning_table(parameters) {
-                println!("{:?}", solution);
+            handle_ctrl_c(context);
+            let output = solve_puzzle::<SpinningTable>(parameters, out)?;
+            report_max_solutions(max_solutions, output.number_of_solutions);
+            if output.streamed {
+                // The solutions have already been written.
+                if verbosity != Verbosity::Quiet {
+                    writeln!(out, "number of solutions: {}", output.number_of_solutions)?;
+                }
+            } else {
+                if verbosity != Verbosity::Quiet {
+                    writeln!(out, "solutions ({}):", output.number_of_solutions)?;
+                }
+
+                if !output.solutions.is_empty() {
+                    writeln!(out, "{}", output)?;
+                }
             }
+
+            report_interruption(context);
         }
 
         Puzzle::Scrabble(parameters) => {
-            println!("{}", scrabble::scrabble(parameters));
+            handle_ctrl_c(context);
+            let streamed = parameters.is_streamed();
+            let output = solve_puzzle::<Scrabble>(parameters, out)?;
+            if streamed {
+                // The hands have already been written, and the output is their number.
+                if let scrabble::Output::Count(count) = output {
+                    report_max_solutions(max_solutions, count as usize);
+                    if verbosity != Verbosity::Quiet {
+                        writeln!(out, "number of hands: {}", count)?;
+                    }
+                }
+
+                report_interruption(context);
+                return Ok(());
+            }
+
+            report_max_solutions(max_solutions, number_of_listed_hands(&output));
+            if verbosity != Verbosity::Quiet {
+                match &output {
+                    scrabble::Output::Count(_) => write!(out, "number of hands: ")?,
+                    scrabble::Output::Sequences(_) => write!(out, "number of sequences: ")?,
+                    scrabble::Output::Probability(_) => write!(out, "probability: ")?,
+                    scrabble::Output::Unreachable(_) => {
+                        write!(out, "smallest unreachable score: ")?
+                    }
+                    scrabble::Output::Distribution(distribution) => {
+                        writeln!(out, "distribution of scores ({}):", distribution.len())?
+                    }
+                    scrabble::Output::List(list) => writeln!(out, "hands ({}):", list.len())?,
+                    scrabble::Output::Bingos(words) => writeln!(out, "bingos ({}):", words.len())?,
+                    scrabble::Output::Deals(deals) => writeln!(out, "deals ({}):", deals.len())?,
+                    scrabble::Output::Top(hands) => writeln!(out, "top hands ({}):", hands.len())?,
+                    scrabble::Output::ByValues(groups) => {
+                        writeln!(out, "hands by tile values ({}):", groups.len())?
+                    }
+                    scrabble::Output::Statistics(_) => writeln!(out, "score statistics:")?,
+                    scrabble::Output::ByScore(hands_by_score) => writeln!(
+                        out,
+                        "hands by score ({}):",
+                        hands_by_score.values().map(Vec::len).sum::<usize>()
+                    )?,
+                    scrabble::Output::Editions(counts) => {
+                        writeln!(out, "number of hands by edition ({}):", counts.len())?
+                    }
+                    scrabble::Output::ByHandSize(counts) => {
+                        writeln!(out, "number of hands by hand size ({}):", counts.len())?
+                    }
+                }
+            }
+
+            writeln!(out, "{}", output)?;
+            report_interruption(context);
         }
 
         Puzzle::Card(parameters) => {
-            println!("{:?}", card::card(parameters));
+            let solution = solve_puzzle::<Card>(parameters, out)?;
+            if verbosity != Verbosity::Quiet {
+                // The outputs with several lines start on the line after the label.
+                let separator = if solution.is_multiline() { "\n" } else { " " };
+                write!(out, "flips ({}):{}", solution.flips.len(), separator)?;
+            }
+
+            writeln!(out, "{}", solution)?;
+        }
+
+        Puzzle::List => {
+            for puzzle in puzzle_info::puzzles() {
+                writeln!(
+                    out,
+                    "{}: {} <{}>",
+                    puzzle.name, puzzle.description, puzzle.url
+                )?;
+            }
+        }
+
+        Puzzle::Analyze(Analysis::SpinningTable(parameters)) => {
+            writeln!(out, "{}", solve(parameters, spinning_table::dihedral_count))?;
+        }
+
+        Puzzle::Analyze(Analysis::Scrabble(parameters)) => {
+            writeln!(out, "{}", solve(*parameters, scrabble::analyze)?)?;
+        }
+
+        Puzzle::All if json => writeln!(out, "{:#}", solve_all(context)?)?,
+
+        Puzzle::Bench(bench) => {
+            let runs = bench.runs;
+            let report = match bench.puzzle {
+                BenchPuzzle::SpinningTable(parameters) => {
+                    bench_puzzle::<SpinningTable>(runs, parameters)?
+                }
+                BenchPuzzle::Scrabble(parameters) => bench_puzzle::<Scrabble>(runs, parameters)?,
+                BenchPuzzle::Card(parameters) => bench_puzzle::<Card>(runs, parameters)?,
+            };
+
+            if json {
+                writeln!(out, "{:#}", report.to_json())?;
+            } else {
+                write!(out, "{}", report)?;
+            }
+        }
+
+        Puzzle::Simulate(simulation) => {
+            handle_ctrl_c(context);
+            let samples = simulation.samples;
+            let seed = simulation.seed.unwrap_or_else(rand::random);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (name, mut echoed, result, report) = match simulation.puzzle {
+                SimulatedPuzzle::SpinningTable(parameters) => {
+                    let simulation = solve(&parameters, |parameters| {
+                        spinning_table::simulate(parameters, samples, &mut rng)
+                    })?;
+                    let report = simulation.to_string();
+                    (
+                        SpinningTable::NAME,
+                        json!(parameters),
+                        json!(simulation),
+                        report,
+                    )
+                }
+                SimulatedPuzzle::Scrabble(parameters) => {
+                    let echoed = json!(parameters);
+                    let simulation = solve(parameters, |parameters| {
+                        scrabble::simulate(parameters, samples, &mut rng)
+                    })?;
+                    let report = simulation.to_string();
+                    (Scrabble::NAME, echoed, json!(simulation), report)
+                }
+                SimulatedPuzzle::Card(parameters) => {
+                    let simulation = solve(&parameters, |parameters| {
+                        card::simulate(parameters, samples, &mut rng)
+                    });
+                    let report = simulation.to_string();
+                    (Card::NAME, json!(parameters), json!(simulation), report)
+                }
+            };
+
+            if json {
+                // The seed is echoed so that the simulation can be reproduced.
+                echoed["samples"] = json!(samples);
+                echoed["seed"] = json!(seed);
+                write_json(out, name, echoed, result)?;
+            } else {
+                if verbosity != Verbosity::Quiet {
+                    writeln!(out, "seed: {}", seed)?;
+                }
+
+                writeln!(out, "{}", report)?;
+            }
+
+            report_interruption(context);
+        }
+
+        Puzzle::All => {
+            for (index, puzzle) in puzzle_info::puzzles().iter().enumerate() {
+                if index != 0 {
+                    writeln!(out)?;
+                }
+
+                writeln!(out, "== {} ==", puzzle.description)?;
+
+                // Parsing a command line with only the puzzle's name
+                // gives the puzzle's default parameters.
+                let mut args = vec![env!("CARGO_PKG_NAME")];
+                match verbosity {
+                    Verbosity::Silent | Verbosity::Quiet => args.push("-q"),
+                    Verbosity::Normal => {}
+                    Verbosity::Verbose => args.push("-v"),
+                }
+
+                let max_solutions = max_solutions.map(|max| format!("--max-solutions={}", max));
+                args.extend(max_solutions.as_deref());
+                args.push(if color {
+                    "--color=always"
+                } else {
+                    "--color=never"
+                });
+                args.push(puzzle.name);
+                let options = Options::parse_from(args);
+                run(options, context, out)?;
+            }
+        }
+
+        Puzzle::SolveAll => writeln!(out, "{:#}", solve_all(context)?)?,
+
+        Puzzle::Selftest => {
+            if !selftest::run_selftest(context, json, verbosity, out)? {
+                SELFTEST_FAILED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        Puzzle::Repl => repl(context, out)?,
+
+        Puzzle::Batch => batch::run_batch(context, out)?,
+
+        Puzzle::Completions { shell } => {
+            clap_complete::generate(shell, &mut Options::command(), env!("CARGO_PKG_NAME"), out);
         }
     }
+
+    Ok(())
+}
+
+/// Reads commands from stdin and runs them until the end of the input,
+/// writing their output to `out`.
+///
+/// Each line holds the arguments of a command, quoted like in a shell.
+/// The errors are reported on stderr and don't stop the loop.
+/// The tile files and word lists of the Scrabble® commands are only loaded once,
+/// unless they change between commands.
+fn repl(context: &Context, out: &mut impl Write) -> Result<(), Error> {
+    let cache = scrabble::Cache::default();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        // Show the output of the previous command before the prompt.
+        out.flush()?;
+        eprint!("> ");
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        let args = match shell_words::split(&line) {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                continue;
+            }
+        };
+
+        match args.first().map(String::as_str) {
+            None => continue,
+            Some("exit") | Some("quit") => break,
+            Some(_) => {}
+        }
+
+        let args = iter::once(env!("CARGO_PKG_NAME").to_string())
+            .chain(args)
+            .map(OsString::from)
+            .collect();
+        let mut options = match params_json::apply(args)
+            .and_then(config::apply)
+            .and_then(Options::try_parse_from)
+            .and_then(|options| {
+                options.validate()?;
+                Ok(options)
+            }) {
+            Ok(options) => options,
+            Err(err) if err.use_stderr() => {
+                eprint!("{}", err.render());
+                continue;
+            }
+            Err(err) => {
+                // The help and version messages are the output of the command.
+                write!(out, "{}", err.render())?;
+                continue;
+            }
+        };
+
+        if options.threads.is_some() {
+            eprintln!("warning: `--threads` only applies when the program starts");
+        }
+
+        match &mut options.puzzle {
+            Puzzle::Scrabble(parameters) => parameters.set_cache(cache.clone()),
+            Puzzle::Analyze(Analysis::Scrabble(parameters)) => parameters.set_cache(cache.clone()),
+            _ => {}
+        }
+
+        if let Err(err) = execute(options, context, out) {
+            eprintln!("error: {}", err);
+        }
+
+        // Let the next command run to completion if this one was interrupted.
+        context.ctrl_c.reset();
+    }
+
+    Ok(())
+}
+
+/// Solves every puzzle with the default parameters
+/// and collects the results in a JSON document keyed by puzzle.
+fn solve_all(context: &Context) -> Result<serde_json::Value, Error> {
+    let spinning_table = SpinningTable::solve(context.default_parameters())?;
+    let scrabble = match Scrabble::solve(context.default_parameters())? {
+        scrabble::Output::Count(count) => count,
+        _ => unreachable!("the default Scrabble output is a count"),
+    };
+    let card = Card::solve(context.default_parameters())?;
+
+    let info = |name| {
+        let puzzle = puzzle_info::puzzles()
+            .iter()
+            .find(|puzzle| puzzle.name == name)
+            .expect("unknown puzzle");
+        (puzzle.description, puzzle.url)
+    };
+    let (spinning_table_description, spinning_table_url) = info(SpinningTable::NAME);
+    let (scrabble_description, scrabble_url) = info(Scrabble::NAME);
+    let (card_description, card_url) = info(Card::NAME);
+
+    Ok(json!({
+        "spinning_table": {
+            "description": spinning_table_description,
+            "url": spinning_table_url,
+            "count": spinning_table.number_of_solutions,
+            "solutions": spinning_table.solutions,
+        },
+        "scrabble": {
+            "description": scrabble_description,
+            "url": scrabble_url,
+            "count": scrabble,
+        },
+        "card": {
+            "description": card_description,
+            "url": card_url,
+            "flips": card.flips.len(),
+            "sequence": card.flips,
+        },
+    }))
+}
+
+/// Makes Ctrl-C stop the search early instead of terminating the program.
+fn handle_ctrl_c(context: &Context) {
+    if let Err(err) = interrupt::handle_ctrl_c(&context.ctrl_c) {
+        eprintln!("warning: Ctrl-C will terminate the program: {}", err);
+    }
+}
+
+/// Warns on stderr that the results are incomplete
+/// if the time limit was reached or the user pressed Ctrl-C.
+fn report_interruption(context: &Context) {
+    if context.ctrl_c.is_triggered() {
+        eprintln!("note: the search was interrupted, so the results are incomplete");
+    } else if context.interrupt.is_triggered() {
+        eprintln!("note: search incomplete, the time limit was reached");
+    }
+}
+
+/// Returns the number of hands that a Scrabble® output lists,
+/// or 0 if it only counts them.
+fn number_of_listed_hands(output: &scrabble::Output) -> usize {
+    match output {
+        scrabble::Output::List(list) | scrabble::Output::Bingos(list) => list.len(),
+        scrabble::Output::Deals(deals) => deals.len(),
+        scrabble::Output::Top(hands) => hands.len(),
+        scrabble::Output::ByScore(hands_by_score) => hands_by_score.values().map(Vec::len).sum(),
+        scrabble::Output::Count(_)
+        | scrabble::Output::Sequences(_)
+        | scrabble::Output::Probability(_)
+        | scrabble::Output::Distribution(_)
+        | scrabble::Output::ByValues(_)
+        | scrabble::Output::Statistics(_)
+        | scrabble::Output::Unreachable(_)
+        | scrabble::Output::Editions(_)
+        | scrabble::Output::ByHandSize(_) => 0,
+    }
+}
+
+/// Reports on stderr if the search stopped at `--max-solutions`,
+/// in which case there may be more solutions.
+fn report_max_solutions(max_solutions: Option<NonZeroUsize>, number_of_solutions: usize) {
+    if max_solutions.map(NonZeroUsize::get) == Some(number_of_solutions) {
+        eprintln!(
+            "note: the search stopped after {} solutions, so there may be more",
+            number_of_solutions
+        );
+    }
+}
+
+/// Solves a puzzle,
+/// writing the solutions that are found one at a time to `out`.
+fn solve_puzzle<S>(parameters: S::Parameters, out: &mut impl Write) -> Result<S::Output, Error>
+where
+    S: Solver,
+    S::Parameters: Debug,
+{
+    solve(parameters, |parameters| S::solve_to(parameters, out))
+}
+
+/// Solves a puzzle and writes a JSON document
+/// with the puzzle's name, the parameters and the solution to `out`.
+///
+/// The solutions are never streamed,
+/// so that `out` only contains the JSON document.
+fn solve_puzzle_json<S>(
+    parameters: S::Parameters,
+    context: &Context,
+    out: &mut impl Write,
+) -> Result<(), Error>
+where
+    S: Solver,
+    S::Parameters: Debug + Serialize,
+    S::Output: Serialize,
+{
+    handle_ctrl_c(context);
+    let echoed = json!(parameters);
+    let output = solve_puzzle::<S>(parameters, &mut io::sink())?;
+    write_json(out, S::NAME, echoed, json!(output))?;
+    report_interruption(context);
+    Ok(())
+}
+
+/// Returns the JSON value of a Scrabble® output,
+/// with the hands of the list outputs written as their tiles.
+fn scrabble_json(
+    output: scrabble::Output,
+    letter_values: &scrabble::LetterValues,
+) -> serde_json::Value {
+    let hand_tiles = |hands: Vec<String>| -> Vec<_> {
+        hands
+            .iter()
+            .map(|hand| letter_values.hand_tiles(hand))
+            .collect()
+    };
+    match output {
+        scrabble::Output::List(hands) => json!({ "List": hand_tiles(hands) }),
+        scrabble::Output::Deals(deals) => {
+            let deals: Vec<_> = deals.into_iter().map(hand_tiles).collect();
+            json!({ "Deals": deals })
+        }
+        scrabble::Output::ByScore(hands_by_score) => {
+            let hands_by_score: BTreeMap<_, _> = hands_by_score
+                .into_iter()
+                .map(|(score, hands)| (score, hand_tiles(hands)))
+                .collect();
+            json!({ "ByScore": hands_by_score })
+        }
+        scrabble::Output::Top(hands) => {
+            let hands = hand_tiles(hands.into_iter().map(|(_, hand)| hand).collect());
+            json!({ "Top": hands })
+        }
+        output => json!(output),
+    }
+}
+
+/// Writes a JSON document with the puzzle's name, the parameters and the solution to `out`.
+fn write_json(
+    out: &mut impl Write,
+    puzzle: &str,
+    parameters: serde_json::Value,
+    solution: serde_json::Value,
+) -> Result<(), Error> {
+    let document = json!({
+        "puzzle": puzzle,
+        "parameters": parameters,
+        "solution": solution,
+    });
+    writeln!(out, "{:#}", document)?;
+    Ok(())
+}
+
+/// Writes a header and rows to `out`
+/// as CSV, or as a Markdown table under `caption` for `--format markdown`.
+fn write_table<H>(
+    out: &mut impl Write,
+    format: Format,
+    caption: &str,
+    header: impl IntoIterator<Item = H>,
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Result<(), Error>
+where
+    H: AsRef<str>,
+{
+    if format == Format::Markdown {
+        write_markdown(out, caption, header, rows)
+    } else {
+        write_csv(out, header, rows)
+    }
+}
+
+/// Writes a header and rows as CSV to `out`.
+fn write_csv<H>(
+    out: &mut impl Write,
+    header: impl IntoIterator<Item = H>,
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Result<(), Error>
+where
+    H: AsRef<str>,
+{
+    let mut writer = csv::Writer::from_writer(out);
+    writer
+        .write_record(header.into_iter().map(|field| field.as_ref().to_owned()))
+        .map_err(io::Error::from)?;
+    for row in rows {
+        writer.write_record(&row).map_err(io::Error::from)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a caption, then a header and rows as a Markdown table to `out`.
+fn write_markdown<H>(
+    out: &mut impl Write,
+    caption: &str,
+    header: impl IntoIterator<Item = H>,
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Result<(), Error>
+where
+    H: AsRef<str>,
+{
+    let header: Vec<_> = header
+        .into_iter()
+        .map(|field| markdown_cell(field.as_ref()))
+        .collect();
+    writeln!(out, "{}", caption)?;
+    writeln!(out)?;
+    writeln!(out, "| {} |", header.join(" | "))?;
+    writeln!(out, "|{}", " --- |".repeat(header.len()))?;
+    for row in rows {
+        let row: Vec<_> = row.iter().map(|field| markdown_cell(field)).collect();
+        writeln!(out, "| {} |", row.join(" | "))?;
+    }
+
+    Ok(())
+}
+
+/// Escapes a field for a cell of a Markdown table.
+///
+/// A field that starts or ends with a space (e.g. a Scrabble® hand with a blank)
+/// is shown as code, since the spaces around a cell are trimmed otherwise.
+fn markdown_cell(field: &str) -> String {
+    let escaped = field.replace('|', "\\|");
+    if field.starts_with(' ') || field.ends_with(' ') {
+        format!("`{}`", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// Builds the caption of a Markdown table from the puzzle's name and the parameters,
+/// leaving out the parameters that aren't set.
+fn markdown_caption(puzzle: &str, parameters: serde_json::Value) -> String {
+    let mut caption = format!("**{}**", puzzle);
+    let set = parameters
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, value)| {
+            !value.is_null() && !value.as_array().is_some_and(|array| array.is_empty())
+        })
+        .map(|(name, value)| match value.as_str() {
+            Some(string) => format!("{}: `{}`", name, string),
+            None => format!("{}: `{}`", name, value),
+        })
+        .collect::<Vec<_>>();
+    if !set.is_empty() {
+        caption.push_str(" — ");
+        caption.push_str(&set.join(", "));
+    }
+
+    caption
+}
+
+/// Runs a solver,
+/// logging its parameters and the time it took.
+fn solve<P, T>(parameters: P, solver: impl FnOnce(P) -> T) -> T
+where
+    P: Debug,
+{
+    info!("parameters: {:?}", parameters);
+
+    let start = Instant::now();
+    let result = solver(parameters);
+
+    info!("solved in {:?}", start.elapsed());
+
+    result
 }
 
 /// Command-line arguments.
-#[derive(StructOpt)]
-#[structopt(about("Solutions to Matt Parker's Math Puzzles in Rust"))]
+#[derive(Parser)]
+#[command(version, about = "Solutions to Matt Parker's Math Puzzles in Rust")]
+struct Options {
+    /// Logs the parameters and the time taken to solve the puzzle on stderr.
+    ///
+    /// Repeat it (`-vv`) to also log a summary of each search,
+    /// or three times (`-vvv`) to log every solution and pruned branch.
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Prints only the result, without labels.
+    ///
+    /// Repeat it (`-qq`) to print nothing
+    /// and only tell through the exit code whether a puzzle has solutions:
+    /// 0 if it has, 1 if it doesn't, or 2 if there was an error.
+    #[arg(short, long, action = ArgAction::Count)]
+    quiet: u8,
+
+    /// How the result is printed.
+    ///
+    /// `json` prints a JSON document with the puzzle's name,
+    /// the parameters and the solution.
+    /// `csv` prints a header and one row per solution;
+    /// `markdown` prints the same table in Markdown,
+    /// under a caption with the puzzle's name and the parameters,
+    /// ready to be pasted in a forum post or on GitHub.
+    /// `ndjson` prints each solution as a JSON object on its own line,
+    /// as soon as it is found when the spinning table and Scrabble® hands are listed.
+    /// They are only available for the puzzles and `list`.
+    /// Solutions are never streamed in JSON, CSV or Markdown.
+    #[arg(long, value_enum, ignore_case = true, default_value_t = Format::Text)]
+    format: Format,
+
+    /// When to color the puzzles' text output:
+    /// the Scrabble® letters by point value,
+    /// the investors in their correct seat
+    /// and the card numbers in the sequence of flips.
+    ///
+    /// `auto` colors the output when it is written to a terminal
+    /// and the `NO_COLOR` environment variable isn't set.
+    #[arg(long, value_enum, ignore_case = true, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Shows a progress bar on stderr while the spinning table and Scrabble® puzzles are solved.
+    #[arg(long)]
+    progress: bool,
+
+    /// Shows the search live in the terminal, then prints the result.
+    ///
+    /// The spinning table and Scrabble® searches show their progress and current step;
+    /// the card puzzle shows the state of the cards after each flip.
+    #[arg(long, conflicts_with = "progress")]
+    tui: bool,
+
+    /// Reads default options from a TOML file
+    /// instead of `mpmp/config.toml` in the user's configuration directory.
+    ///
+    /// The options given on the command line take precedence.
+    /// The `MPMP_CONFIG` environment variable also gives the file to read,
+    /// or turns off the configuration file if it is empty.
+    #[arg(long)]
+    #[allow(dead_code)] // `config::apply` reads it before the options are parsed.
+    config: Option<PathBuf>,
+
+    /// Sets the puzzle's options from a JSON object,
+    /// named like the long options or like the parameters in the JSON output
+    /// (e.g. `scrabble --params-json '{"hand_size": 8, "target_score": 50}'`).
+    ///
+    /// The options given on the command line take precedence.
+    #[arg(long, global = true, value_name = "JSON")]
+    #[allow(dead_code)] // `params_json::apply` reads it before the options are parsed.
+    params_json: Option<String>,
+
+    /// Stops the spinning table and Scrabble® searches
+    /// once they have found that many solutions.
+    ///
+    /// The Scrabble® count isn't limited, since it doesn't list the hands.
+    /// With the spinning table's `--shuffle`, all the solutions are found
+    /// and a random sample of that many solutions is kept.
+    #[arg(long, value_parser = non_zero_usize())]
+    max_solutions: Option<NonZeroUsize>,
+
+    /// Stops the spinning table and Scrabble® searches after that long,
+    /// like Ctrl-C does, and prints what they found so far.
+    ///
+    /// The duration is a number followed by `ms`, `s`, `m` or `h` (e.g. `30s`).
+    #[arg(long, value_parser = parse_duration)]
+    time_limit: Option<Duration>,
+
+    /// The number of threads that the searches that run in parallel share.
+    ///
+    /// Defaults to the number of cores.
+    /// It can't be changed by the commands of `repl`.
+    #[arg(long, value_parser = non_zero_usize())]
+    threads: Option<NonZeroUsize>,
+
+    /// Reports the size of the search space of a puzzle and a rough prediction
+    /// of the time it takes to solve it, instead of solving it.
+    ///
+    /// The spinning table and Scrabble® searches are run for a moment to measure their speed;
+    /// the card puzzle is solved with at most 20 cards.
+    #[arg(long)]
+    estimate: bool,
+
+    /// Reports a summary of the run on stderr after the result:
+    /// the wall time, the number of solutions found,
+    /// the number of nodes explored and of branches pruned by the searches,
+    /// and the peak memory used.
+    ///
+    /// With `--format json`, the summary is a JSON document.
+    #[arg(long)]
+    stats: bool,
+
+    /// Writes the result to a file instead of stdout.
+    ///
+    /// The file is only replaced once the whole result has been written.
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// The puzzle to solve.
+    #[command(subcommand)]
+    puzzle: Puzzle,
+}
+
+impl Options {
+    /// Checks the combinations of options that clap can't check.
+    fn validate(&self) -> clap::error::Result<()> {
+        if !matches!(self.format, Format::Text | Format::Json) {
+            if let Puzzle::All
+            | Puzzle::SolveAll
+            | Puzzle::Selftest
+            | Puzzle::Analyze(_)
+            | Puzzle::Bench(_)
+            | Puzzle::Simulate(_)
+            | Puzzle::Repl
+            | Puzzle::Batch = self.puzzle
+            {
+                return Err(Options::command().error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "`--format {}` is only available for the puzzles and `list`",
+                        self.format.name()
+                    ),
+                ));
+            }
+        }
+
+        if self.verbosity() == Verbosity::Silent {
+            if let Puzzle::SpinningTable(_) | Puzzle::Scrabble(_) | Puzzle::Card(_) = self.puzzle {
+                if self.estimate {
+                    return Err(Options::command().error(
+                        ErrorKind::ArgumentConflict,
+                        "`-qq` can't be used with `--estimate`",
+                    ));
+                }
+            } else {
+                return Err(Options::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "`-qq` is only available for the puzzles",
+                ));
+            }
+        }
+
+        if self.stats {
+            if let Puzzle::SpinningTable(_) | Puzzle::Scrabble(_) | Puzzle::Card(_) = self.puzzle {
+                if self.estimate {
+                    return Err(Options::command().error(
+                        ErrorKind::ArgumentConflict,
+                        "`--stats` can't be used with `--estimate`",
+                    ));
+                }
+            } else {
+                return Err(Options::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "`--stats` is only available for the puzzles",
+                ));
+            }
+        }
+
+        if self.estimate {
+            if let Puzzle::SpinningTable(_) | Puzzle::Scrabble(_) | Puzzle::Card(_) = self.puzzle {
+                if !matches!(self.format, Format::Text | Format::Json) {
+                    return Err(Options::command().error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "`--estimate` can't be used with `--format {}`",
+                            self.format.name()
+                        ),
+                    ));
+                }
+            } else {
+                return Err(Options::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "`--estimate` is only available for the puzzles",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determines whether to color the output from the `--color` option.
+    ///
+    /// Only the text output is colored.
+    fn use_color(&self) -> bool {
+        if self.format != Format::Text {
+            return false;
+        }
+
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                self.output.is_none()
+                    && io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none()
+            }
+        }
+    }
+
+    /// Determines the most detailed level of the events to log
+    /// from the `--verbose` flag.
+    fn log_level(&self) -> Option<Level> {
+        match self.verbose {
+            0 => None,
+            1 => Some(Level::INFO),
+            2 => Some(Level::DEBUG),
+            _ => Some(Level::TRACE),
+        }
+    }
+
+    /// Determines the verbosity level from the `--verbose` and `--quiet` flags.
+    fn verbosity(&self) -> Verbosity {
+        if self.quiet > 1 {
+            Verbosity::Silent
+        } else if self.quiet > 0 {
+            Verbosity::Quiet
+        } else if self.verbose > 0 {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
+
+/// Choices for how the result is printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Print the result as text.
+    Text,
+
+    /// Print a JSON document.
+    Json,
+
+    /// Print a header and one row per solution.
+    Csv,
+
+    /// Print a Markdown table with a caption.
+    Markdown,
+
+    /// Print each solution as a JSON object on its own line.
+    Ndjson,
+}
+
+impl Format {
+    /// Returns the name of the format as given to `--format`.
+    fn name(self) -> &'static str {
+        match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Markdown => "markdown",
+            Format::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Choices for when the output is colored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color the output when it is written to a terminal.
+    Auto,
+
+    /// Always color the output.
+    Always,
+
+    /// Never color the output.
+    Never,
+}
+
+/// How much context is printed along with the result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verbosity {
+    /// Nothing is printed;
+    /// the exit code tells whether the puzzle has solutions.
+    Silent,
+
+    /// Only the result is printed.
+    Quiet,
+
+    /// The result is printed with a label.
+    Normal,
+
+    /// The result is printed with a label,
+    /// and the parameters and the time taken are printed on stderr.
+    Verbose,
+}
+
+/// The puzzles that can be solved.
+#[derive(Subcommand)]
 enum Puzzle {
     /// Puzzle 1 - Spinning table <http://www.think-maths.co.uk/table-puzzle>
     SpinningTable(spinning_table::Parameters),
@@ -42,4 +1738,426 @@ enum Puzzle {
 
     /// Puzzle 4 - Card <http://www.think-maths.co.uk/card-puzzle>
     Card(card::Parameters),
+
+    /// Lists the available puzzles
+    ///
+    /// With `--format json`, it prints a catalog of the puzzles
+    /// with their options, the options' defaults and possible values,
+    /// and the answer to each puzzle with its default parameters.
+    List,
+
+    /// Solves every puzzle with the default parameters and prints a report
+    All,
+
+    /// Solves every puzzle with the default parameters and prints the results as JSON
+    SolveAll,
+
+    /// Checks that the puzzles give their known answers with the default parameters
+    ///
+    /// This is a quick check of a build of the program:
+    /// it exits with 1 if an answer is wrong.
+    Selftest,
+
+    /// Analyzes the solutions to a puzzle
+    #[command(subcommand)]
+    Analyze(Analysis),
+
+    /// Solves a puzzle several times and reports the time and allocations it took
+    Bench(Bench),
+
+    /// Samples random draws, seatings or starting positions of a puzzle
+    /// and reports how often they are solutions
+    ///
+    /// This cross-checks the exact counts
+    /// and gives an idea of the puzzles that are too large to solve exactly.
+    Simulate(Simulate),
+
+    /// Reads commands from stdin, one per line, and runs them
+    ///
+    /// The commands take the same arguments as the program,
+    /// except that `--verbose` only has an effect when it is given to `repl`.
+    /// The tile files and word lists are kept between the commands.
+    /// Enter `exit` or `quit`, or end the input, to stop.
+    Repl,
+
+    /// Solves the puzzles described on stdin, one per line, and prints one JSON result per line
+    ///
+    /// Each line holds the arguments of a puzzle, quoted like in a shell
+    /// (e.g. `scrabble --hand-size 8 --target-score 50`),
+    /// or a JSON object with the puzzle's name and its options
+    /// (e.g. `{"puzzle": "scrabble", "hand_size": 8, "target_score": 50}`).
+    /// A line that can't be solved gives an object with the error.
+    Batch,
+
+    /// Generates a completion script for a shell
+    Completions {
+        /// The shell to generate the completion script for.
+        #[arg(value_enum, ignore_case = true)]
+        shell: Shell,
+    },
+}
+
+/// Arguments for benchmarking a puzzle.
+#[derive(Args)]
+struct Bench {
+    /// The number of times the puzzle is solved.
+    #[arg(short, long, default_value = "10", value_parser = non_zero_usize())]
+    runs: NonZeroUsize,
+
+    /// The puzzle to benchmark.
+    #[command(subcommand)]
+    puzzle: BenchPuzzle,
+}
+
+/// The puzzles that can be benchmarked.
+#[derive(Subcommand)]
+enum BenchPuzzle {
+    /// Puzzle 1 - Spinning table
+    SpinningTable(spinning_table::Parameters),
+
+    /// Puzzle 3 - Scrabble®
+    Scrabble(scrabble::Parameters),
+
+    /// Puzzle 4 - Card
+    Card(card::Parameters),
+}
+
+/// Arguments for simulating a puzzle.
+#[derive(Args)]
+struct Simulate {
+    /// The number of random samples to draw.
+    #[arg(long, default_value = "10000", value_parser = non_zero_usize())]
+    samples: NonZeroUsize,
+
+    /// The seed of the random samples, to reproduce a simulation.
+    ///
+    /// Defaults to a different seed on every run, which is printed with the result.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// The puzzle to simulate.
+    #[command(subcommand)]
+    puzzle: SimulatedPuzzle,
+}
+
+/// The puzzles that can be simulated.
+#[derive(Subcommand)]
+enum SimulatedPuzzle {
+    /// Puzzle 1 - Spinning table: seats the investors at random
+    SpinningTable(spinning_table::Parameters),
+
+    /// Puzzle 3 - Scrabble®: draws random hands from the bag
+    Scrabble(scrabble::Parameters),
+
+    /// Puzzle 4 - Card: deals the cards in random starting positions
+    Card(card::Parameters),
+}
+
+/// The time and allocations taken by the runs of a benchmark.
+struct BenchReport {
+    /// The name of the puzzle.
+    puzzle: &'static str,
+
+    /// The wall time of each run, in increasing order.
+    times: Vec<Duration>,
+
+    /// The number of allocations made by all the runs.
+    allocations: u64,
+
+    /// The number of bytes allocated by all the runs.
+    allocated_bytes: u64,
+}
+
+impl BenchReport {
+    /// Returns the shortest wall time.
+    fn min(&self) -> Duration {
+        self.times[0]
+    }
+
+    /// Returns the median wall time.
+    fn median(&self) -> Duration {
+        let middle = self.times.len() / 2;
+        if self.times.len().is_multiple_of(2) {
+            (self.times[middle - 1] + self.times[middle]) / 2
+        } else {
+            self.times[middle]
+        }
+    }
+
+    /// Returns the mean wall time.
+    fn mean(&self) -> Duration {
+        self.times.iter().sum::<Duration>() / self.times.len() as u32
+    }
+
+    /// Returns the mean number of allocations per run.
+    fn allocations_per_run(&self) -> u64 {
+        self.allocations / self.times.len() as u64
+    }
+
+    /// Returns the mean number of bytes allocated per run.
+    fn allocated_bytes_per_run(&self) -> u64 {
+        self.allocated_bytes / self.times.len() as u64
+    }
+
+    /// Returns the report as a JSON document, with the times in seconds.
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "puzzle": self.puzzle,
+            "runs": self.times.len(),
+            "min": self.min().as_secs_f64(),
+            "median": self.median().as_secs_f64(),
+            "mean": self.mean().as_secs_f64(),
+            "allocations_per_run": self.allocations_per_run(),
+            "allocated_bytes_per_run": self.allocated_bytes_per_run(),
+        })
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "{} ({} runs):", self.puzzle, self.times.len())?;
+        writeln!(fmt, "min: {:?}", self.min())?;
+        writeln!(fmt, "median: {:?}", self.median())?;
+        writeln!(fmt, "mean: {:?}", self.mean())?;
+        writeln!(
+            fmt,
+            "allocations per run: {} ({} bytes)",
+            self.allocations_per_run(),
+            self.allocated_bytes_per_run()
+        )
+    }
+}
+
+/// Solves a puzzle `runs` times, discarding the solutions,
+/// and measures the wall time and the allocations of each run.
+fn bench_puzzle<S>(runs: NonZeroUsize, parameters: S::Parameters) -> Result<BenchReport, Error>
+where
+    S: Solver,
+    S::Parameters: Clone,
+{
+    let mut times = Vec::with_capacity(runs.get());
+    let mut allocations = 0;
+    let mut allocated_bytes = 0;
+    for _ in 0..runs.get() {
+        // Clone the parameters before starting the clock,
+        // so that only the solver is measured.
+        let parameters = parameters.clone();
+        let (allocations_before, allocated_bytes_before) = ALLOCATOR.counts();
+        let start = Instant::now();
+        S::solve_to(parameters, &mut io::sink())?;
+        times.push(start.elapsed());
+        let (allocations_after, allocated_bytes_after) = ALLOCATOR.counts();
+        allocations += allocations_after - allocations_before;
+        allocated_bytes += allocated_bytes_after - allocated_bytes_before;
+    }
+
+    times.sort();
+    Ok(BenchReport {
+        puzzle: S::NAME,
+        times,
+        allocations,
+        allocated_bytes,
+    })
+}
+
+/// A summary of a run for `--stats`.
+#[derive(Debug)]
+struct RunStats {
+    /// The wall time of the run.
+    wall_time: Duration,
+
+    /// The number of solutions found.
+    solutions: u64,
+
+    /// The number of nodes explored by the searches.
+    explored: u64,
+
+    /// The number of branches pruned by the searches.
+    pruned: u64,
+
+    /// The largest number of bytes in use on the heap during the run.
+    peak_memory: u64,
+}
+
+impl RunStats {
+    /// Collects the statistics of the searches and of the allocator
+    /// since they were reset at the start of the run.
+    fn collect(wall_time: Duration, progress: &Progress) -> Self {
+        RunStats {
+            wall_time,
+            solutions: progress.solutions(),
+            explored: progress.explored(),
+            pruned: progress.pruned(),
+            peak_memory: ALLOCATOR.peak(),
+        }
+    }
+
+    /// Returns the summary as a JSON document, with the wall time in seconds.
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "wall_time": self.wall_time.as_secs_f64(),
+            "solutions": self.solutions,
+            "nodes_explored": self.explored,
+            "branches_pruned": self.pruned,
+            "peak_memory_bytes": self.peak_memory,
+        })
+    }
+}
+
+impl Display for RunStats {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "wall time: {:?}", self.wall_time)?;
+        writeln!(fmt, "solutions: {}", self.solutions)?;
+        writeln!(fmt, "nodes explored: {}", self.explored)?;
+        writeln!(fmt, "branches pruned: {}", self.pruned)?;
+        writeln!(fmt, "peak memory: {} bytes", self.peak_memory)
+    }
+}
+
+/// The subtrees of the Scrabble® search that were pruned and explored, for `--explain-pruning`.
+#[derive(Debug)]
+struct PruningStats {
+    /// The number of subtrees pruned because none of their hands can reach the target score.
+    pruned: u64,
+
+    /// The number of subtrees explored.
+    explored: u64,
+}
+
+impl PruningStats {
+    /// Collects the counters of the searches since they were reset at the start of the run.
+    fn collect(progress: &Progress) -> Self {
+        PruningStats {
+            pruned: progress.pruned(),
+            explored: progress.explored(),
+        }
+    }
+
+    /// Returns the fraction of the subtrees that were pruned, between 0 and 1.
+    fn ratio(&self) -> f64 {
+        let total = self.pruned + self.explored;
+        if total == 0 {
+            0.0
+        } else {
+            self.pruned as f64 / total as f64
+        }
+    }
+
+    /// Returns the counters and the ratio as a JSON document.
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "subtrees_pruned": self.pruned,
+            "subtrees_explored": self.explored,
+            "pruned_ratio": self.ratio(),
+        })
+    }
+}
+
+impl Display for PruningStats {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "subtrees pruned: {}", self.pruned)?;
+        writeln!(fmt, "subtrees explored: {}", self.explored)?;
+        writeln!(fmt, "pruned ratio: {:.2}%", self.ratio() * 100.0)
+    }
+}
+
+/// The global allocator, which counts the allocations for the `bench` subcommand
+/// and tracks the peak memory for `--stats`.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    allocations: AtomicU64::new(0),
+    allocated_bytes: AtomicU64::new(0),
+    in_use: AtomicU64::new(0),
+    peak: AtomicU64::new(0),
+};
+
+/// An allocator that forwards to the system allocator
+/// and counts the allocations and the bytes allocated.
+struct CountingAllocator {
+    /// The number of allocations made so far, including reallocations.
+    allocations: AtomicU64,
+
+    /// The number of bytes allocated so far, including reallocations.
+    allocated_bytes: AtomicU64,
+
+    /// The number of bytes currently allocated.
+    in_use: AtomicU64,
+
+    /// The largest number of bytes allocated at once since `reset_peak`.
+    peak: AtomicU64,
+}
+
+impl CountingAllocator {
+    /// Returns the number of allocations and the number of bytes allocated so far.
+    fn counts(&self) -> (u64, u64) {
+        (
+            self.allocations.load(Ordering::Relaxed),
+            self.allocated_bytes.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Returns the largest number of bytes allocated at once since `reset_peak`.
+    fn peak(&self) -> u64 {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Starts tracking the peak from the number of bytes currently allocated.
+    fn reset_peak(&self) {
+        self.peak
+            .store(self.in_use.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Counts an allocation of `size` bytes.
+    fn count(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.allocated_bytes
+            .fetch_add(size as u64, Ordering::Relaxed);
+        self.grow(size);
+    }
+
+    /// Records that `size` more bytes are in use.
+    fn grow(&self, size: usize) {
+        let in_use = self.in_use.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        self.peak.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    /// Records that `size` fewer bytes are in use.
+    fn shrink(&self, size: usize) {
+        self.in_use.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every method forwards to the system allocator with the same arguments.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.shrink(layout.size());
+        self.count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.shrink(layout.size());
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// The analyses of the solutions to the puzzles.
+#[derive(Subcommand)]
+enum Analysis {
+    /// Counts the solutions up to rotation and up to rotation and reflection
+    /// for each number of seats
+    SpinningTable(spinning_table::DihedralCountParameters),
+
+    /// Reports which letters appear in all, some or none of the hands
+    Scrabble(Box<scrabble::Parameters>),
 }
@@ -0,0 +1,133 @@
+//! Options given as a JSON object with `--params-json`.
+//!
+//! The object sets the options of the puzzle that is solved,
+//! named like the long options or like the parameters in the JSON output:
+//!
+//! ```text
+//! mpmp scrabble --params-json '{"hand_size": 8, "target_score": 50}'
+//! ```
+//!
+//! so the `parameters` of a JSON output can be given back to solve the same puzzle.
+//! `true` gives a flag, `false` and `null` leave it out,
+//! and an array gives an option once for each element.
+//! The options given on the command line take precedence.
+
+use std::ffi::OsString;
+
+use clap::{error::ErrorKind, parser::ValueSource, ArgMatches, Command, CommandFactory};
+
+use serde_json::Value;
+
+use crate::Options;
+
+/// The subcommands whose options can be set with `--params-json`.
+const PUZZLES: &[&str] = &["spinning-table", "scrabble", "card"];
+
+/// Adds the options set with `--params-json` to `args`,
+/// except the ones that `args` already gives.
+pub fn apply(mut args: Vec<OsString>) -> clap::error::Result<Vec<OsString>> {
+    let command = Options::command();
+    let matches = command.clone().try_get_matches_from(&args)?;
+    let params = match matches.get_one::<String>("params_json") {
+        Some(params) => params,
+        None => return Ok(args),
+    };
+
+    let (puzzle, puzzle_matches) = match matches.subcommand() {
+        Some((puzzle, puzzle_matches)) if PUZZLES.contains(&puzzle) => (puzzle, puzzle_matches),
+        _ => {
+            return Err(Options::command().error(
+                ErrorKind::ArgumentConflict,
+                "`--params-json` is only available for the puzzles",
+            ))
+        }
+    };
+    let puzzle_command = command
+        .find_subcommand(puzzle)
+        .expect("the puzzles are subcommands");
+
+    let object = match serde_json::from_str(params) {
+        Ok(Value::Object(object)) => object,
+        Ok(_) => return Err(invalid(&command, "expected a JSON object")),
+        Err(err) => return Err(invalid(&command, &err.to_string())),
+    };
+
+    let mut global_args = vec![];
+    let mut puzzle_args = vec![];
+    for (key, value) in object {
+        let long = key.replace('_', "-");
+        // The JSON output also echoes the global options that apply to the puzzle,
+        // but the puzzle's own options come first (`output` is both).
+        let (command, matches, args) = if puzzle_command
+            .get_arguments()
+            .any(|arg| arg.get_long() == Some(long.as_str()))
+        {
+            (puzzle_command, puzzle_matches, &mut puzzle_args)
+        } else {
+            (&command, &matches, &mut global_args)
+        };
+
+        if !is_given(command, matches, &long)? {
+            args.extend(
+                value_to_args(&long, value)
+                    .map_err(|err| invalid(command, &err))?
+                    .into_iter()
+                    .map(OsString::from),
+            );
+        }
+    }
+
+    // The global options go before the subcommand,
+    // and the puzzle takes no positional arguments, so its options can go at the end.
+    args.extend(puzzle_args);
+    args.splice(1..1, global_args);
+    Ok(args)
+}
+
+/// Returns whether the command line already gives the option with that long name,
+/// or an error if `command` has no such option.
+fn is_given(command: &Command, matches: &ArgMatches, long: &str) -> clap::error::Result<bool> {
+    let arg = command
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+        .ok_or_else(|| {
+            invalid(
+                command,
+                &format!("`{}` is not an option of `{}`", long, command.get_name()),
+            )
+        })?;
+    Ok(matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+}
+
+/// Converts the value of an option in a JSON object to arguments.
+///
+/// `true` gives a flag, `false` and `null` leave it out,
+/// and an array gives the option once for each element.
+pub fn value_to_args(long: &str, value: Value) -> Result<Vec<String>, String> {
+    let values = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let arg = match value {
+                Value::Bool(true) => format!("--{}", long),
+                Value::Bool(false) | Value::Null => return None,
+                Value::String(value) => format!("--{}={}", long, value),
+                Value::Number(value) => format!("--{}={}", long, value),
+                _ => return Some(Err(format!("`{}` can't be set to an object", long))),
+            };
+            Some(Ok(arg))
+        })
+        .collect()
+}
+
+/// Returns the error for a `--params-json` that can't be used.
+fn invalid(command: &Command, message: &str) -> clap::Error {
+    command.clone().error(
+        ErrorKind::InvalidValue,
+        format!("invalid value for `--params-json`: {}", message),
+    )
+}
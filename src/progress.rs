@@ -0,0 +1,129 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+/// The number of steps of a search that are done and the total number of steps,
+/// along with a description of the step being done,
+/// and the statistics of the searches since the counters were reset.
+#[derive(Debug, Default)]
+struct Counters {
+    position: AtomicU64,
+    length: AtomicU64,
+    snapshot_requested: AtomicBool,
+    snapshot: Mutex<String>,
+    explored: AtomicU64,
+    pruned: AtomicU64,
+    solutions: AtomicU64,
+}
+
+/// Reports how far a search has gone,
+/// so that another thread can display it.
+///
+/// The clones of a `Progress` share the same counters,
+/// so giving a clone to each search adds up their statistics.
+#[derive(Clone, Debug, Default)]
+pub struct Progress(Arc<Counters>);
+
+impl Progress {
+    /// Creates counters that are not shared with other searches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears the counters, including the statistics that `start` keeps,
+    /// before running a command whose statistics are reported.
+    pub fn reset(&self) {
+        self.start(0);
+        self.0.explored.store(0, Ordering::Relaxed);
+        self.0.pruned.store(0, Ordering::Relaxed);
+        self.0.solutions.store(0, Ordering::Relaxed);
+    }
+
+    /// Resets the progress at the start of a search with `length` steps.
+    pub(crate) fn start(&self, length: u64) {
+        self.0.position.store(0, Ordering::Relaxed);
+        self.0.length.store(length, Ordering::Relaxed);
+    }
+
+    /// Records that `steps` more steps of the search are done.
+    #[cfg(any(feature = "puzzle-spinning-table", feature = "puzzle-scrabble"))]
+    pub(crate) fn advance(&self, steps: u64) {
+        self.0.position.fetch_add(steps, Ordering::Relaxed);
+    }
+
+    /// Records that `nodes` more nodes of the search were explored.
+    pub(crate) fn explore(&self, nodes: u64) {
+        self.0.explored.fetch_add(nodes, Ordering::Relaxed);
+    }
+
+    /// Records that a branch of the search was pruned.
+    #[cfg(feature = "puzzle-scrabble")]
+    pub(crate) fn prune(&self) {
+        self.0.pruned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that the search found `solutions` more solutions.
+    pub(crate) fn find(&self, solutions: u64) {
+        self.0.solutions.fetch_add(solutions, Ordering::Relaxed);
+    }
+
+    /// Records a description of the current step of the search
+    /// if `request_snapshot` was called since the last one.
+    ///
+    /// `describe` is only called in that case,
+    /// so that the search doesn't pay for descriptions that nobody reads.
+    #[cfg(any(feature = "puzzle-spinning-table", feature = "puzzle-scrabble"))]
+    pub(crate) fn record_snapshot(&self, describe: impl FnOnce() -> String) {
+        if self.0.snapshot_requested.load(Ordering::Relaxed)
+            && self.0.snapshot_requested.swap(false, Ordering::Relaxed)
+        {
+            *self
+                .0
+                .snapshot
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()) = describe();
+        }
+    }
+
+    /// Returns the number of steps of the search that are done.
+    pub fn position(&self) -> u64 {
+        self.0.position.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of steps of the search,
+    /// or 0 if no search has started.
+    pub fn length(&self) -> u64 {
+        self.0.length.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of nodes explored by the searches since the counters were reset.
+    pub fn explored(&self) -> u64 {
+        self.0.explored.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of branches pruned by the searches since the counters were reset.
+    pub fn pruned(&self) -> u64 {
+        self.0.pruned.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of solutions found since the counters were reset.
+    pub fn solutions(&self) -> u64 {
+        self.0.solutions.load(Ordering::Relaxed)
+    }
+
+    /// Asks the search to describe the step it is at in the next snapshot.
+    pub fn request_snapshot(&self) {
+        self.0.snapshot_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the description of a recent step of the search,
+    /// or an empty string if the search hasn't described any step yet.
+    pub fn snapshot(&self) -> String {
+        self.0
+            .snapshot
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
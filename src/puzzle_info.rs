@@ -0,0 +1,63 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Information about a puzzle, for presentation purposes.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PuzzleInfo {
+    /// The name of the puzzle's subcommand.
+    pub name: &'static str,
+
+    /// The URL of the puzzle's statement.
+    pub url: &'static str,
+
+    /// A short description of the puzzle.
+    pub description: &'static str,
+
+    /// The answer to the puzzle with its default parameters,
+    /// as given by Matt Parker.
+    pub answer: &'static str,
+}
+
+/// The information for all implemented puzzles.
+static PUZZLES: &[PuzzleInfo] = &[
+    PuzzleInfo {
+        name: "spinning-table",
+        url: "http://www.think-maths.co.uk/table-puzzle",
+        description: "Puzzle 1 - Spinning table",
+        answer: "19 solutions",
+    },
+    PuzzleInfo {
+        name: "scrabble",
+        url: "http://www.think-maths.co.uk/scrabble-puzzle",
+        description: "Puzzle 3 - Scrabble®",
+        answer: "138 hands",
+    },
+    PuzzleInfo {
+        name: "card",
+        url: "http://www.think-maths.co.uk/card-puzzle",
+        description: "Puzzle 4 - Card",
+        answer: "15 flips: [1, 2, 1, 3, 1, 2, 1, 4, 1, 2, 1, 3, 1, 2, 1]",
+    },
+];
+
+/// Returns the information for all implemented puzzles.
+pub fn puzzles() -> &'static [PuzzleInfo] {
+    PUZZLES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_puzzles() {
+        let names: Vec<_> = puzzles().iter().map(|puzzle| puzzle.name).collect();
+
+        assert_eq!(names, ["spinning-table", "scrabble", "card"]);
+        for puzzle in puzzles() {
+            assert!(!puzzle.url.is_empty(), "{} has no URL", puzzle.name);
+            assert!(!puzzle.answer.is_empty(), "{} has no answer", puzzle.name);
+        }
+    }
+}
@@ -1,13 +1,46 @@
 use std::{
-    cell::Cell,
-    collections::BTreeMap,
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap},
+    convert::TryFrom,
+    ffi::OsStr,
     fmt::{self, Display, Formatter},
-    iter::{self, Peekable},
+    fs,
+    io::{self, BufWriter, Write},
+    iter,
+    num::{NonZeroUsize, ParseIntError},
+    ops::{ControlFlow, RangeInclusive},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::SystemTime,
 };
 
 use itertools::Itertools;
 
-use structopt::{clap::arg_enum, StructOpt};
+use rayon::prelude::*;
+
+use quick_error::quick_error;
+
+use rand::{seq::index, Rng};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "clap")]
+use clap::{Arg, ArgAction, Parser, ValueEnum};
+
+use tracing::{debug, debug_span, info, trace};
+
+use crate::{
+    builder::BuildError,
+    color::{self, Color},
+    interrupt::Interrupt,
+    progress::Progress,
+    shard::Shard,
+    wordlist::{Wordlist, WordlistError},
+    Error, Solver,
+};
 
 /// Produces the solution to the Scrabble® puzzle.
 ///
@@ -21,498 +54,7428 @@ use structopt::{clap::arg_enum, StructOpt};
 /// that total exactly 46.
 /// So order does not matter
 /// and identical letters are indistinguishable.
-pub fn scrabble(parameters: Parameters) -> Output {
-    match parameters.output {
-        OutputFormat::Count => Output::Count(scrabble1::<SolutionCount>(parameters)),
-        OutputFormat::List => Output::List(scrabble1::<SolutionList>(parameters)),
+pub fn scrabble(mut parameters: Parameters) -> Result<Output, Error> {
+    if parameters.compare_editions {
+        return EDITIONS
+            .iter()
+            .map(|edition| {
+                let tiles = &*parameters.adjust_tiles(Cow::Borrowed(edition.tiles))?;
+                parameters.validate(tiles)?;
+                let count = scrabble1::<SolutionCount>(&parameters, tiles, None, None)?;
+                parameters.progress.find(count);
+                Ok((edition.name.into(), count))
+            })
+            .collect::<Result<_, _>>()
+            .map(Output::Editions);
     }
-}
 
-/// Produces the solution to the Scrabble® puzzle in the specified output format.
-fn scrabble1<S>(parameters: Parameters) -> S
-where
-    S: SolutionAccumulator,
-{
-    let mut solution_accumulator = S::new();
+    let tiles = &*tiles(&parameters)?;
+    parameters.resolve_target_percent(tiles);
+    parameters.resolve_score_range(tiles);
+    parameters.validate(tiles)?;
+    let wordlist = parameters.load_wordlist()?;
+    let wordlist = wordlist.as_deref();
 
-    // Group tile definitions by their value.
-    let tiles_by_value = STANDARD_ENGLISH_SCRABBLE_TILES
-        .iter()
-        // Accumulate into a BTreeMap so that the order is consistent between runs.
-        .fold(BTreeMap::new(), |mut map, counted_tile| {
-            let tile_value = map
-                .entry(counted_tile.tile.value)
-                .or_insert_with(TilesForValue::default);
-            tile_value.live_counted_tiles.push(LiveCountedTile {
-                counted_tile,
-                occurrences_drawn: Default::default(),
-            });
-            tile_value.number_of_tiles += counted_tile.occurrences;
-            map
-        });
+    if parameters.hand_size.is_range() {
+        let counts = count_by_hand_size(&parameters, tiles);
+        parameters.progress.find(counts.values().sum());
+        return Ok(Output::ByHandSize(counts));
+    }
 
-    draw_abstract(
-        &parameters,
-        &tiles_by_value,
-        &mut solution_accumulator,
-        // When we do a recursive call,
-        // we must start at the same tile value,
-        // not from the start,
-        // otherwise we would find duplicate solutions.
-        tiles_by_value.values().peekable(),
-        0,
-    );
+    if !parameters.deal.is_empty() {
+        return scrabble_deals(&parameters, tiles);
+    }
 
-    solution_accumulator.finish();
+    if let Some(top) = parameters.top {
+        let top_hands = scrabble_top(&parameters, tiles, wordlist, top.get())?;
+        let (scores, mut hands): (Vec<_>, SolutionList) = top_hands.into_iter().unzip();
+        render_hands(&mut hands, tiles, &parameters);
 
-    solution_accumulator
-}
+        parameters.progress.find(hands.len() as SolutionCount);
+        return Ok(Output::Top(scores.into_iter().zip(hands).collect()));
+    }
 
-/// For each distinct tile value,
-/// draw one tile with that value (without specifying which letter),
-/// then recursively draw more tiles
-/// until the hand size is reached.
-/// If the hand is full, add the solutions to `solution_accumulator`.
-fn draw_abstract<'a, S>(
-    parameters: &Parameters,
-    tiles_by_value: &BTreeMap<u32, TilesForValue<'_>>,
-    solution_accumulator: &mut S,
-    mut tiles_for_value_iter: Peekable<impl Iterator<Item = &'a TilesForValue<'a>> + Clone>,
-    tiles_drawn_so_far: u32,
-) where
-    S: SolutionAccumulator,
-{
-    // Have we drawn enough tiles yet?
-    if tiles_drawn_so_far == parameters.hand_size {
-        // Does the cumulative value of the tiles we drew match the target score?
-        let hand_score: u32 = tiles_by_value
-            .iter()
-            .map(|(&tile_value, tiles)| tile_value * tiles.number_of_abstract_tiles_drawn.get())
-            .sum();
-        if hand_score == parameters.target_score {
-            // Enumerate the possible hands
-            // for the combination of tile values that was drawn.
+    if let OutputFormat::Statistics = parameters.output {
+        // The statistics are computed from the generating functions, whatever the method.
+        return Ok(Output::Statistics(scrabble_statistics(&parameters, tiles)));
+    }
 
-            let concrete_tile_combinations_by_tile_value = tiles_by_value
-                // For each tile value
-                .values()
-                // for which we drew at least one tile (for performance),
-                .filter(|x| x.number_of_abstract_tiles_drawn.get() > 0)
-                // enumerate all unique groups of letters
-                // of the size matching the number of tiles drawn
-                .map(|tiles_for_value| {
-                    let mut concrete_tile_combinations = S::new();
-                    draw_concrete(
-                        tiles_for_value,
-                        &mut concrete_tile_combinations,
-                        // When we do a recursive call,
-                        // we must start at the same letter,
-                        // not from the start,
-                        // otherwise we would find duplicate solutions.
-                        tiles_for_value.live_counted_tiles.iter().peekable(),
-                        0,
-                    );
-                    concrete_tile_combinations
-                })
-                .collect();
+    if let Method::GeneratingFunction | Method::Memoized = parameters.method {
+        let output = match parameters.method {
+            Method::GeneratingFunction => scrabble_generating_function(&parameters, tiles),
+            _ => scrabble_memoized(&parameters, tiles),
+        };
+        parameters.progress.find(match &output {
+            Output::Count(count) => *count,
+            Output::Probability(probability) => probability.hands,
+            _ => unreachable!("the hands are only counted without a search"),
+        });
+        return Ok(output);
+    }
 
-            // Perform the cartesian product
-            // of the possible groups of letters by tile value,
-            // and add the result to the solutions.
-            solution_accumulator.add_solutions(S::cartesian_product(
-                concrete_tile_combinations_by_tile_value,
-            ));
+    let output = match parameters.output {
+        OutputFormat::Count if parameters.ordered => {
+            let sequences = scrabble1::<Sequences>(&parameters, tiles, wordlist, None)?;
+            parameters.progress.find(sequences.hands);
+            return Ok(Output::Sequences(sequences.sequences));
         }
-    } else {
-        // We need to clone the iterator here,
-        // because `peek` borrows the iterator
-        // and the borrow is still active
-        // at the point where we need the cloned iterator.
-        let mut tiles_for_value_iter_clone = tiles_for_value_iter.clone();
-
-        while let Some(tiles_for_value) = tiles_for_value_iter.peek() {
-            // If there are any tiles of that value left:
-            if tiles_for_value.number_of_abstract_tiles_drawn.get()
-                < tiles_for_value.number_of_tiles
-            {
-                // Draw any tile of that value.
-                tiles_for_value
-                    .number_of_abstract_tiles_drawn
-                    .set(tiles_for_value.number_of_abstract_tiles_drawn.get() + 1);
-
-                draw_abstract(
-                    parameters,
-                    tiles_by_value,
-                    solution_accumulator,
-                    tiles_for_value_iter_clone,
-                    tiles_drawn_so_far + 1,
-                );
-
-                // Put the tile back in the bag for the next draw.
-                tiles_for_value
-                    .number_of_abstract_tiles_drawn
-                    .set(tiles_for_value.number_of_abstract_tiles_drawn.get() - 1);
+        OutputFormat::Count => match parameters.blank_mode {
+            BlankMode::Tile => Output::Count(scrabble1::<SolutionCount>(
+                &parameters,
+                tiles,
+                wordlist,
+                None,
+            )?),
+            BlankMode::Wildcard => Output::Count(
+                scrabble1::<SolutionList>(&parameters, tiles, wordlist, None)?
+                    .iter()
+                    .map(|hand| wildcard_hands(hand))
+                    .sum(),
+            ),
+        },
+        OutputFormat::List => {
+            let mut hands =
+                scrabble1::<SolutionList>(&parameters, tiles, wordlist, parameters.max_solutions)?;
+            if let BlankMode::Wildcard = parameters.blank_mode {
+                hands = hands.iter().flat_map(|hand| expand_blanks(hand)).collect();
+                if let Some(max_solutions) = parameters.max_solutions {
+                    hands.truncate(max_solutions.get());
+                }
             }
 
-            tiles_for_value_iter.next();
-            tiles_for_value_iter_clone = tiles_for_value_iter.clone();
+            render_hands(&mut hands, tiles, &parameters);
+            Output::List(hands)
         }
-    }
-}
-
-/// For a given tile value,
-/// for each distinct letter,
-/// draw one tile of that letter,
-/// then recursively draw more tiles
-/// until we've reached the number of tiles
-/// that were drawn for the tile value.
-/// If we've reached the target number of tiles,
-/// add the partial solutions to `concrete_tile_combinations`.
-fn draw_concrete<'a, S>(
-    tiles_for_value: &TilesForValue<'_>,
-    concrete_tile_combinations: &mut S,
-    mut live_counted_tiles_iter: Peekable<impl Iterator<Item = &'a LiveCountedTile<'a>> + Clone>,
-    tiles_drawn_so_far: u32,
-) where
-    S: SolutionAccumulator,
-{
-    if tiles_drawn_so_far == tiles_for_value.number_of_abstract_tiles_drawn.get() {
-        concrete_tile_combinations.add_solution(|| {
-            tiles_for_value
-                .live_counted_tiles
+        OutputFormat::Probability => {
+            let draws = scrabble1::<Draws>(&parameters, tiles, None, None)?;
+            let total_tiles = tiles
                 .iter()
-                .flat_map(|live_counted_tile| {
-                    iter::repeat(live_counted_tile.counted_tile.tile.letter)
-                        .take(live_counted_tile.occurrences_drawn.get() as usize)
-                })
-                .collect()
-        });
-    } else {
-        // We need to clone the iterator here,
-        // because `peek` borrows the iterator
-        // and the borrow is still active
-        // at the point where we need the cloned iterator.
-        let mut live_counted_tiles_iter_clone = live_counted_tiles_iter.clone();
-
-        while let Some(live_counted_tile) = live_counted_tiles_iter.peek() {
-            if live_counted_tile.occurrences_drawn.get()
-                < live_counted_tile.counted_tile.occurrences
-            {
-                // Draw a tile of that letter.
-                live_counted_tile
-                    .occurrences_drawn
-                    .set(live_counted_tile.occurrences_drawn.get() + 1);
-
-                draw_concrete(
-                    tiles_for_value,
-                    concrete_tile_combinations,
-                    live_counted_tiles_iter_clone,
-                    tiles_drawn_so_far + 1,
-                );
+                .map(|counted_tile| counted_tile.occurrences)
+                .sum();
+            Output::Probability(Probability {
+                hands: draws.hands,
+                draws: draws.draws,
+                total_draws: binomial(total_tiles, parameters.hand_size()),
+            })
+        }
+        OutputFormat::Distribution => {
+            Output::Distribution(scrabble_distribution(&parameters, tiles)?)
+        }
+        OutputFormat::Unreachable => Output::Unreachable(scrabble_unreachable(&parameters, tiles)?),
+        OutputFormat::Statistics => unreachable!("the statistics are computed above"),
+        OutputFormat::ByValues => {
+            Output::ByValues(scrabble1::<ValueGroups>(&parameters, tiles, None, None)?)
+        }
+        OutputFormat::Bingos => Output::Bingos(scrabble_bingos(
+            &parameters,
+            tiles,
+            wordlist.expect("the bingos are only listed with a word list"),
+        )),
+        OutputFormat::ByScore => {
+            let mut hands_by_score = scrabble_by_score(&parameters, tiles)?;
+            if let BlankMode::Wildcard = parameters.blank_mode {
+                let mut solutions_left = parameters
+                    .max_solutions
+                    .map_or(usize::MAX, NonZeroUsize::get);
+                for hands in hands_by_score.values_mut() {
+                    *hands = hands
+                        .iter()
+                        .flat_map(|hand| expand_blanks(hand))
+                        .take(solutions_left)
+                        .collect();
+                    solutions_left -= hands.len();
+                }
+            }
 
-                // Put the tile back in the bag for the next draw.
-                live_counted_tile
-                    .occurrences_drawn
-                    .set(live_counted_tile.occurrences_drawn.get() - 1);
+            for hands in hands_by_score.values_mut() {
+                render_hands(hands, tiles, &parameters);
             }
 
-            live_counted_tiles_iter.next();
-            live_counted_tiles_iter_clone = live_counted_tiles_iter.clone();
+            Output::ByScore(hands_by_score)
         }
-    }
+    };
+
+    let number_of_hands = match &output {
+        Output::Count(count) => *count,
+        Output::Probability(probability) => probability.hands,
+        Output::Distribution(distribution) => distribution
+            .values()
+            .map(|probability| probability.hands)
+            .sum(),
+        Output::Unreachable(_) => 0,
+        Output::List(hands) => hands.len() as SolutionCount,
+        Output::Bingos(words) => words.len() as SolutionCount,
+        Output::ByValues(groups) => groups.iter().map(|group| group.hands).sum(),
+        Output::ByScore(hands_by_score) => hands_by_score
+            .values()
+            .map(|hands| hands.len() as SolutionCount)
+            .sum(),
+        Output::Editions(_) => unreachable!("the editions are compared above"),
+        Output::Deals(_) => unreachable!("the deals are dealt above"),
+        Output::Top(_) => unreachable!("the top hands are listed above"),
+        Output::Statistics(_) => unreachable!("the statistics are computed above"),
+        Output::Sequences(_) => unreachable!("the sequences are counted above"),
+        Output::ByHandSize(_) => unreachable!("the hand sizes are counted above"),
+    };
+    parameters.progress.find(number_of_hands);
+    Ok(output)
 }
 
-/// Parameters for solving variants of the Scrabble puzzle.
-#[derive(Debug, StructOpt)]
-pub struct Parameters {
-    /// The number of tiles in a hand.
-    #[structopt(short = "h", long, default_value = "7")]
-    hand_size: u32,
+/// The Scrabble® puzzle.
+#[derive(Clone, Copy, Debug)]
+pub struct Scrabble;
 
-    /// The target score for a hand.
-    #[structopt(short = "s", long, default_value = "46")]
-    target_score: u32,
+impl Solver for Scrabble {
+    const NAME: &'static str = "scrabble";
+    type Parameters = Parameters;
+    type Output = Output;
 
-    /// How the solution will be presented.
-    #[structopt(long, possible_values = &OutputFormat::variants(), case_insensitive = true, default_value = "count")]
-    output: OutputFormat,
-}
+    fn solve(parameters: Parameters) -> Result<Output, Error> {
+        scrabble(parameters)
+    }
 
-arg_enum! {
-    /// Choices for how the solution should be presented.
-    #[derive(Debug)]
-    pub enum OutputFormat {
-        Count,
-        List,
+    fn solve_to(parameters: Parameters, out: &mut dyn io::Write) -> Result<Output, Error> {
+        if parameters.is_streamed() {
+            write_sorted_hands(parameters, out).map(Output::Count)
+        } else {
+            scrabble(parameters)
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Output {
-    /// The number of valid hands.
-    Count(SolutionCount),
+/// Returns an iterator over the hands that have the target score,
+/// written like in the list output.
+///
+/// Unlike the list output,
+/// the hands are produced one combination of tile values at a time,
+/// so only the hands for the current combination are held in memory.
+/// For the same reason, the hands are not sorted.
+/// The `--output` option is ignored.
+/// The iterator stops after the maximum number of solutions, if one is set.
+pub fn iter_hands(mut parameters: Parameters) -> Result<impl Iterator<Item = String>, Error> {
+    let tiles = tiles(&parameters)?;
+    parameters.resolve_target_percent(&tiles);
+    parameters.resolve_score_range(&tiles);
+    parameters.validate(&tiles)?;
+    let wordlist = parameters.load_wordlist()?;
+    let shorter_words = parameters.shorter_words;
+    let blank_mode = parameters.blank_mode;
 
-    /// The full list of valid hands.
-    List(SolutionList),
-}
+    let tiles_by_value = constrained_tiles_by_value(&parameters, &tiles);
+    let values: Vec<_> = tiles_by_value
+        .iter()
+        .map(|tiles_for_value| tiles_for_value.value)
+        .collect();
+    let number_of_tiles: Vec<_> = tiles_by_value
+        .iter()
+        .map(|tiles_for_value| tiles_for_value.number_of_tiles)
+        .collect();
+    let required_tiles: Vec<_> = tiles_by_value
+        .iter()
+        .map(TilesForValue::required_tiles)
+        .collect();
+    let interrupt = parameters.interrupt.clone();
+    let progress = parameters.progress.clone();
+    let found = progress.clone();
+    let scores = parameters.score_range();
+    // The hands written as their tiles are left as drawn.
+    let letters_only = parameters.letters_only && !parameters.structured;
+    let hand_format = if parameters.structured {
+        HandFormat::Plain
+    } else {
+        parameters.hand_format
+    };
+    let shown_values = (parameters.show_values && !parameters.structured)
+        .then(|| LetterValues::from_tiles(&tiles));
+    let shard = parameters.shard;
+    let max_solutions = parameters
+        .max_solutions
+        .map_or(usize::MAX, NonZeroUsize::get);
 
-impl Display for Output {
-    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Count(count) => {
-                write!(fmt, "{}", count)?;
+    // Draw the tile values in the same order as `draw_abstract`:
+    // each combination lists the indices of the drawn tile values
+    // in increasing order.
+    let number_of_values = tiles_by_value.len();
+    progress.start(multisets(number_of_values, parameters.hand_size()));
+    Ok((0..number_of_values)
+        .combinations_with_replacement(parameters.hand_size() as usize)
+        .take_while(move |_| !interrupt.is_triggered())
+        .inspect(move |_| {
+            progress.advance(1);
+            progress.explore(1);
+        })
+        .filter(move |tile_value_indices| {
+            // The first index is the lowest tile value, like the first draw of `draw_abstract`.
+            match (shard, tile_value_indices.first()) {
+                (Some(shard), Some(&first)) => shard.contains(first),
+                _ => true,
             }
-
-            Self::List(list) => {
-                let mut iter = list.iter();
-                if let Some(item) = iter.next() {
-                    write!(fmt, "{}", item)?;
-                    for item in iter {
-                        writeln!(fmt)?;
-                        write!(fmt, "{}", item)?;
-                    }
-                }
+        })
+        .map(move |tile_value_indices| {
+            let mut abstract_tiles_drawn = vec![0; number_of_values];
+            for tile_value_index in tile_value_indices {
+                abstract_tiles_drawn[tile_value_index] += 1;
             }
-        }
-
-        Ok(())
-    }
+            abstract_tiles_drawn
+        })
+        .filter(move |abstract_tiles_drawn| {
+            abstract_tiles_drawn
+                .iter()
+                .zip(&number_of_tiles)
+                .zip(&required_tiles)
+                .all(|((tiles_drawn, number_of_tiles), required_tiles)| {
+                    required_tiles <= tiles_drawn && tiles_drawn <= number_of_tiles
+                })
+        })
+        .filter(move |abstract_tiles_drawn| {
+            let hand_score: u32 = abstract_tiles_drawn
+                .iter()
+                .zip(&values)
+                .map(|(tiles_drawn, value)| tiles_drawn * value)
+                .sum();
+            scores.contains(&hand_score)
+        })
+        .flat_map(move |abstract_tiles_drawn| {
+            // The hands are produced one combination at a time, so their size isn't limited.
+            concrete_hands::<SolutionList>(&tiles_by_value, &abstract_tiles_drawn, None)
+                .expect("the size of the hands is not limited")
+        })
+        .filter(move |hand| {
+            wordlist
+                .as_ref()
+                .is_none_or(|wordlist| wordlist.can_form(hand, shorter_words))
+        })
+        .flat_map(move |hand| match blank_mode {
+            BlankMode::Tile => vec![hand],
+            BlankMode::Wildcard => expand_blanks(&hand),
+        })
+        .map(move |hand| render_listed_hand(hand, letters_only, hand_format, shown_values.as_ref()))
+        .take(max_solutions)
+        .inspect(move |_| found.find(1)))
 }
 
-macro_rules! tiles {
-    ($($letter:tt x $occurrences:tt, value $value:tt)*) => {
-        [
-            $(
-                CountedTile {
-                    tile: Tile {
-                        letter: $letter,
-                        value: $value,
-                    },
-                    occurrences: $occurrences,
-                },
-            )*
-        ]
+/// Returns an iterator over the hands that have the target score,
+/// written like in the list output and in the same order.
+///
+/// Unlike `iter_hands`,
+/// the hands are drawn one tile at a time in alphabetical order,
+/// so they come out sorted without being collected first,
+/// and only the hand being drawn is held in memory.
+/// The `--output` option is ignored.
+/// The iterator stops after the maximum number of solutions, if one is set,
+/// so the hands are the first ones of the sorted list.
+pub fn iter_sorted_hands(
+    mut parameters: Parameters,
+) -> Result<impl Iterator<Item = String>, Error> {
+    let tiles = tiles(&parameters)?;
+    parameters.resolve_target_percent(&tiles);
+    parameters.resolve_score_range(&tiles);
+    parameters.validate(&tiles)?;
+    let wordlist = parameters.load_wordlist()?;
+    let shorter_words = parameters.shorter_words;
+    let blank_mode = parameters.blank_mode;
+    // The hands written as their tiles are left as drawn.
+    let letters_only = parameters.letters_only && !parameters.structured;
+    let hand_format = if parameters.structured {
+        HandFormat::Plain
+    } else {
+        parameters.hand_format
     };
+    let shown_values = (parameters.show_values && !parameters.structured)
+        .then(|| LetterValues::from_tiles(&tiles));
+    let progress = parameters.progress.clone();
+    let max_solutions = parameters
+        .max_solutions
+        .map_or(usize::MAX, NonZeroUsize::get);
+
+    Ok(SortedHands::new(&parameters, &tiles)
+        .filter(move |hand| {
+            wordlist
+                .as_ref()
+                .is_none_or(|wordlist| wordlist.can_form(hand, shorter_words))
+        })
+        .flat_map(move |hand| match blank_mode {
+            BlankMode::Tile => vec![hand],
+            BlankMode::Wildcard => expand_blanks(&hand),
+        })
+        .map(move |hand| render_listed_hand(hand, letters_only, hand_format, shown_values.as_ref()))
+        .take(max_solutions)
+        .inspect(move |_| progress.find(1)))
 }
 
-/// The distribution of tiles in a standard English edition of Scrabble®.
-static STANDARD_ENGLISH_SCRABBLE_TILES: &[CountedTile] = &tiles![
-    ' ' x  2, value 0
-    'A' x  9, value 1
-    'B' x  2, value 3
-    'C' x  2, value 3
-    'D' x  4, value 2
-    'E' x 12, value 1
-    'F' x  2, value 4
-    'G' x  3, value 2
-    'H' x  2, value 4
-    'I' x  9, value 1
-    'J' x  1, value 8
-    'K' x  1, value 5
-    'L' x  4, value 1
-    'M' x  2, value 3
-    'N' x  6, value 1
-    'O' x  8, value 1
-    'P' x  2, value 3
-    'Q' x  1, value 10
-    'R' x  6, value 1
-    'S' x  4, value 1
-    'T' x  6, value 1
-    'U' x  4, value 1
-    'V' x  2, value 4
-    'W' x  2, value 4
-    'X' x  1, value 8
-    'Y' x  2, value 4
-    'Z' x  1, value 10
-];
+/// Writes the hands that have the target score to `out`, one per line,
+/// as they are produced by `iter_sorted_hands`,
+/// and returns the number of hands.
+///
+/// This is the list output of `--stream`,
+/// so the hands are colored like in the list output.
+/// `out` is flushed after each hand, so that it shows up as soon as it is found.
+pub fn write_sorted_hands(
+    parameters: Parameters,
+    mut out: impl io::Write,
+) -> Result<SolutionCount, Error> {
+    let tiles = tiles(&parameters)?.into_owned();
+    let color_parameters = parameters.color.then(|| parameters.clone());
+    let mut count = 0;
+    for hand in iter_sorted_hands(parameters)? {
+        let mut hands = vec![hand];
+        if let Some(parameters) = &color_parameters {
+            apply_colors(&mut hands, &tiles, parameters);
+        }
 
-/// A tile from the Scrabble board game.
+        writeln!(out, "{}", hands[0])?;
+        out.flush()?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// A tile that `SortedHands` can draw.
 #[derive(Debug)]
-struct Tile {
-    /// The letter on the tile (or a space for blank tiles).
+struct SortedTile {
+    /// The letter of the tile.
     letter: char,
 
-    /// The point value of the tile
-    /// in the English edition of Scrabble.
+    /// The value of the tile.
     value: u32,
-}
 
-/// A tile along with the number of copies of that tile
-/// in the English edition of Scrabble.
-#[derive(Debug)]
-struct CountedTile {
-    /// The attributes of a tile.
-    tile: Tile,
+    /// The index of the tile value in the tiles grouped by value,
+    /// which is what `--shard` splits the search by.
+    value_index: usize,
 
-    /// The number of occurrences of that tile.
+    /// The number of tiles with the letter in the bag.
     occurrences: u32,
+
+    /// The number of tiles with the letter that the hands must have.
+    required_occurrences: u32,
 }
 
-/// Aggregates all the tiles for a particular tile value.
-#[derive(Debug, Default)]
-struct TilesForValue<'a> {
-    /// The list of `LiveCountedTile`s with the tile value.
-    live_counted_tiles: Vec<LiveCountedTile<'a>>,
+/// An iterator over the hands with a score in the range of the parameters,
+/// in alphabetical order.
+///
+/// A hand is written with its tiles by increasing value,
+/// and the tiles with the same value in alphabetical order,
+/// like `concrete_hands` writes it.
+/// Since the hands all have the same number of tiles,
+/// drawing the tiles of each position in alphabetical order
+/// among the tiles that can come after the previous one
+/// produces the hands in alphabetical order.
+/// The draws that can't reach the range of scores are pruned.
+struct SortedHands {
+    /// The tiles that can be drawn, by increasing value and then by letter.
+    tiles: Vec<SortedTile>,
 
-    /// The total number of tiles with the tile value.
-    number_of_tiles: u32,
+    /// The indices of `tiles` in alphabetical order of the letters.
+    alphabetical: Vec<usize>,
 
-    /// The number of tiles (no matter the letter) that have been drawn
-    /// at the current point in the algorithm.
-    number_of_abstract_tiles_drawn: Cell<u32>,
-}
+    /// The number of tiles in a hand.
+    hand_size: usize,
 
-/// A `CountedTile` paired with how many of them have currently been drawn.
-#[derive(Debug)]
-struct LiveCountedTile<'a> {
-    /// A reference to a `CountedTile`.
-    counted_tile: &'a CountedTile,
+    /// The scores of the hands to produce.
+    scores: RangeInclusive<u32>,
 
-    /// The number of tiles that have been drawn
-    /// at the current point in the algorithm.
-    occurrences_drawn: Cell<u32>,
-}
+    /// The part of the search to do, by the value of the first tile.
+    shard: Option<Shard>,
 
-/// Implemented for types
-/// that can accumulate solutions as they are found
-/// and that represent possible output formats for the solution.
-trait SolutionAccumulator: Sized {
-    /// Initializes a new accumulator.
-    fn new() -> Self;
+    /// Whether some tiles must be in the hands, with `--include`.
+    has_requirements: bool,
 
-    /// Adds a solution to this accumulator.
-    ///
-    /// The solution is generated lazily
-    /// because some accumulators don't care about the particular value.
-    fn add_solution(&mut self, solution_fn: impl Fn() -> String);
+    /// The indices in `tiles` of the tiles drawn so far.
+    hand: Vec<usize>,
 
-    /// Adds all the solutions from another accumulator to this accumulator.
-    fn add_solutions(&mut self, other: Self);
+    /// The number of tiles drawn so far for each entry in `tiles`.
+    occurrences_drawn: Vec<u32>,
 
-    /// Performs the cartesian product
-    /// of partial solutions by tile value
-    /// to generate final solutions.
-    fn cartesian_product(solutions_by_value: Vec<Self>) -> Self;
+    /// The score of the tiles drawn so far.
+    score: u32,
 
-    /// Transforms the accumulated solutions for presentation.
-    fn finish(&mut self) {}
+    /// For each position up to the next tile to draw,
+    /// the position in `alphabetical` of the next tile to try there.
+    next_tiles: Vec<usize>,
+
+    /// Stops the iterator when triggered.
+    interrupt: Interrupt,
+
+    /// Reports how many tiles were tried for the first position.
+    progress: Progress,
 }
 
-/// A `SolutionAccumulator` that simply counts the number of solutions.
-///
-/// This is more efficient than actually listing the solutions
-/// because the `cartesian_product` can be implemented
-/// as simply the product of the solution counts.
-type SolutionCount = u64;
+impl SortedHands {
+    fn new(parameters: &Parameters, tiles: &[CountedTile]) -> Self {
+        let tiles: Vec<_> = constrained_tiles_by_value(parameters, tiles)
+            .into_iter()
+            .enumerate()
+            .flat_map(|(value_index, tiles_for_value)| {
+                let value = tiles_for_value.value;
+                tiles_for_value
+                    .counted_tiles
+                    .into_iter()
+                    .zip(tiles_for_value.required_occurrences)
+                    .map(move |(counted_tile, required_occurrences)| SortedTile {
+                        letter: counted_tile.tile.letter,
+                        value,
+                        value_index,
+                        occurrences: counted_tile.occurrences,
+                        required_occurrences,
+                    })
+            })
+            .collect();
+        let alphabetical = (0..tiles.len())
+            .sorted_by_key(|&index| tiles[index].letter)
+            .collect();
 
-impl SolutionAccumulator for SolutionCount {
-    fn new() -> Self {
-        0
+        parameters.progress.start(tiles.len() as u64);
+        SortedHands {
+            occurrences_drawn: vec![0; tiles.len()],
+            tiles,
+            alphabetical,
+            hand_size: parameters.hand_size() as usize,
+            scores: parameters.score_range(),
+            shard: parameters.shard,
+            has_requirements: !parameters.include.is_empty(),
+            hand: vec![],
+            score: 0,
+            next_tiles: vec![0],
+            interrupt: parameters.interrupt.clone(),
+            progress: parameters.progress.clone(),
+        }
+    }
+
+    /// Returns whether the hand drawn so far can be completed into a hand to produce
+    /// after drawing the tile at `index` in `tiles`.
+    fn can_draw(&self, index: usize) -> bool {
+        let tile = &self.tiles[index];
+        if self.hand.last().is_some_and(|&last| last > index)
+            || self.occurrences_drawn[index] == tile.occurrences
+        {
+            return false;
+        }
+
+        if self.hand.is_empty() {
+            if let Some(shard) = self.shard {
+                if !shard.contains(tile.value_index) {
+                    return false;
+                }
+            }
+        }
+
+        // Only this tile and the ones after it can fill the rest of the hand.
+        let tiles_left = self.hand_size - self.hand.len() - 1;
+        let available = |later: usize| {
+            let drawn = self.occurrences_drawn[later] + u32::from(later == index);
+            (self.tiles[later].occurrences - drawn) as usize
+        };
+
+        if self.has_requirements {
+            // The tiles before this one can't be drawn anymore,
+            // so they must already be in the hand as many times as required.
+            if (0..index).any(|earlier| {
+                self.occurrences_drawn[earlier] < self.tiles[earlier].required_occurrences
+            }) {
+                return false;
+            }
+
+            let required: u32 = (index..self.tiles.len())
+                .map(|later| {
+                    let drawn = self.occurrences_drawn[later] + u32::from(later == index);
+                    self.tiles[later].required_occurrences.saturating_sub(drawn)
+                })
+                .sum();
+            if required as usize > tiles_left {
+                return false;
+            }
+        }
+
+        // The tiles are by increasing value,
+        // so the lowest and highest scores of the rest of the hand
+        // come from the first and last tiles that are left.
+        let score_of = |indices: &mut dyn Iterator<Item = usize>| {
+            let mut tiles_to_draw = tiles_left;
+            let mut score = 0;
+            for later in indices {
+                if tiles_to_draw == 0 {
+                    break;
+                }
+
+                let drawn = available(later).min(tiles_to_draw);
+                score += self.tiles[later].value * drawn as u32;
+                tiles_to_draw -= drawn;
+            }
+            (tiles_to_draw == 0).then_some(score)
+        };
+        let score = self.score + tile.value;
+        match (
+            score_of(&mut (index..self.tiles.len())),
+            score_of(&mut (index..self.tiles.len()).rev()),
+        ) {
+            (Some(lowest), Some(highest)) => {
+                score + lowest <= *self.scores.end() && score + highest >= *self.scores.start()
+            }
+            _ => false,
+        }
+    }
+
+    /// Draws the tile at `index` in `tiles`.
+    fn draw(&mut self, index: usize) {
+        self.hand.push(index);
+        self.occurrences_drawn[index] += 1;
+        self.score += self.tiles[index].value;
+    }
+
+    /// Puts the last tile drawn back in the bag.
+    fn put_back(&mut self) {
+        let index = self.hand.pop().expect("no tile to put back");
+        self.occurrences_drawn[index] -= 1;
+        self.score -= self.tiles[index].value;
+    }
+}
+
+impl Iterator for SortedHands {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some(next_tile) = self.next_tiles.last_mut() {
+            if self.interrupt.is_triggered() {
+                return None;
+            }
+
+            let first_position = self.hand.is_empty();
+            let start = *next_tile;
+            let found = (start..self.alphabetical.len())
+                .find(|&position| self.can_draw(self.alphabetical[position]));
+            if first_position {
+                let tried = found.map_or(self.alphabetical.len(), |position| position + 1);
+                self.progress.advance((tried - start) as u64);
+            }
+
+            let position = match found {
+                Some(position) => position,
+                None => {
+                    // Every tile was tried at this position.
+                    self.next_tiles.pop();
+                    if !self.hand.is_empty() {
+                        self.put_back();
+                    }
+                    continue;
+                }
+            };
+
+            *self.next_tiles.last_mut().expect("no position to draw") = position + 1;
+            self.progress.explore(1);
+            self.draw(self.alphabetical[position]);
+            if self.hand.len() < self.hand_size {
+                self.next_tiles.push(0);
+                continue;
+            }
+
+            let hand: String = self
+                .hand
+                .iter()
+                .map(|&index| self.tiles[index].letter)
+                .collect();
+            let complete = self.scores.contains(&self.score)
+                && self
+                    .tiles
+                    .iter()
+                    .zip(&self.occurrences_drawn)
+                    .all(|(tile, &drawn)| drawn >= tile.required_occurrences);
+            self.put_back();
+            if complete {
+                return Some(hand);
+            }
+        }
+
+        None
+    }
+}
+
+/// Renders a hand of the list output
+/// without the colors, which depend on the tile values.
+///
+/// The hand is rendered with `render_values` if `shown_values` is set.
+fn render_listed_hand(
+    hand: String,
+    letters_only: bool,
+    hand_format: HandFormat,
+    shown_values: Option<&LetterValues>,
+) -> String {
+    if let Some(letter_values) = shown_values {
+        return render_values(&hand, letter_values);
+    }
+
+    let hand = if letters_only {
+        render_letters_only(&hand)
+    } else {
+        hand
+    };
+    match hand_format {
+        HandFormat::Plain => hand,
+        _ => render_hand(&hand, hand_format),
+    }
+}
+
+/// Writes the hands that have the target score to `out`, one per line,
+/// as they are produced by `iter_hands`,
+/// and returns the number of hands.
+///
+/// `out` is buffered by this function.
+pub fn write_hands(parameters: Parameters, out: impl io::Write) -> Result<SolutionCount, Error> {
+    let mut out = BufWriter::new(out);
+    let mut count = 0;
+    for hand in iter_hands(parameters)? {
+        writeln!(out, "{}", hand)?;
+        count += 1;
+    }
+    out.flush()?;
+    Ok(count)
+}
+
+/// Determines which letters appear in all, some or none
+/// of the hands that have the target score.
+pub fn analyze(mut parameters: Parameters) -> Result<LetterAnalysis, Error> {
+    let tiles = &*tiles(&parameters)?;
+    parameters.resolve_target_percent(tiles);
+    parameters.resolve_score_range(tiles);
+    parameters.validate(tiles)?;
+    let wordlist = parameters.load_wordlist()?;
+
+    let hands = scrabble1::<SolutionList>(&parameters, tiles, wordlist.as_deref(), None)?;
+    let letters_by_hand: Vec<BTreeSet<char>> =
+        hands.iter().map(|hand| hand.chars().collect()).collect();
+
+    // The forced letters are in the intersection of the hands' letters,
+    // and the possible letters are in their union.
+    let forced = match letters_by_hand.split_first() {
+        Some((first, rest)) => rest.iter().fold(first.clone(), |forced, letters| {
+            forced.intersection(letters).cloned().collect()
+        }),
+        None => BTreeSet::new(),
+    };
+    let possible: BTreeSet<char> = letters_by_hand.iter().flatten().cloned().collect();
+
+    Ok(LetterAnalysis {
+        some: possible.difference(&forced).cloned().collect(),
+        none: tiles
+            .iter()
+            .map(|counted_tile| counted_tile.tile.letter)
+            .filter(|letter| !possible.contains(letter))
+            .collect(),
+        forced,
+    })
+}
+
+/// Draws `samples` random hands from the bag of tiles
+/// and counts the ones that have the target score,
+/// which estimates the probability of drawing such a hand.
+///
+/// Unlike the count of distinct hands,
+/// each tile is drawn as a separate tile,
+/// so hands made of more common letters are drawn more often.
+/// The output options, the score range and `--at-least` are ignored.
+/// The sampling stops early if it is interrupted.
+pub fn simulate(
+    mut parameters: Parameters,
+    samples: NonZeroUsize,
+    rng: &mut impl Rng,
+) -> Result<Simulation, Error> {
+    if parameters.hand_size.is_range() {
+        return Err(ParameterError::HandSizeRange("a simulation").into());
+    }
+
+    let tiles = &*tiles(&parameters)?;
+    parameters.resolve_target_percent(tiles);
+    parameters.validate(tiles)?;
+
+    // The value of every tile in the bag.
+    let bag: Vec<u32> = tiles
+        .iter()
+        .flat_map(|counted_tile| {
+            iter::repeat_n(counted_tile.tile.value, counted_tile.occurrences as usize)
+        })
+        .collect();
+
+    parameters.progress.start(samples.get() as u64);
+    let mut simulation = Simulation {
+        samples: 0,
+        hits: 0,
+        target_score: parameters.target_score,
+    };
+    while simulation.samples < samples.get() && !parameters.interrupt.is_triggered() {
+        let score: u32 = index::sample(rng, bag.len(), parameters.hand_size() as usize)
+            .iter()
+            .map(|tile_index| bag[tile_index])
+            .sum();
+        if score == parameters.target_score {
+            simulation.hits += 1;
+        }
+
+        simulation.samples += 1;
+        parameters.progress.advance(1);
+    }
+
+    debug!(
+        samples = simulation.samples,
+        hits = simulation.hits,
+        "simulation finished"
+    );
+    Ok(simulation)
+}
+
+/// Returns the tile distribution from the tiles file if one was given,
+/// or the edition's distribution otherwise,
+/// with as many sets of each letter as `--bags` gives
+/// and without the tiles of `--removed`.
+fn tiles(parameters: &Parameters) -> Result<Cow<'static, [CountedTile]>, TilesError> {
+    let tiles = match (&parameters.tiles, &parameters.cache) {
+        (Some(path), Some(cache)) => {
+            let mut files = cache.lock();
+            Cow::Owned(cached(&mut files.tiles, path, load_tiles)?)
+        }
+        (Some(path), None) => Cow::Owned(load_tiles(path)?),
+        (None, _) => Cow::Borrowed(parameters.edition.tiles()),
+    };
+    parameters.adjust_tiles(tiles)
+}
+
+/// Replaces the point value of each letter of `values` (a space for a blank tile)
+/// in the distribution.
+fn override_values(
+    tiles: Cow<'static, [CountedTile]>,
+    values: &[(char, u32)],
+) -> Result<Cow<'static, [CountedTile]>, TilesError> {
+    if values.is_empty() {
+        return Ok(tiles);
+    }
+
+    let mut tiles = tiles.into_owned();
+    for &(letter, value) in values {
+        // A letter given more than once gets its last value.
+        let counted_tile = tiles
+            .iter_mut()
+            .find(|counted_tile| counted_tile.tile.letter == letter)
+            .ok_or(TilesError::UnknownValueLetter(letter))?;
+        counted_tile.tile.value = value;
+    }
+
+    Ok(Cow::Owned(tiles))
+}
+
+/// Keeps a single tile of each letter of the distribution,
+/// so that every search draws each letter at most once.
+fn distinct_letters(tiles: Cow<'static, [CountedTile]>) -> Cow<'static, [CountedTile]> {
+    if tiles
+        .iter()
+        .all(|counted_tile| counted_tile.occurrences <= 1)
+    {
+        return tiles;
+    }
+
+    let mut tiles = tiles.into_owned();
+    for counted_tile in &mut tiles {
+        counted_tile.occurrences = counted_tile.occurrences.min(1);
+    }
+
+    Cow::Owned(tiles)
+}
+
+/// Takes the tiles with the letters of `removed` (`_` for a blank tile) out of the distribution.
+fn remove_tiles(
+    tiles: Cow<'static, [CountedTile]>,
+    removed: &str,
+) -> Result<Cow<'static, [CountedTile]>, TilesError> {
+    let mut tiles = tiles.into_owned();
+    let removed = removed
+        .chars()
+        .filter(|letter| !letter.is_whitespace())
+        .map(|letter| match letter {
+            '_' => ' ',
+            letter => letter.to_ascii_uppercase(),
+        })
+        .sorted()
+        .group_by(|&letter| letter);
+    for (letter, group) in &removed {
+        let counted_tile = tiles
+            .iter_mut()
+            .find(|counted_tile| counted_tile.tile.letter == letter)
+            .ok_or(TilesError::UnknownRemovedLetter(letter))?;
+        let occurrences = group.count() as u32;
+        if occurrences > counted_tile.occurrences {
+            return Err(TilesError::TooManyRemoved(
+                letter,
+                occurrences,
+                counted_tile.occurrences,
+            ));
+        }
+
+        counted_tile.occurrences -= occurrences;
+    }
+
+    Ok(Cow::Owned(tiles))
+}
+
+/// Counts or lists the ways to deal a hand with each score of `--deal` from the bag.
+fn scrabble_deals(parameters: &Parameters, tiles: &[CountedTile]) -> Result<Output, Error> {
+    // The searches for the hands of the players after the first one are not reported.
+    let inner_parameters = Parameters {
+        progress: Progress::default(),
+        ..parameters.clone()
+    };
+
+    let output = match parameters.output {
+        OutputFormat::List => {
+            let mut deals = vec![];
+            // The list stops early once it has the maximum number of solutions.
+            let _ = list_deals(
+                parameters,
+                &inner_parameters,
+                tiles,
+                &parameters.deal,
+                &mut vec![],
+                &mut deals,
+            )?;
+
+            for hands in &mut deals {
+                render_hands(hands, tiles, parameters);
+            }
+
+            Output::Deals(deals)
+        }
+        _ => Output::Count(count_deals(
+            parameters,
+            &inner_parameters,
+            tiles,
+            &parameters.deal,
+        )?),
+    };
+
+    parameters.progress.find(match &output {
+        Output::Count(count) => *count,
+        Output::Deals(deals) => deals.len() as SolutionCount,
+        _ => unreachable!("the deals are counted or listed"),
+    });
+    Ok(output)
+}
+
+/// Counts the ways to deal a hand with each of the `scores` from the bag,
+/// the first hand being dealt first.
+///
+/// The search for the first hand is done with `parameters`,
+/// and the searches for the other hands with `inner_parameters`.
+fn count_deals(
+    parameters: &Parameters,
+    inner_parameters: &Parameters,
+    tiles: &[CountedTile],
+    scores: &[u32],
+) -> Result<SolutionCount, Error> {
+    let (&score, other_scores) = scores.split_first().expect("no scores to deal");
+    if other_scores.is_empty() {
+        let mut count = 0;
+        search(
+            parameters,
+            tiles,
+            score..=score,
+            &mut |_, solutions: SolutionCount| {
+                count += solutions;
+                Ok(ControlFlow::Continue(()))
+            },
+        )?;
+        return Ok(count);
+    }
+
+    let mut count = 0;
+    for hand in hands_with_score(parameters, tiles, score)? {
+        if parameters.interrupt.is_triggered() {
+            break;
+        }
+
+        let tiles = without_hand(tiles, &hand);
+        count += count_deals(inner_parameters, inner_parameters, &tiles, other_scores)?;
+    }
+
+    Ok(count)
+}
+
+/// Lists the ways to deal a hand with each of the `scores` from the bag
+/// after the hands of `deal`, adding them to `deals`.
+///
+/// Returns `Break` once the maximum number of solutions is reached.
+fn list_deals(
+    parameters: &Parameters,
+    inner_parameters: &Parameters,
+    tiles: &[CountedTile],
+    scores: &[u32],
+    deal: &mut Vec<String>,
+    deals: &mut Vec<SolutionList>,
+) -> Result<ControlFlow<()>, Error> {
+    let (&score, other_scores) = scores.split_first().expect("no scores to deal");
+    for hand in hands_with_score(parameters, tiles, score)? {
+        if parameters.interrupt.is_triggered() {
+            break;
+        }
+
+        let tiles = without_hand(tiles, &hand);
+        deal.push(hand);
+        let flow = if other_scores.is_empty() {
+            deals.push(deal.clone());
+            match parameters.max_solutions {
+                Some(max_solutions) if deals.len() >= max_solutions.get() => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        } else {
+            list_deals(
+                inner_parameters,
+                inner_parameters,
+                &tiles,
+                other_scores,
+                deal,
+                deals,
+            )?
+        };
+        deal.pop();
+
+        if flow.is_break() {
+            return Ok(flow);
+        }
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Lists the hands with the given score.
+fn hands_with_score(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    score: u32,
+) -> Result<SolutionList, Error> {
+    let mut hands = SolutionList::new();
+    search(
+        parameters,
+        tiles,
+        score..=score,
+        &mut |_, solutions: SolutionList| {
+            hands.add_solutions(solutions);
+            Ok(ControlFlow::Continue(()))
+        },
+    )?;
+    hands.finish();
+    Ok(hands)
+}
+
+/// Returns the tile distribution left after dealing `hand`,
+/// which must have been drawn from `tiles`.
+fn without_hand(tiles: &[CountedTile], hand: &str) -> Vec<CountedTile> {
+    let mut tiles = tiles.to_vec();
+    for letter in hand.chars() {
+        let counted_tile = tiles
+            .iter_mut()
+            .find(|counted_tile| counted_tile.tile.letter == letter)
+            .expect("the hand was drawn from the tiles");
+        counted_tile.occurrences -= 1;
+    }
+
+    tiles
+}
+
+/// Produces the solution to the Scrabble® puzzle in the specified output format.
+///
+/// The search stops once `max_solutions` solutions are found.
+///
+/// With a word list, only the hands that form a word are kept,
+/// so the hands of each combination of tile values are listed to be checked.
+fn scrabble1<S>(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    wordlist: Option<&Wordlist>,
+    max_solutions: Option<NonZeroUsize>,
+) -> Result<S, Error>
+where
+    S: SolutionAccumulator,
+{
+    let mut solution_accumulator = S::new();
+    let mut len_in_bytes = 0;
+    let mut solutions_found = 0;
+
+    let mut add_solutions = |mut solutions: S| {
+        let flow = limit_solutions(&mut solutions, &mut solutions_found, max_solutions);
+        len_in_bytes += solutions.len_in_bytes();
+        check_max_list_bytes(parameters.max_list_bytes, len_in_bytes)?;
+        solution_accumulator.add_solutions(solutions);
+        Ok(flow)
+    };
+
+    match wordlist {
+        None => search(
+            parameters,
+            tiles,
+            parameters.score_range(),
+            &mut |_, solutions: S| add_solutions(solutions),
+        )?,
+        Some(wordlist) => search(
+            parameters,
+            tiles,
+            parameters.score_range(),
+            &mut |_, hands: SolutionList| {
+                let mut solutions = S::new();
+                for hand in hands
+                    .iter()
+                    .filter(|hand| wordlist.can_form(hand, parameters.shorter_words))
+                {
+                    solutions.add_solution(|| hand.clone());
+                }
+
+                add_solutions(solutions)
+            },
+        )?,
+    }
+
+    solution_accumulator.finish();
+
+    Ok(solution_accumulator)
+}
+
+/// Counts the hands with a score in the range for each hand size of the range of hand sizes.
+///
+/// Instead of searching the hands of each size separately,
+/// the counts for every hand size and score are built at once
+/// by `multiply_generating_functions`.
+fn count_by_hand_size(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+) -> BTreeMap<u32, SolutionCount> {
+    let hand_sizes = parameters.hand_size.first..=parameters.hand_size.last;
+    let scores = parameters.score_range();
+
+    let _span = debug_span!("scrabble", hand_sizes = ?hand_sizes, scores = ?scores).entered();
+    debug!(letters = tiles.len(), "counting the hands of each size");
+
+    // The scores above the highest score of the largest hands have no hands,
+    // so the coefficients stop there.
+    let counts = multiply_generating_functions(
+        parameters,
+        tiles,
+        parameters.hand_size.last,
+        *parameters.reachable_score_range(tiles).end(),
+        |_, _| 1,
+    );
+    hand_sizes
+        .map(|hand_size| {
+            let count = coefficient(&counts[hand_size as usize], &scores);
+            (
+                hand_size,
+                SolutionCount::try_from(count).unwrap_or(SolutionCount::MAX),
+            )
+        })
+        .collect()
+}
+
+/// Counts the hands of the hand size with a score in the range,
+/// or their probability,
+/// with `multiply_generating_functions` instead of a search.
+fn scrabble_generating_function(parameters: &Parameters, tiles: &[CountedTile]) -> Output {
+    let hand_size = parameters.hand_size();
+    let scores = parameters.score_range();
+
+    let _span = debug_span!("scrabble", hand_size, scores = ?scores).entered();
+    debug!(
+        letters = tiles.len(),
+        "multiplying the generating functions of the letters"
+    );
+
+    let count = |weight: fn(u32, u32) -> u128| {
+        let coefficients =
+            multiply_generating_functions(parameters, tiles, hand_size, *scores.end(), weight);
+        coefficient(&coefficients[hand_size as usize], &scores)
+    };
+    let hands = SolutionCount::try_from(count(|_, _| 1)).unwrap_or(SolutionCount::MAX);
+    match parameters.output {
+        OutputFormat::Probability => {
+            let total_tiles = tiles
+                .iter()
+                .map(|counted_tile| counted_tile.occurrences)
+                .sum();
+            Output::Probability(Probability {
+                hands,
+                draws: count(binomial),
+                total_draws: binomial(total_tiles, hand_size),
+            })
+        }
+        _ => Output::Count(hands),
+    }
+}
+
+/// Counts the hands of the hand size with a score in the range,
+/// or their probability,
+/// with `count_memoized` instead of a search.
+fn scrabble_memoized(parameters: &Parameters, tiles: &[CountedTile]) -> Output {
+    let tiles_by_value = constrained_tiles_by_value(parameters, tiles);
+
+    // Each score is counted separately, so only the scores that a hand can have are counted.
+    let scores = parameters.reachable_score_range(tiles);
+
+    let _span =
+        debug_span!("scrabble", hand_size = parameters.hand_size(), scores = ?scores).entered();
+    debug!(
+        tile_values = tiles_by_value.len(),
+        "counting the hands with memoized subproblems"
+    );
+
+    let hands = count_memoized(parameters, &tiles_by_value, &scores, value_hands);
+    let hands = SolutionCount::try_from(hands).unwrap_or(SolutionCount::MAX);
+    match parameters.output {
+        OutputFormat::Probability => {
+            let total_tiles = tiles
+                .iter()
+                .map(|counted_tile| counted_tile.occurrences)
+                .sum();
+            Output::Probability(Probability {
+                hands,
+                draws: count_memoized(parameters, &tiles_by_value, &scores, value_draws),
+                total_draws: binomial(total_tiles, parameters.hand_size()),
+            })
+        }
+        _ => Output::Count(hands),
+    }
+}
+
+/// Counts the hands of the hand size with a score in `scores`
+/// by drawing each tile value in turn like `draw_abstract`,
+/// but remembering the count for each tile value, number of tiles left to draw
+/// and score left to reach,
+/// since many combinations of the lower tile values leave the same subproblem.
+///
+/// `weight` returns the count for drawing a number of tiles of a tile value,
+/// which the counts of the tile values of a hand are multiplied by.
+fn count_memoized(
+    parameters: &Parameters,
+    tiles_by_value: &[TilesForValue],
+    scores: &RangeInclusive<u32>,
+    weight: impl Fn(&TilesForValue, u32) -> u128,
+) -> u128 {
+    let hand_size = parameters.hand_size();
+
+    // The weight of each number of tiles drawn for each tile value.
+    let weights: Vec<Vec<u128>> = tiles_by_value
+        .iter()
+        .map(|tiles_for_value| {
+            (0..=tiles_for_value.number_of_tiles.min(hand_size))
+                .map(|tiles_drawn| weight(tiles_for_value, tiles_drawn))
+                .collect()
+        })
+        .collect();
+
+    let mut memo = HashMap::new();
+    parameters.progress.start(1);
+    let count = scores
+        .clone()
+        .map(|score| {
+            count_subproblem(
+                parameters,
+                tiles_by_value,
+                &weights,
+                &mut memo,
+                0,
+                hand_size,
+                score,
+            )
+        })
+        .fold(0, u128::saturating_add);
+    parameters.progress.advance(1);
+    count
+}
+
+/// Counts the ways to draw `tiles_left` tiles with a score of `score_left`
+/// with the tile values from `tile_value_index` onwards, for `count_memoized`.
+fn count_subproblem(
+    parameters: &Parameters,
+    tiles_by_value: &[TilesForValue],
+    weights: &[Vec<u128>],
+    memo: &mut HashMap<(usize, u32, u32), u128>,
+    tile_value_index: usize,
+    tiles_left: u32,
+    score_left: u32,
+) -> u128 {
+    let tiles_for_value = match tiles_by_value.get(tile_value_index) {
+        Some(tiles_for_value) => tiles_for_value,
+        None => return u128::from(tiles_left == 0 && score_left == 0),
+    };
+
+    let key = (tile_value_index, tiles_left, score_left);
+    if let Some(&count) = memo.get(&key) {
+        return count;
+    }
+
+    parameters.progress.explore(1);
+    let most = tiles_for_value.number_of_tiles.min(tiles_left);
+    let count = (0..=most)
+        .take_while(|&tiles_drawn| tiles_drawn * tiles_for_value.value <= score_left)
+        .filter(|&tiles_drawn| weights[tile_value_index][tiles_drawn as usize] > 0)
+        .map(|tiles_drawn| {
+            weights[tile_value_index][tiles_drawn as usize].saturating_mul(count_subproblem(
+                parameters,
+                tiles_by_value,
+                weights,
+                memo,
+                tile_value_index + 1,
+                tiles_left - tiles_drawn,
+                score_left - tiles_drawn * tiles_for_value.value,
+            ))
+        })
+        .fold(0, u128::saturating_add);
+    memo.insert(key, count);
+    count
+}
+
+/// Returns the sum of the coefficients of the scores in `scores`
+/// among the coefficients of a hand size.
+fn coefficient(coefficients_by_score: &[u128], scores: &RangeInclusive<u32>) -> u128 {
+    coefficients_by_score
+        .get(*scores.start() as usize..)
+        .map_or(0, |coefficients| {
+            coefficients
+                .iter()
+                .fold(0, |sum: u128, &coefficient| sum.saturating_add(coefficient))
+        })
+}
+
+/// Multiplies the generating functions of the letters,
+/// Σ weight(occurrences, k)·x^(k·value)·y^k over the numbers of copies k of each letter,
+/// and returns the coefficients of x^score·y^hand_size of the product
+/// as `coefficients[hand_size][score]`,
+/// up to `max_hand_size` and `max_score`,
+/// or up to the highest score of `max_hand_size` tiles if it is lower,
+/// since the hands can't score more.
+///
+/// With a weight of 1, the coefficients are the numbers of hands;
+/// with a weight of C(occurrences, k), they are the numbers of ways to draw them.
+/// The product is built one letter at a time:
+/// the hands drawn from the letters so far are extended
+/// with every possible number of copies of the next letter.
+/// The hands scoring more than `max_score` are left out,
+/// and so are the letters of `--exclude`
+/// and the hands without the copies of the letters of `--include`.
+fn multiply_generating_functions(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    max_hand_size: u32,
+    max_score: u32,
+    weight: impl Fn(u32, u32) -> u128,
+) -> Vec<Vec<u128>> {
+    let (_, highest_score) = score_bounds(tiles, max_hand_size);
+    let max_hand_size = max_hand_size as usize;
+    let max_score = max_score.min(highest_score) as usize;
+
+    // The coefficients of the product of the generating functions
+    // of the letters handled so far.
+    let mut coefficients = vec![vec![0u128; max_score + 1]; max_hand_size + 1];
+    coefficients[0][0] = 1;
+    parameters.progress.start(tiles.len() as u64);
+    for counted_tile in tiles {
+        if parameters.interrupt.is_triggered() {
+            break;
+        }
+
+        if parameters.exclude.contains(&counted_tile.tile.letter) {
+            parameters.progress.advance(1);
+            continue;
+        }
+
+        let value = counted_tile.tile.value as usize;
+        let occurrences = counted_tile.occurrences as usize;
+        let required = parameters.required_occurrences(counted_tile.tile.letter) as usize;
+
+        // Going from the largest hands down,
+        // the smaller hands don't have copies of this letter yet.
+        for hand_size in (1..=max_hand_size).rev() {
+            for score in (0..=max_score).rev() {
+                let with_copies = (required.max(1)..=occurrences.min(hand_size))
+                    .take_while(|copies| copies * value <= score)
+                    .map(|copies| {
+                        coefficients[hand_size - copies][score - copies * value]
+                            .saturating_mul(weight(occurrences as u32, copies as u32))
+                    })
+                    .fold(0, u128::saturating_add);
+                if required == 0 {
+                    coefficients[hand_size][score] =
+                        coefficients[hand_size][score].saturating_add(with_copies);
+                } else {
+                    coefficients[hand_size][score] = with_copies;
+                }
+            }
+        }
+
+        // The hands without this letter are left out if it is required.
+        if required > 0 {
+            coefficients[0].iter_mut().for_each(|count| *count = 0);
+        }
+
+        parameters.progress.advance(1);
+        parameters.progress.explore(1);
+    }
+
+    coefficients
+}
+
+/// Produces the list of hands for each score
+/// from `--min-score` to `--max-score`
+/// in a single traversal of the search space.
+///
+/// All the hands for all the scores are kept in memory,
+/// so the memory usage grows with the range of scores that a hand can have.
+/// The search stops once the maximum number of solutions is found,
+/// counting the hands for all the scores.
+fn scrabble_by_score(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+) -> Result<BTreeMap<u32, SolutionList>, Error> {
+    let scores = parameters.reachable_score_range(tiles);
+    let mut hands_by_score: BTreeMap<_, _> = scores
+        .clone()
+        .map(|score| (score, SolutionList::new()))
+        .collect();
+    let mut len_in_bytes = 0;
+    let mut solutions_found = 0;
+
+    search(
+        parameters,
+        tiles,
+        scores,
+        &mut |hand_score, mut solutions: SolutionList| {
+            let flow = limit_solutions(
+                &mut solutions,
+                &mut solutions_found,
+                parameters.max_solutions,
+            );
+            len_in_bytes += solutions.len_in_bytes();
+            check_max_list_bytes(parameters.max_list_bytes, len_in_bytes)?;
+            hands_by_score
+                .get_mut(&hand_score)
+                .expect("hand score outside of the range")
+                .add_solutions(solutions);
+            Ok(flow)
+        },
+    )?;
+
+    hands_by_score.values_mut().for_each(SolutionList::finish);
+
+    Ok(hands_by_score)
+}
+
+/// Lists the `top` hands with the highest scores,
+/// from the highest score down and then in alphabetical order,
+/// with their score.
+///
+/// The scores are searched from the highest score of a hand down,
+/// keeping the best hands found so far in a heap of at most `top` hands,
+/// until the heap is full after a score,
+/// since the hands with the lower scores can't be better.
+/// The hands with the last score that don't fit are left out in alphabetical order,
+/// so that the ties are always broken the same way.
+fn scrabble_top(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    wordlist: Option<&Wordlist>,
+    top: usize,
+) -> Result<Vec<(u32, String)>, Error> {
+    let (lowest_score, highest_score) = score_bounds(tiles, parameters.hand_size());
+
+    // The worst hand is at the top of the heap:
+    // the one with the lowest score, and the last one in alphabetical order among them.
+    let mut heap = BinaryHeap::with_capacity(top + 1);
+    for score in (lowest_score..=highest_score).rev() {
+        if heap.len() == top || parameters.interrupt.is_triggered() {
+            break;
+        }
+
+        search(
+            parameters,
+            tiles,
+            score..=score,
+            &mut |score, hands: SolutionList| {
+                for hand in hands.into_iter().filter(|hand| {
+                    wordlist
+                        .is_none_or(|wordlist| wordlist.can_form(hand, parameters.shorter_words))
+                }) {
+                    heap.push((Reverse(score), hand));
+                    if heap.len() > top {
+                        heap.pop();
+                    }
+                }
+
+                Ok(ControlFlow::Continue(()))
+            },
+        )?;
+    }
+
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|(Reverse(score), hand)| (score, hand))
+        .collect())
+}
+
+/// Computes the mean and the variance of the score of a random hand
+/// from the number of ways to draw the hands of each score,
+/// which are the coefficients of `multiply_generating_functions`
+/// with a weight of C(occurrences, k).
+fn scrabble_statistics(parameters: &Parameters, tiles: &[CountedTile]) -> ScoreStatistics {
+    let hand_size = parameters.hand_size();
+    let (_, highest_score) = score_bounds(tiles, hand_size);
+    let coefficients =
+        multiply_generating_functions(parameters, tiles, hand_size, highest_score, binomial);
+
+    let mut statistics = ScoreStatistics {
+        draws: 0,
+        score_sum: 0,
+        square_sum: 0,
+        target_score: parameters.target_score,
+    };
+    for (score, &draws) in coefficients[hand_size as usize].iter().enumerate() {
+        let score = score as u128;
+        statistics.draws = statistics.draws.saturating_add(draws);
+        statistics.score_sum = statistics
+            .score_sum
+            .saturating_add(draws.saturating_mul(score));
+        statistics.square_sum = statistics
+            .square_sum
+            .saturating_add(draws.saturating_mul(score * score));
+    }
+
+    statistics
+}
+
+/// Counts the hands and their draws for each score in the range,
+/// leaving out the scores that no hand has.
+fn scrabble_distribution(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+) -> Result<BTreeMap<u32, Probability>, Error> {
+    let total_tiles = tiles
+        .iter()
+        .map(|counted_tile| counted_tile.occurrences)
+        .sum();
+    let total_draws = binomial(total_tiles, parameters.hand_size());
+
+    let mut distribution = BTreeMap::new();
+    search(
+        parameters,
+        tiles,
+        parameters.score_range(),
+        &mut |hand_score, draws: Draws| {
+            let probability = distribution.entry(hand_score).or_insert(Probability {
+                hands: 0,
+                draws: 0,
+                total_draws,
+            });
+            probability.hands += draws.hands;
+            probability.draws += draws.draws;
+            Ok(ControlFlow::Continue(()))
+        },
+    )?;
+
+    Ok(distribution)
+}
+
+/// Finds the smallest score above the lowest score of a hand that no hand has,
+/// searching for one hand of each score in turn.
+///
+/// If the search is interrupted, the score being searched is reported.
+fn scrabble_unreachable(parameters: &Parameters, tiles: &[CountedTile]) -> Result<Gap, Error> {
+    let (lowest_score, highest_score) = score_bounds(tiles, parameters.hand_size());
+    let mut score = lowest_score + 1;
+    while score <= highest_score && !parameters.interrupt.is_triggered() {
+        // One hand is enough to know that the score is reachable.
+        let mut reachable = false;
+        search(
+            parameters,
+            tiles,
+            score..=score,
+            &mut |_, _: SolutionCount| {
+                reachable = true;
+                Ok(ControlFlow::Break(()))
+            },
+        )?;
+
+        if !reachable {
+            break;
+        }
+
+        score += 1;
+    }
+
+    Ok(Gap {
+        score,
+        lowest_score,
+        highest_score,
+    })
+}
+
+/// Lists the words of the word list with as many letters as a hand
+/// whose score is in the range and that can be drawn from the bag
+/// without blank tiles, in alphabetical order.
+///
+/// The list stops after the maximum number of solutions, if one is set.
+fn scrabble_bingos(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    wordlist: &Wordlist,
+) -> Vec<String> {
+    let scores = parameters.score_range();
+    let mut words: Vec<String> = wordlist
+        .words()
+        .filter(|word| word.chars().count() == parameters.hand_size() as usize)
+        .filter(|word| word_score(tiles, word).is_some_and(|score| scores.contains(&score)))
+        .map(String::from)
+        .collect();
+    words.sort_unstable();
+
+    if let Some(max_solutions) = parameters.max_solutions {
+        words.truncate(max_solutions.get());
+    }
+
+    words
+}
+
+/// Returns the score of the tiles that spell `word`,
+/// or `None` if the bag doesn't have enough tiles of some letter.
+fn word_score(tiles: &[CountedTile], word: &str) -> Option<u32> {
+    let letters = word.chars().sorted().group_by(|&letter| letter);
+    letters
+        .into_iter()
+        .map(|(letter, group)| {
+            let counted_tile = tiles
+                .iter()
+                .find(|counted_tile| counted_tile.tile.letter == letter)?;
+            let occurrences = group.count() as u32;
+            if occurrences > counted_tile.occurrences {
+                return None;
+            }
+
+            Some(counted_tile.tile.value * occurrences)
+        })
+        .sum()
+}
+
+/// Adds the number of `solutions` to `solutions_found`,
+/// dropping the solutions beyond `max_solutions`.
+///
+/// Returns `Break` once `max_solutions` solutions are found.
+fn limit_solutions<S>(
+    solutions: &mut S,
+    solutions_found: &mut usize,
+    max_solutions: Option<NonZeroUsize>,
+) -> ControlFlow<()>
+where
+    S: SolutionAccumulator,
+{
+    *solutions_found += solutions.number_of_solutions();
+    match max_solutions {
+        Some(max_solutions) if *solutions_found >= max_solutions.get() => {
+            let excess = *solutions_found - max_solutions.get();
+            solutions.truncate(solutions.number_of_solutions() - excess);
+            *solutions_found = max_solutions.get();
+            ControlFlow::Break(())
+        }
+        _ => ControlFlow::Continue(()),
+    }
+}
+
+/// Returns an error if the hands accumulated so far
+/// take up more than `--max-list-bytes`.
+fn check_max_list_bytes(max_list_bytes: Option<usize>, len_in_bytes: usize) -> Result<(), Error> {
+    match max_list_bytes {
+        Some(max_list_bytes) if len_in_bytes > max_list_bytes => {
+            Err(Error::ListTooLarge(max_list_bytes))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The number of combinations of tile values
+/// from which the search is split across threads.
+///
+/// The smaller searches are faster on a single thread.
+const PARALLEL_COMBINATIONS: u64 = 100_000;
+
+/// The number of tiles whose draws are searched in parallel in the large searches,
+/// which splits the search in enough branches for the threads to share the work
+/// even though the branches with the lowest tile values are much larger.
+const PARALLEL_DEPTH: u32 = 3;
+
+/// Searches for all the hands whose score is in `scores`,
+/// passing the solutions for each combination of tile values
+/// to `add_solutions` along with the hand's score.
+///
+/// The search stops at the first error returned by `add_solutions`,
+/// or when it returns `Break`.
+fn search<S>(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+    scores: RangeInclusive<u32>,
+    add_solutions: &mut impl FnMut(u32, S) -> Result<ControlFlow<()>, Error>,
+) -> Result<(), Error>
+where
+    S: SolutionAccumulator,
+{
+    let tiles_by_value = constrained_tiles_by_value(parameters, tiles);
+
+    // The number of tiles (no matter the letter) that have been drawn
+    // for each tile value at the current point in the algorithm.
+    let mut abstract_tiles_drawn = vec![0; tiles_by_value.len()];
+
+    // Each combination of tile values is a step of the search,
+    // including the combinations that need more tiles than there are in the bag.
+    let combinations = multisets(tiles_by_value.len(), parameters.hand_size());
+    parameters.progress.start(combinations);
+
+    let _span =
+        debug_span!("scrabble", hand_size = parameters.hand_size(), scores = ?scores).entered();
+    debug!(
+        tile_values = tiles_by_value.len(),
+        combinations, "searching the combinations of tile values"
+    );
+
+    // The large searches are split across threads,
+    // unless they can stop early at the maximum number of solutions or bytes,
+    // since all the branches are searched before their solutions are added.
+    let parallel_depth = if combinations >= PARALLEL_COMBINATIONS
+        && parameters.max_solutions.is_none()
+        && parameters.max_list_bytes.is_none()
+        && rayon::current_num_threads() > 1
+    {
+        PARALLEL_DEPTH
+    } else {
+        0
+    };
+
+    // Count the combinations with a score in range as they are found.
+    let mut matching_combinations = 0;
+    let result = draw_abstract(
+        parameters,
+        &tiles_by_value,
+        &scores,
+        &mut |score, solutions| {
+            matching_combinations += 1;
+            add_solutions(score, solutions)
+        },
+        &mut abstract_tiles_drawn,
+        0,
+        0,
+        parallel_depth,
+    );
+
+    debug!(
+        matching_combinations,
+        interrupted = parameters.interrupt.is_triggered(),
+        limit_reached = matches!(result, Ok(ControlFlow::Break(()))),
+        "search finished"
+    );
+    result.map(|_| ())
+}
+
+/// Groups tile definitions by their value, in increasing order of value.
+fn group_tiles_by_value(tiles: &[CountedTile]) -> Vec<TilesForValue> {
+    tiles
+        .iter()
+        // Accumulate into a BTreeMap so that the order is consistent between runs.
+        .fold(BTreeMap::new(), |mut map, counted_tile| {
+            let tiles_for_value =
+                map.entry(counted_tile.tile.value)
+                    .or_insert_with(|| TilesForValue {
+                        value: counted_tile.tile.value,
+                        counted_tiles: vec![],
+                        number_of_tiles: 0,
+                        required_occurrences: vec![],
+                    });
+            tiles_for_value.counted_tiles.push(counted_tile.clone());
+            tiles_for_value.number_of_tiles += counted_tile.occurrences;
+            tiles_for_value.required_occurrences.push(0);
+            map
+        })
+        .into_values()
+        .collect()
+}
+
+/// Groups tile definitions by their value like `group_tiles_by_value`,
+/// leaving out the letters of `--exclude`
+/// and requiring the letters of `--include`.
+fn constrained_tiles_by_value(
+    parameters: &Parameters,
+    tiles: &[CountedTile],
+) -> Vec<TilesForValue> {
+    let tiles: Vec<_> = tiles
+        .iter()
+        .filter(|counted_tile| !parameters.exclude.contains(&counted_tile.tile.letter))
+        .cloned()
+        .collect();
+    let mut tiles_by_value = group_tiles_by_value(&tiles);
+    for tiles_for_value in &mut tiles_by_value {
+        tiles_for_value.required_occurrences = tiles_for_value
+            .counted_tiles
+            .iter()
+            .map(|counted_tile| parameters.required_occurrences(counted_tile.tile.letter))
+            .collect();
+    }
+
+    tiles_by_value
+}
+
+/// Returns the number of tiles that must still be drawn
+/// with the tile values from `first_tile_value_index` onwards
+/// to have the letters that the hands must have,
+/// or `None` if a lower tile value, which is not drawn anymore, is missing some.
+fn missing_required_tiles(
+    tiles_by_value: &[TilesForValue],
+    abstract_tiles_drawn: &[u32],
+    first_tile_value_index: usize,
+) -> Option<u32> {
+    let mut missing =
+        tiles_by_value
+            .iter()
+            .zip(abstract_tiles_drawn)
+            .map(|(tiles_for_value, &tiles_drawn)| {
+                tiles_for_value.required_tiles().saturating_sub(tiles_drawn)
+            });
+    if missing
+        .by_ref()
+        .take(first_tile_value_index)
+        .any(|missing| missing > 0)
+    {
+        None
+    } else {
+        Some(missing.sum())
+    }
+}
+
+/// For each distinct tile value,
+/// draw one tile with that value (without specifying which letter),
+/// then recursively draw more tiles
+/// until the hand size is reached.
+/// If the hand is full and its score is in `scores`,
+/// pass the solutions to `add_solutions`,
+/// stopping at the first error or `Break` it returns.
+///
+/// `abstract_tiles_drawn` holds the number of tiles drawn so far
+/// for each entry in `tiles_by_value`.
+/// Only tile values from `first_tile_value_index` onwards are drawn.
+///
+/// The draws of the first `parallel_depth` tiles are searched in parallel,
+/// each with its own copy of `abstract_tiles_drawn` and its own solutions,
+/// which are then passed to `add_solutions` in the order of the sequential search.
+/// Since all the branches are searched before their solutions are passed,
+/// a `Break` only stops the search once they are done.
+#[allow(clippy::too_many_arguments)]
+fn draw_abstract<S>(
+    parameters: &Parameters,
+    tiles_by_value: &[TilesForValue],
+    scores: &RangeInclusive<u32>,
+    add_solutions: &mut dyn FnMut(u32, S) -> Result<ControlFlow<()>, Error>,
+    abstract_tiles_drawn: &mut [u32],
+    first_tile_value_index: usize,
+    tiles_drawn_so_far: u32,
+    parallel_depth: u32,
+) -> Result<ControlFlow<()>, Error>
+where
+    S: SolutionAccumulator,
+{
+    parameters.progress.explore(1);
+
+    // Have we drawn enough tiles yet?
+    if tiles_drawn_so_far == parameters.hand_size() {
+        parameters.progress.advance(1);
+
+        // Is the cumulative value of the tiles we drew one of the scores we're looking for?
+        let hand_score: u32 = tiles_by_value
+            .iter()
+            .zip(abstract_tiles_drawn.iter())
+            .map(|(tiles_for_value, &tiles_drawn)| tiles_for_value.value * tiles_drawn)
+            .sum();
+        parameters.progress.record_snapshot(|| {
+            let values = tiles_by_value
+                .iter()
+                .zip(abstract_tiles_drawn.iter())
+                .flat_map(|(tiles_for_value, &tiles_drawn)| {
+                    iter::repeat_n(tiles_for_value.value, tiles_drawn as usize)
+                })
+                .join(" ");
+            format!("tile values {} (score {})", values, hand_score)
+        });
+
+        if scores.contains(&hand_score) {
+            trace!(
+                score = hand_score,
+                tiles_drawn = ?abstract_tiles_drawn,
+                "found a combination of tile values"
+            );
+
+            // Add the possible hands
+            // for the combination of tile values that was drawn
+            // to the solutions.
+            return add_solutions(
+                hand_score,
+                S::from_combination(
+                    tiles_by_value,
+                    abstract_tiles_drawn,
+                    parameters.max_list_bytes,
+                )?,
+            );
+        }
+    } else {
+        let score_so_far: u32 = tiles_by_value
+            .iter()
+            .zip(abstract_tiles_drawn.iter())
+            .map(|(tiles_for_value, &tiles_drawn)| tiles_for_value.value * tiles_drawn)
+            .sum();
+
+        // When we do a recursive call,
+        // we must start at the same tile value,
+        // not from the start,
+        // otherwise we would find duplicate solutions.
+        let tile_value_indices = first_tile_value_index..tiles_by_value.len();
+        if tiles_drawn_so_far < parallel_depth {
+            let abstract_tiles_drawn: &[u32] = abstract_tiles_drawn;
+            let solutions_by_tile_value = tile_value_indices
+                .into_par_iter()
+                .map(|tile_value_index| {
+                    let mut abstract_tiles_drawn = abstract_tiles_drawn.to_vec();
+                    let mut solutions = vec![];
+                    if !parameters.interrupt.is_triggered() {
+                        // The solutions are only collected here, which never stops the branch.
+                        let _ = draw_tile_value(
+                            parameters,
+                            tiles_by_value,
+                            scores,
+                            &mut |score, solutions_for_combination| {
+                                solutions.push((score, solutions_for_combination));
+                                Ok(ControlFlow::Continue(()))
+                            },
+                            &mut abstract_tiles_drawn,
+                            tile_value_index,
+                            tiles_drawn_so_far,
+                            score_so_far,
+                            parallel_depth,
+                        )?;
+                    }
+
+                    Ok(solutions)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            for (score, solutions) in solutions_by_tile_value.into_iter().flatten() {
+                let flow = add_solutions(score, solutions)?;
+                if flow.is_break() {
+                    return Ok(flow);
+                }
+            }
+        } else {
+            for tile_value_index in tile_value_indices {
+                // Stop the search if it was interrupted,
+                // keeping the solutions found so far.
+                if parameters.interrupt.is_triggered() {
+                    break;
+                }
+
+                let flow = draw_tile_value(
+                    parameters,
+                    tiles_by_value,
+                    scores,
+                    add_solutions,
+                    abstract_tiles_drawn,
+                    tile_value_index,
+                    tiles_drawn_so_far,
+                    score_so_far,
+                    parallel_depth,
+                )?;
+                if flow.is_break() {
+                    return Ok(flow);
+                }
+            }
+        }
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Draws one more tile with the tile value at `tile_value_index`
+/// for `draw_abstract`, then draws the rest of the hand,
+/// unless the draw can't lead to a solution.
+///
+/// `score_so_far` is the score of the `tiles_drawn_so_far` tiles drawn before this one.
+#[allow(clippy::too_many_arguments)]
+fn draw_tile_value<S>(
+    parameters: &Parameters,
+    tiles_by_value: &[TilesForValue],
+    scores: &RangeInclusive<u32>,
+    add_solutions: &mut dyn FnMut(u32, S) -> Result<ControlFlow<()>, Error>,
+    abstract_tiles_drawn: &mut [u32],
+    tile_value_index: usize,
+    tiles_drawn_so_far: u32,
+    score_so_far: u32,
+    parallel_depth: u32,
+) -> Result<ControlFlow<()>, Error>
+where
+    S: SolutionAccumulator,
+{
+    // Leave the branches at the top of the search that belong to other parts.
+    if tiles_drawn_so_far == 0 {
+        if let Some(shard) = parameters.shard {
+            if !shard.contains(tile_value_index) {
+                parameters.progress.advance(multisets(
+                    tiles_by_value.len() - tile_value_index,
+                    parameters.hand_size() - 1,
+                ));
+                return Ok(ControlFlow::Continue(()));
+            }
+        }
+    }
+
+    // If there are no tiles of that value left:
+    if abstract_tiles_drawn[tile_value_index] == tiles_by_value[tile_value_index].number_of_tiles {
+        trace!(
+            depth = tiles_drawn_so_far,
+            value = tiles_by_value[tile_value_index].value,
+            "pruned: no tiles of that value are left"
+        );
+        parameters.progress.prune();
+
+        // Skip the combinations that would have been drawn after this tile.
+        parameters.progress.advance(multisets(
+            tiles_by_value.len() - tile_value_index,
+            parameters.hand_size() - tiles_drawn_so_far - 1,
+        ));
+        return Ok(ControlFlow::Continue(()));
+    }
+
+    // Draw any tile of that value.
+    abstract_tiles_drawn[tile_value_index] += 1;
+
+    // Skip the draw if the tiles left to draw can't bring the score in range,
+    // or if there aren't enough tiles left in the bag to fill the hand.
+    let tiles_left = parameters.hand_size() - tiles_drawn_so_far - 1;
+    let score = score_so_far + tiles_by_value[tile_value_index].value;
+    let reachable = remaining_score_bounds(
+        tiles_by_value,
+        abstract_tiles_drawn,
+        tile_value_index,
+        tiles_left,
+    )
+    .is_some_and(|(lowest_score, highest_score)| {
+        score + highest_score >= *scores.start() && score + lowest_score <= *scores.end()
+    });
+    if !reachable {
+        trace!(
+            depth = tiles_drawn_so_far,
+            value = tiles_by_value[tile_value_index].value,
+            "pruned: no score in range can be reached"
+        );
+        parameters.progress.prune();
+        parameters.progress.advance(multisets(
+            tiles_by_value.len() - tile_value_index,
+            tiles_left,
+        ));
+        abstract_tiles_drawn[tile_value_index] -= 1;
+        return Ok(ControlFlow::Continue(()));
+    }
+
+    // Skip the draw if the hand can't have all the letters it must have anymore.
+    match missing_required_tiles(tiles_by_value, abstract_tiles_drawn, tile_value_index) {
+        Some(missing) if missing <= tiles_left => {}
+        _ => {
+            trace!(
+                depth = tiles_drawn_so_far,
+                value = tiles_by_value[tile_value_index].value,
+                "pruned: the required letters can't be drawn"
+            );
+            parameters.progress.prune();
+            parameters.progress.advance(multisets(
+                tiles_by_value.len() - tile_value_index,
+                tiles_left,
+            ));
+            abstract_tiles_drawn[tile_value_index] -= 1;
+            return Ok(ControlFlow::Continue(()));
+        }
+    }
+
+    let flow = draw_abstract(
+        parameters,
+        tiles_by_value,
+        scores,
+        add_solutions,
+        abstract_tiles_drawn,
+        tile_value_index,
+        tiles_drawn_so_far + 1,
+        parallel_depth,
+    )?;
+
+    // Put the tile back in the bag for the next draw.
+    abstract_tiles_drawn[tile_value_index] -= 1;
+    Ok(flow)
+}
+
+/// Returns the lowest and the highest score of `tiles_left` more tiles,
+/// drawn from the tiles left in the bag
+/// with the tile values from `first_tile_value_index` onwards,
+/// or `None` if there aren't that many tiles left with these values.
+fn remaining_score_bounds(
+    tiles_by_value: &[TilesForValue],
+    abstract_tiles_drawn: &[u32],
+    first_tile_value_index: usize,
+    tiles_left: u32,
+) -> Option<(u32, u32)> {
+    let indices = first_tile_value_index..tiles_by_value.len();
+    let tiles_in_bag: u32 = indices
+        .clone()
+        .map(|index| tiles_by_value[index].number_of_tiles - abstract_tiles_drawn[index])
+        .sum();
+    if tiles_in_bag < tiles_left {
+        return None;
+    }
+
+    // Draw the tiles with the lowest (or highest) values first.
+    let score = |indices: &mut dyn Iterator<Item = usize>| {
+        let mut tiles_left = tiles_left;
+        let mut score = 0;
+        for index in indices {
+            let tiles_drawn =
+                tiles_left.min(tiles_by_value[index].number_of_tiles - abstract_tiles_drawn[index]);
+            score += tiles_by_value[index].value * tiles_drawn;
+            tiles_left -= tiles_drawn;
+        }
+
+        score
+    };
+
+    Some((score(&mut indices.clone()), score(&mut indices.rev())))
+}
+
+/// Returns the number of ways to draw `size` tile values
+/// out of `values` distinct values, allowing repetitions,
+/// saturating at `u64::MAX`.
+fn multisets(values: usize, size: u32) -> u64 {
+    // This is the binomial coefficient (values + size - 1, size),
+    // computed so that each intermediate result is itself a binomial coefficient.
+    let values = values as u64;
+    (1..=u64::from(size))
+        .try_fold(1u64, |count, index| {
+            Some(count.checked_mul(values + index - 1)? / index)
+        })
+        .unwrap_or(u64::MAX)
+}
+
+/// Returns the binomial coefficient (`n`, `k`),
+/// the number of ways to choose `k` of `n` distinguishable tiles.
+fn binomial(n: u32, k: u32) -> u128 {
+    if k > n {
+        return 0;
+    }
+
+    // Each intermediate result is itself a binomial coefficient.
+    (1..=u128::from(k.min(n - k))).fold(1, |count, index| {
+        count * (u128::from(n - k.min(n - k)) + index) / index
+    })
+}
+
+/// Returns the number of ways to draw `tiles_drawn` of the tiles with a value,
+/// telling apart the tiles that have the same letter,
+/// that have the required occurrences of each letter.
+fn value_draws(tiles_for_value: &TilesForValue, tiles_drawn: u32) -> u128 {
+    if tiles_for_value.required_tiles() == 0 {
+        return binomial(tiles_for_value.number_of_tiles, tiles_drawn);
+    }
+
+    value_combinations(tiles_for_value, tiles_drawn, binomial)
+}
+
+/// Returns the number of distinct hands of `tiles_drawn` of the tiles with a value
+/// that have the required occurrences of each letter,
+/// like `concrete_hands` counts them, but without going through them.
+fn value_hands(tiles_for_value: &TilesForValue, tiles_drawn: u32) -> u128 {
+    value_combinations(tiles_for_value, tiles_drawn, |_, _| 1)
+}
+
+/// Returns the number of sequences in which `tiles_drawn` of the tiles with a value
+/// can be drawn one at a time, with the required occurrences of each letter,
+/// where the tiles with the same letter are alike.
+fn value_sequences(tiles_for_value: &TilesForValue, tiles_drawn: u32) -> u128 {
+    // The number of sequences of each number of tiles from the letters so far.
+    let tiles_drawn = tiles_drawn as usize;
+    let mut sequences = vec![0u128; tiles_drawn + 1];
+    sequences[0] = 1;
+    for (counted_tile, &required) in tiles_for_value
+        .counted_tiles
+        .iter()
+        .zip(&tiles_for_value.required_occurrences)
+    {
+        let mut next_sequences = vec![0u128; tiles_drawn + 1];
+        for (drawn, &ways) in sequences.iter().enumerate() {
+            let most = (counted_tile.occurrences as usize).min(tiles_drawn - drawn);
+            for copies in required as usize..=most {
+                // The copies of this letter can go anywhere among the tiles drawn so far.
+                next_sequences[drawn + copies] = next_sequences[drawn + copies].saturating_add(
+                    ways.saturating_mul(binomial((drawn + copies) as u32, copies as u32)),
+                );
+            }
+        }
+        sequences = next_sequences;
+    }
+
+    sequences[tiles_drawn]
+}
+
+/// Returns the number of sequences in which a hand can be drawn one tile at a time,
+/// where the tiles with the same letter are alike.
+fn hand_sequences(hand: &str) -> u128 {
+    let mut drawn = 0;
+    hand.chars()
+        .fold(BTreeMap::new(), |mut map, letter| {
+            *map.entry(letter).or_insert(0) += 1;
+            map
+        })
+        .values()
+        .fold(1u128, |sequences, &copies| {
+            drawn += copies;
+            sequences.saturating_mul(binomial(drawn, copies))
+        })
+}
+
+/// Returns the sum, over the ways to choose the number of copies of each letter
+/// among `tiles_drawn` of the tiles with a value,
+/// with the required occurrences of each letter,
+/// of the product of `weight(occurrences, copies)` for the letters.
+fn value_combinations(
+    tiles_for_value: &TilesForValue,
+    tiles_drawn: u32,
+    weight: impl Fn(u32, u32) -> u128,
+) -> u128 {
+    // The number of ways to draw each number of tiles from the letters so far.
+    let tiles_drawn = tiles_drawn as usize;
+    let mut draws = vec![0; tiles_drawn + 1];
+    draws[0] = 1;
+    for (counted_tile, &required) in tiles_for_value
+        .counted_tiles
+        .iter()
+        .zip(&tiles_for_value.required_occurrences)
+    {
+        let mut next_draws = vec![0; tiles_drawn + 1];
+        for (drawn, &ways) in draws.iter().enumerate() {
+            let most = (counted_tile.occurrences as usize).min(tiles_drawn - drawn);
+            for copies in required as usize..=most {
+                next_draws[drawn + copies] +=
+                    ways * weight(counted_tile.occurrences, copies as u32);
+            }
+        }
+        draws = next_draws;
+    }
+
+    draws[tiles_drawn]
+}
+
+/// Enumerates the possible hands
+/// for a combination of tile values that was drawn.
+///
+/// `abstract_tiles_drawn` holds the number of tiles drawn
+/// for each entry in `tiles_by_value`.
+fn concrete_hands<S>(
+    tiles_by_value: &[TilesForValue],
+    abstract_tiles_drawn: &[u32],
+    max_list_bytes: Option<usize>,
+) -> Result<S, Error>
+where
+    S: SolutionAccumulator,
+{
+    let concrete_tile_combinations_by_tile_value = tiles_by_value
+        .iter()
+        .zip(abstract_tiles_drawn.iter())
+        // For each tile value
+        // for which we drew at least one tile (for performance),
+        .filter(|&(_, &tiles_drawn)| tiles_drawn > 0)
+        // enumerate all unique groups of letters
+        // of the size matching the number of tiles drawn
+        .map(|(tiles_for_value, &tiles_drawn)| {
+            let mut concrete_tile_combinations = S::new();
+            draw_concrete(
+                tiles_for_value,
+                tiles_drawn,
+                &mut concrete_tile_combinations,
+                &mut vec![0; tiles_for_value.counted_tiles.len()],
+                0,
+                0,
+            );
+            concrete_tile_combinations
+        })
+        .collect();
+
+    // Perform the cartesian product
+    // of the possible groups of letters by tile value.
+    S::cartesian_product(concrete_tile_combinations_by_tile_value, max_list_bytes)
+}
+
+/// For a given tile value,
+/// for each distinct letter,
+/// draw one tile of that letter,
+/// then recursively draw more tiles
+/// until we've reached the number of tiles
+/// that were drawn for the tile value (`tiles_to_draw`).
+/// If we've reached the target number of tiles,
+/// add the partial solutions to `concrete_tile_combinations`.
+///
+/// `occurrences_drawn` holds the number of tiles drawn so far
+/// for each entry in `tiles_for_value.counted_tiles`.
+/// Only letters from `first_letter_index` onwards are drawn.
+fn draw_concrete<S>(
+    tiles_for_value: &TilesForValue,
+    tiles_to_draw: u32,
+    concrete_tile_combinations: &mut S,
+    occurrences_drawn: &mut [u32],
+    first_letter_index: usize,
+    tiles_drawn_so_far: u32,
+) where
+    S: SolutionAccumulator,
+{
+    if tiles_drawn_so_far == tiles_to_draw {
+        // Leave out the letters that don't have the required occurrences.
+        if occurrences_drawn
+            .iter()
+            .zip(&tiles_for_value.required_occurrences)
+            .any(|(occurrences, required)| occurrences < required)
+        {
+            return;
+        }
+
+        concrete_tile_combinations.add_solution(|| {
+            tiles_for_value
+                .counted_tiles
+                .iter()
+                .zip(occurrences_drawn.iter())
+                .flat_map(|(counted_tile, &occurrences)| {
+                    iter::repeat_n(counted_tile.tile.letter, occurrences as usize)
+                })
+                .collect()
+        });
+    } else {
+        // When we do a recursive call,
+        // we must start at the same letter,
+        // not from the start,
+        // otherwise we would find duplicate solutions.
+        for letter_index in first_letter_index..tiles_for_value.counted_tiles.len() {
+            if occurrences_drawn[letter_index]
+                < tiles_for_value.counted_tiles[letter_index].occurrences
+            {
+                // Draw a tile of that letter.
+                occurrences_drawn[letter_index] += 1;
+
+                draw_concrete(
+                    tiles_for_value,
+                    tiles_to_draw,
+                    concrete_tile_combinations,
+                    occurrences_drawn,
+                    letter_index,
+                    tiles_drawn_so_far + 1,
+                );
+
+                // Put the tile back in the bag for the next draw.
+                occurrences_drawn[letter_index] -= 1;
+            }
+        }
+    }
+}
+
+/// Parameters for solving variants of the Scrabble puzzle.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+// `-h` is the hand size, so the help is only available as `--help`.
+#[cfg_attr(feature = "clap", command(disable_help_flag = true, arg = Arg::new("help").long("help").action(ArgAction::Help).help("Print help")))]
+pub struct Parameters {
+    /// The number of tiles in a hand,
+    /// or a range of hand sizes (e.g. `2..=10`) to count the hands of each size.
+    #[cfg_attr(feature = "clap", arg(short = 'h', long, default_value = "7"))]
+    hand_size: HandSize,
+
+    /// The target score for a hand.
+    #[cfg_attr(feature = "clap", arg(short = 's', long, default_value = "46"))]
+    target_score: u32,
+
+    /// The target score for a hand,
+    /// as a percentage of the highest score of a hand,
+    /// instead of `--target-score`.
+    ///
+    /// The resolved target score is logged with `--verbose`.
+    #[cfg_attr(feature = "clap", arg(long, value_parser = clap::value_parser!(u32).range(0..=100), conflicts_with_all = ["target_score", "compare_editions"]))]
+    target_percent: Option<u32>,
+
+    /// The lowest score of the hands that are counted or listed,
+    /// which are all found in a single search.
+    ///
+    /// Defaults to the target score.
+    #[cfg_attr(feature = "clap", arg(long))]
+    min_score: Option<u32>,
+
+    /// The highest score of the hands that are counted or listed.
+    ///
+    /// Defaults to the target score.
+    #[cfg_attr(feature = "clap", arg(long))]
+    max_score: Option<u32>,
+
+    /// If set, the hands scoring at least the target score are counted or listed,
+    /// instead of the hands scoring exactly the target score.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["min_score", "max_score", "compare_editions"]))]
+    at_least: bool,
+
+    /// If set, the hands with the highest score that a hand can have are counted or listed,
+    /// instead of the hands scoring the target score.
+    ///
+    /// The highest score is logged with `--verbose`.
+    /// The search skips the draws that can't reach it,
+    /// so it ends as soon as the highest-valued tiles have been drawn.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["target_score", "target_percent", "min_score", "max_score", "at_least", "compare_editions"]))]
+    maximize: bool,
+
+    /// The scores of hands dealt to several players from the same bag,
+    /// separated by commas (e.g. `46,46`),
+    /// to count or list the deals instead of the hands.
+    ///
+    /// Each player gets a hand of the hand size with its score,
+    /// and no tile is in more than one hand.
+    /// The deals are ordered by player,
+    /// so swapping the hands of two players gives another deal.
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_name = "SCORES", conflicts_with_all = ["target_score", "target_percent", "min_score", "max_score", "at_least", "maximize", "compare_editions"]))]
+    deal: Vec<u32>,
+
+    /// How the solution will be presented.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = OutputFormat::Count))]
+    output: OutputFormat,
+
+    /// How the hands are counted.
+    ///
+    /// `search` draws the hands one tile value at a time, for every output.
+    /// `generating-function` multiplies the generating functions of the letters instead,
+    /// which counts the hands of any size up to the whole bag almost instantly,
+    /// but only for the count and probability outputs.
+    /// `memoized` counts like the search,
+    /// but counts each subproblem of the remaining tile values only once,
+    /// with the same restrictions as `generating-function`.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = Method::Search))]
+    method: Method,
+
+    /// Lists the N hands with the highest scores instead of the hands with the target score,
+    /// from the highest score down, with their score.
+    ///
+    /// The hands with the same score are in alphabetical order,
+    /// and the last ones are left out if they don't all fit.
+    /// The output is a list whether `--output` is `count` or `list`.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "N", conflicts_with_all = ["target_score", "target_percent", "min_score", "max_score", "at_least", "maximize", "deal", "stream", "compare_editions"]))]
+    top: Option<NonZeroUsize>,
+
+    /// If set, the sequences in which the hands can be drawn one tile at a time are counted
+    /// instead of the hands.
+    ///
+    /// The order of the draws matters,
+    /// but the tiles with the same letter are still alike,
+    /// so a hand with 2 `E`s can be drawn in half as many orders as a hand with distinct letters.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["compare_editions", "deal", "top", "stream"]))]
+    ordered: bool,
+
+    /// If set, the hands of the list output are printed as soon as they are found
+    /// instead of after the search,
+    /// and the number of hands is printed last.
+    ///
+    /// The hands are drawn in alphabetical order,
+    /// so they are printed in the same order as without this flag,
+    /// but without holding the whole list in memory.
+    /// With `--max-solutions`, the hands are the first ones in alphabetical order.
+    /// This only applies to the text output.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["compare_editions", "deal"]))]
+    stream: bool,
+
+    /// Searches only the `i`th of `n` parts of the search (e.g. `2/4`),
+    /// so that the search can be split across machines.
+    ///
+    /// The parts are split by the lowest tile value in the hand,
+    /// which is the first tile value that the search draws.
+    /// The hands of the `n` parts together are the hands of the whole search,
+    /// and their counts add up to its count.
+    /// Since the search can't be saved with `--checkpoint` like the spinning table search,
+    /// running the parts one at a time also keeps the finished parts of a long search.
+    #[cfg_attr(feature = "clap", arg(long))]
+    shard: Option<Shard>,
+
+    /// The number of sets of tiles mixed into the bag (e.g. `2`),
+    /// or the number of sets of some letters (e.g. `E=3,S=2`),
+    /// which can follow the number of sets of the other letters (e.g. `2,E=3`).
+    ///
+    /// The occurrences of each letter are multiplied by its number of sets;
+    /// `_` is the blank tile.
+    #[cfg_attr(feature = "clap", arg(long, default_value = "1"))]
+    bags: Bags,
+
+    /// The tiles already out of the bag, such as the tiles on the board (e.g. `AEEINRT`),
+    /// which are left out of the tile distribution.
+    ///
+    /// `_` is a blank tile.
+    /// A letter can't be removed more times than it occurs in the distribution.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "LETTERS"))]
+    removed: Option<String>,
+
+    /// A point value that replaces the value of a letter in the tile distribution (e.g. `Q=12`),
+    /// which can be given for several letters.
+    ///
+    /// `_` is the blank tile.
+    #[cfg_attr(feature = "clap", arg(long = "value", value_name = "LETTER=VALUE", value_parser = parse_value_arg))]
+    values: Vec<(char, u32)>,
+
+    /// Letters that the hands must have, separated by commas (e.g. `Q,Z`).
+    ///
+    /// A letter given more than once must be in the hands that many times;
+    /// `_` is the blank tile.
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_parser = parse_letter_arg))]
+    include: Vec<char>,
+
+    /// Letters that the hands must not have, separated by commas (e.g. `_`).
+    ///
+    /// The tiles of these letters are left out of the bag before the search.
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ',', value_parser = parse_letter_arg))]
+    exclude: Vec<char>,
+
+    /// If set, the hands have no repeated letter.
+    ///
+    /// Each letter is drawn at most once, as if the bag had a single tile of each letter,
+    /// so a hand has at most one blank tile too.
+    #[cfg_attr(feature = "clap", arg(long))]
+    distinct_letters: bool,
+
+    /// The edition of Scrabble® whose tile distribution is used.
+    ///
+    /// `super` is Super Scrabble®, with 200 tiles including 4 blank tiles.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = Edition::English))]
+    edition: Edition,
+
+    /// A file describing the tile distribution to use
+    /// instead of the edition's distribution.
+    ///
+    /// Each line contains a letter (`_` for a blank tile),
+    /// the number of occurrences of that letter
+    /// and the point value of that letter,
+    /// separated by whitespace.
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// A `.csv` file has a header and the same three columns,
+    /// and a `.toml` file has a `[[tiles]]` table
+    /// with a `letter`, `occurrences` and a `value` for each letter.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long, visible_alias = "tiles-file", conflicts_with = "edition")
+    )]
+    tiles: Option<PathBuf>,
+
+    /// If set, the total number of tiles and the total point value
+    /// of the tile distribution are reported on stderr before solving.
+    #[cfg_attr(feature = "clap", arg(long))]
+    distribution_check: bool,
+
+    /// The total number of tiles that the tile distribution should contain.
+    ///
+    /// The distribution check warns if the total doesn't match.
+    #[cfg_attr(feature = "clap", arg(long, requires = "distribution_check"))]
+    expected_total: Option<u32>,
+
+    /// If set, the number of subtrees of the search that were pruned
+    /// and the number that were explored are reported on stderr after solving.
+    #[cfg_attr(feature = "clap", arg(long))]
+    explain_pruning: bool,
+
+    /// How blank tiles are treated.
+    ///
+    /// `wildcard` counts or lists a hand for each set of letters that the blanks stand for,
+    /// with each blank written as its letter (e.g. `A[=S]KJXQZ`).
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = BlankMode::Tile, conflicts_with_all = ["letters_only", "hand_format", "wordlist"]))]
+    blank_mode: BlankMode,
+
+    /// If set, each hand in the list output is shown
+    /// as its distinct letters in alphabetical order,
+    /// with the multiplicity of repeated letters annotated (e.g. `B×2 F J Q X Z`).
+    #[cfg_attr(feature = "clap", arg(long))]
+    letters_only: bool,
+
+    /// How the letters of each hand in the list output are written.
+    ///
+    /// `dashed` and `spaced` separate the letters with `-` or a space
+    /// and show blank tiles as `_`;
+    /// `lower` writes the letters in lowercase.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = HandFormat::Plain, conflicts_with = "letters_only"))]
+    hand_format: HandFormat,
+
+    /// If set, each hand in the list outputs is shown as its tiles,
+    /// each with its point value, followed by the score of the hand
+    /// (e.g. `A₁ F₄ K₅ J₈ X₈ Q₁₀ Z₁₀ = 46`).
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["letters_only", "hand_format", "blank_mode"]))]
+    show_values: bool,
+
+    /// The maximum number of bytes that the hands in the list output can take up.
+    ///
+    /// The search is aborted if the hands found so far take up more than that.
+    #[cfg_attr(feature = "clap", arg(long))]
+    max_list_bytes: Option<usize>,
+
+    /// A word list with one word per line,
+    /// to count or list only the hands whose tiles form a word of the list.
+    ///
+    /// Blank tiles stand for any letter.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "FILE"))]
+    wordlist: Option<PathBuf>,
+
+    /// If set, the hands that form a word with only some of their tiles
+    /// are also counted or listed with `--wordlist`.
+    #[cfg_attr(feature = "clap", arg(long, requires = "wordlist"))]
+    shorter_words: bool,
+
+    /// If set, the hands are counted with each built-in edition's tile distribution,
+    /// ignoring the output format.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["edition", "tiles", "min_score", "max_score", "at_least", "maximize", "deal", "letters_only", "hand_format", "show_values", "max_list_bytes", "wordlist", "blank_mode"]))]
+    compare_editions: bool,
+
+    /// Stops the search early.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    interrupt: Interrupt,
+
+    /// Reports how far the search has gone.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    progress: Progress,
+
+    /// The number of hands in the list outputs after which the search stops.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    max_solutions: Option<NonZeroUsize>,
+
+    /// Whether the letters in the list outputs are colored by their point value.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    color: bool,
+
+    /// Whether the hands in the list outputs are left as drawn,
+    /// to be written as their tiles with `LetterValues`.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    structured: bool,
+
+    /// The files loaded by the previous queries of the session, if any.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: Option<Cache>,
+}
+
+/// Returns the defaults of the command line.
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            hand_size: HandSize::from(7),
+            target_score: 46,
+            target_percent: None,
+            min_score: None,
+            max_score: None,
+            at_least: false,
+            maximize: false,
+            deal: vec![],
+            output: OutputFormat::Count,
+            method: Method::Search,
+            top: None,
+            ordered: false,
+            stream: false,
+            shard: None,
+            bags: Bags::default(),
+            removed: None,
+            values: vec![],
+            include: vec![],
+            exclude: vec![],
+            distinct_letters: false,
+            edition: Edition::English,
+            tiles: None,
+            distribution_check: false,
+            expected_total: None,
+            explain_pruning: false,
+            blank_mode: BlankMode::Tile,
+            letters_only: false,
+            hand_format: HandFormat::Plain,
+            show_values: false,
+            max_list_bytes: None,
+            wordlist: None,
+            shorter_words: false,
+            compare_editions: false,
+            interrupt: Interrupt::default(),
+            progress: Progress::default(),
+            max_solutions: None,
+            color: false,
+            structured: false,
+            cache: None,
+        }
+    }
+}
+
+impl Parameters {
+    /// Starts building parameters from the defaults of the command line.
+    pub fn builder() -> ParametersBuilder {
+        ParametersBuilder {
+            parameters: Parameters::default(),
+            target_score_set: false,
+        }
+    }
+
+    /// Sets the number of hands in the list outputs after which the search stops,
+    /// or removes the limit.
+    ///
+    /// This is the global `--max-solutions` option of the command line.
+    pub fn set_max_solutions(&mut self, max_solutions: Option<NonZeroUsize>) {
+        self.max_solutions = max_solutions;
+    }
+
+    /// Sets whether the letters in the list outputs are colored by their point value.
+    ///
+    /// This is the global `--color` option of the command line.
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
+    /// Shares the files loaded by the queries of a session, such as the queries of `repl`.
+    pub fn set_cache(&mut self, cache: Cache) {
+        self.cache = Some(cache);
+    }
+
+    /// Sets whether the hands in the list outputs are left as drawn,
+    /// without `--show-values`, `--letters-only`, `--hand-format` and the colors,
+    /// so that `LetterValues::hand_tiles` can write them as their tiles.
+    ///
+    /// The command line sets this for `--format json` and `--format ndjson`.
+    pub fn set_structured(&mut self, structured: bool) {
+        self.structured = structured;
+    }
+
+    /// Sets whether the hands of the list output are printed as soon as they are found.
+    ///
+    /// The command line only streams the text output,
+    /// so it unsets this for the other formats.
+    pub fn set_stream(&mut self, stream: bool) {
+        self.stream = stream;
+    }
+
+    /// Sets the flag that stops the search early,
+    /// such as the one that the command line sets when Ctrl-C is pressed.
+    pub fn set_interrupt(&mut self, interrupt: Interrupt) {
+        self.interrupt = interrupt;
+    }
+
+    /// Sets the counters that report how far the search has gone,
+    /// which `--progress` and `--stats` read on the command line.
+    pub fn set_progress(&mut self, progress: Progress) {
+        self.progress = progress;
+    }
+
+    /// Returns whether the hands of the list output are printed as soon as they are found,
+    /// in which case `solve_to` writes them and returns their number as a count.
+    pub fn is_streamed(&self) -> bool {
+        self.stream && self.is_list()
+    }
+
+    /// Returns the totals of the tile distribution
+    /// if `--distribution-check` is set,
+    /// for the caller to report before solving.
+    pub fn check_distribution(&self) -> Result<Option<DistributionReport>, Error> {
+        if !self.distribution_check {
+            return Ok(None);
+        }
+
+        let tiles = tiles(self)?;
+        Ok(Some(check_distribution(&tiles, self.expected_total)))
+    }
+
+    /// Returns whether the pruning of the search is reported after solving.
+    pub fn explains_pruning(&self) -> bool {
+        self.explain_pruning
+    }
+
+    /// Returns whether the hands that have the target score are listed,
+    /// in which case `iter_hands` produces them one at a time.
+    pub fn is_list(&self) -> bool {
+        matches!(self.output, OutputFormat::List)
+            && !self.compare_editions
+            && self.deal.is_empty()
+            && self.top.is_none()
+    }
+
+    /// Replaces the target score with `--target-percent`
+    /// of the highest score of a hand from the given tile distribution,
+    /// rounded to the nearest point.
+    fn resolve_target_percent(&mut self, tiles: &[CountedTile]) {
+        if let Some(target_percent) = self.target_percent {
+            let (_, highest_score) = score_bounds(tiles, self.hand_size());
+            let target_score = (u64::from(target_percent) * u64::from(highest_score) + 50) / 100;
+            self.target_score = target_score.min(u64::from(highest_score)) as u32;
+            info!(
+                target_score = self.target_score,
+                target_percent, highest_score, "resolved the target score"
+            );
+        }
+    }
+
+    /// Replaces the score range with the scores from the target score
+    /// to the highest score of a hand from the given tile distribution
+    /// if `--at-least` is set,
+    /// with only the highest score if `--maximize` is set,
+    /// or fills it in with every score of a hand for the distribution output.
+    fn resolve_score_range(&mut self, tiles: &[CountedTile]) {
+        let (lowest_score, highest_score) = score_bounds(tiles, self.hand_size.last);
+        if self.maximize {
+            self.target_score = highest_score;
+            self.min_score = Some(highest_score);
+            self.max_score = Some(highest_score);
+            info!(highest_score, "resolved the highest score");
+        } else if self.at_least {
+            self.min_score = Some(self.target_score);
+            self.max_score = Some(highest_score);
+        } else if matches!(self.output, OutputFormat::Distribution) {
+            self.min_score.get_or_insert(lowest_score);
+            self.max_score.get_or_insert(highest_score);
+        }
+    }
+
+    /// Loads the word list given with `--wordlist`, if any.
+    fn load_wordlist(&self) -> Result<Option<Arc<Wordlist>>, Error> {
+        let load = |path: &Path| -> Result<_, WordlistError> {
+            let wordlist = Wordlist::load(path)?;
+            debug!(keys = wordlist.len(), "loaded the word list");
+            Ok(Arc::new(wordlist))
+        };
+        match (&self.wordlist, &self.cache) {
+            (Some(path), Some(cache)) => {
+                let mut files = cache.lock();
+                Ok(Some(cached(&mut files.wordlists, path, load)?))
+            }
+            (Some(path), None) => Ok(Some(load(path)?)),
+            (None, _) => Ok(None),
+        }
+    }
+
+    /// Applies `--bags`, `--removed`, `--value` and `--distinct-letters` to a tile distribution.
+    fn adjust_tiles(
+        &self,
+        tiles: Cow<'static, [CountedTile]>,
+    ) -> Result<Cow<'static, [CountedTile]>, TilesError> {
+        let tiles = self.bags.apply(tiles)?;
+        let tiles = match &self.removed {
+            Some(removed) => remove_tiles(tiles, removed)?,
+            None => tiles,
+        };
+        let tiles = override_values(tiles, &self.values)?;
+        let tiles = if self.distinct_letters {
+            distinct_letters(tiles)
+        } else {
+            tiles
+        };
+
+        // The numbers of tiles and the scores of the hands are counted with `u32`s,
+        // which the totals of the distribution bound.
+        let report = check_distribution(&tiles, None);
+        if report.total_tiles > u64::from(u32::MAX) || report.total_value > u128::from(u32::MAX) {
+            return Err(TilesError::TooLarge(report.total_tiles, report.total_value));
+        }
+
+        Ok(tiles)
+    }
+
+    /// Returns the number of tiles of a letter that the hands must have.
+    fn required_occurrences(&self, letter: char) -> u32 {
+        self.include
+            .iter()
+            .filter(|&&included| included == letter)
+            .count() as u32
+    }
+
+    /// Returns the number of tiles in a hand,
+    /// or the smallest hand size of a range.
+    fn hand_size(&self) -> u32 {
+        self.hand_size.first
+    }
+
+    /// Returns the scores of the hands to search for,
+    /// from `--min-score` to `--max-score`, which default to the target score.
+    fn score_range(&self) -> RangeInclusive<u32> {
+        self.min_score.unwrap_or(self.target_score)..=self.max_score.unwrap_or(self.target_score)
+    }
+
+    /// Returns the scores of `score_range` that a hand can have,
+    /// for the outputs that keep something for every score,
+    /// so that their size doesn't grow with `--min-score` and `--max-score`.
+    fn reachable_score_range(&self, tiles: &[CountedTile]) -> RangeInclusive<u32> {
+        let scores = self.score_range();
+        let (lowest_score, _) = score_bounds(tiles, self.hand_size.first);
+        let (_, highest_score) = score_bounds(tiles, self.hand_size.last);
+        (*scores.start()).max(lowest_score)..=(*scores.end()).min(highest_score)
+    }
+
+    /// Checks that the parameters describe hands
+    /// that can be drawn from the given tile distribution.
+    fn validate(&self, tiles: &[CountedTile]) -> Result<(), ParameterError> {
+        if self.hand_size.first == 0 {
+            return Err(ParameterError::EmptyHand);
+        }
+
+        let total_tiles = tiles
+            .iter()
+            .map(|counted_tile| counted_tile.occurrences)
+            .sum();
+        if self.hand_size.last > total_tiles {
+            return Err(ParameterError::HandTooLarge(
+                self.hand_size.last,
+                total_tiles,
+            ));
+        }
+
+        if let Some(&letter) = self
+            .include
+            .iter()
+            .find(|&&letter| self.exclude.contains(&letter))
+        {
+            return Err(ParameterError::IncludedAndExcluded(letter));
+        }
+
+        if let Some(&letter) = self.include.iter().find(|&&letter| {
+            !tiles
+                .iter()
+                .any(|counted_tile| counted_tile.tile.letter == letter)
+        }) {
+            return Err(ParameterError::UnknownIncludedLetter(letter));
+        }
+
+        if self.include.len() as u32 > self.hand_size.first {
+            return Err(ParameterError::TooManyIncludedLetters(
+                self.include.len() as u32,
+                self.hand_size.first,
+            ));
+        }
+
+        for conflicts in CONFLICTS {
+            let (_, is_set) = conflicts.option;
+            if !is_set(self) {
+                continue;
+            }
+
+            if let Some(&(option, _)) = conflicts.conflicts.iter().find(|(_, is_set)| is_set(self))
+            {
+                return Err((conflicts.error)(self, option));
+            }
+        }
+
+        if self.hand_size.is_range() {
+            // Each hand size is counted at once, so only the scores can be checked.
+            let scores = self.score_range();
+            if scores.is_empty() {
+                return Err(ParameterError::EmptyScoreRange(
+                    *scores.start(),
+                    *scores.end(),
+                ));
+            }
+
+            return Ok(());
+        }
+
+        // The top hands are searched from the highest score down, whatever the target score.
+        if self.top.is_some() {
+            return Ok(());
+        }
+
+        // Each hand of a deal is searched with its score and the tiles left by the others.
+        if !self.deal.is_empty() {
+            if self.hand_size() * self.deal.len() as u32 > total_tiles {
+                return Err(ParameterError::HandTooLarge(
+                    self.hand_size() * self.deal.len() as u32,
+                    total_tiles,
+                ));
+            }
+
+            return Ok(());
+        }
+
+        if matches!(self.output, OutputFormat::Bingos) && self.wordlist.is_none() {
+            return Err(ParameterError::MissingWordlist);
+        }
+
+        let (lowest_score, highest_score) = score_bounds(tiles, self.hand_size());
+        if self.at_least && self.target_score > highest_score {
+            return Err(ParameterError::UnreachableScore(
+                self.target_score,
+                lowest_score,
+                highest_score,
+            ));
+        }
+
+        // Every score of a hand is scanned, whatever the target score.
+        if matches!(self.output, OutputFormat::Unreachable) {
+            return Ok(());
+        }
+
+        let scores = self.score_range();
+        let is_range = self.min_score.is_some() || self.max_score.is_some();
+        if is_range || matches!(self.output, OutputFormat::ByScore) {
+            if scores.is_empty() {
+                return Err(ParameterError::EmptyScoreRange(
+                    *scores.start(),
+                    *scores.end(),
+                ));
+            }
+        } else if self.target_score < lowest_score || self.target_score > highest_score {
+            return Err(ParameterError::UnreachableScore(
+                self.target_score,
+                lowest_score,
+                highest_score,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// An option of the Scrabble® puzzle that can conflict with other options:
+/// its name in the error messages and whether it is set.
+type Setting = (&'static str, fn(&Parameters) -> bool);
+
+const OUTPUT_LIST: Setting = ("`--output list`", |parameters| {
+    matches!(parameters.output, OutputFormat::List)
+});
+const OUTPUT_COUNT: Setting = ("`--output count`", |parameters| {
+    matches!(parameters.output, OutputFormat::Count)
+});
+const OUTPUT_BY_SCORE: Setting = ("`--output by-score`", |parameters| {
+    matches!(parameters.output, OutputFormat::ByScore)
+});
+const OUTPUT_PROBABILITY: Setting = ("`--output probability`", |parameters| {
+    matches!(parameters.output, OutputFormat::Probability)
+});
+const OUTPUT_DISTRIBUTION: Setting = ("`--output distribution`", |parameters| {
+    matches!(parameters.output, OutputFormat::Distribution)
+});
+const OUTPUT_UNREACHABLE: Setting = ("`--output unreachable`", |parameters| {
+    matches!(parameters.output, OutputFormat::Unreachable)
+});
+const OUTPUT_BINGOS: Setting = ("`--output bingos`", |parameters| {
+    matches!(parameters.output, OutputFormat::Bingos)
+});
+const OUTPUT_BY_VALUES: Setting = ("`--output by-values`", |parameters| {
+    matches!(parameters.output, OutputFormat::ByValues)
+});
+const OUTPUT_STATISTICS: Setting = ("`--output statistics`", |parameters| {
+    matches!(parameters.output, OutputFormat::Statistics)
+});
+const HAND_SIZE_RANGE: Setting = ("a range of hand sizes", |parameters| {
+    parameters.hand_size.is_range()
+});
+const METHOD: Setting = ("`--method`", |parameters| {
+    !matches!(parameters.method, Method::Search)
+});
+const BLANK_MODE_WILDCARD: Setting = ("`--blank-mode wildcard`", |parameters| {
+    matches!(parameters.blank_mode, BlankMode::Wildcard)
+});
+const COMPARE_EDITIONS: Setting = ("`--compare-editions`", |parameters| {
+    parameters.compare_editions
+});
+const TARGET_PERCENT: Setting = ("`--target-percent`", |parameters| {
+    parameters.target_percent.is_some()
+});
+const MAXIMIZE: Setting = ("`--maximize`", |parameters| parameters.maximize);
+const DEAL: Setting = ("`--deal`", |parameters| !parameters.deal.is_empty());
+const TOP: Setting = ("`--top`", |parameters| parameters.top.is_some());
+const ORDERED: Setting = ("`--ordered`", |parameters| parameters.ordered);
+const STREAM: Setting = ("`--stream`", |parameters| parameters.stream);
+const WORDLIST: Setting = ("`--wordlist`", |parameters| parameters.wordlist.is_some());
+const SHARD: Setting = ("`--shard`", |parameters| parameters.shard.is_some());
+const MAX_LIST_BYTES: Setting = ("`--max-list-bytes`", |parameters| {
+    parameters.max_list_bytes.is_some()
+});
+const INCLUDE: Setting = ("`--include`", |parameters| !parameters.include.is_empty());
+const EXCLUDE: Setting = ("`--exclude`", |parameters| !parameters.exclude.is_empty());
+const DISTINCT_LETTERS: Setting = ("`--distinct-letters`", |parameters| {
+    parameters.distinct_letters
+});
+
+/// An option of the Scrabble® puzzle and the options that can't be used with it.
+struct Conflicts {
+    /// The option.
+    option: Setting,
+
+    /// The options that can't be used with it.
+    conflicts: &'static [Setting],
+
+    /// Returns the error for one of the options that can't be used with it.
+    error: fn(&Parameters, &'static str) -> ParameterError,
+}
+
+/// The options that can't be used together,
+/// which `Parameters::validate` checks in this order.
+const CONFLICTS: &[Conflicts] = &[
+    // Each hand size is counted at once, so only the scores can be checked.
+    Conflicts {
+        option: HAND_SIZE_RANGE,
+        conflicts: &[
+            OUTPUT_LIST,
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+            COMPARE_EDITIONS,
+            TARGET_PERCENT,
+            MAXIMIZE,
+            DEAL,
+            TOP,
+            ORDERED,
+            WORDLIST,
+            BLANK_MODE_WILDCARD,
+            SHARD,
+        ],
+        error: |_, option| ParameterError::HandSizeRange(option),
+    },
+    // The methods other than the search only count the hands and their draws.
+    Conflicts {
+        option: METHOD,
+        conflicts: &[
+            OUTPUT_LIST,
+            OUTPUT_BY_SCORE,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            DEAL,
+            TOP,
+            ORDERED,
+            COMPARE_EDITIONS,
+            SHARD,
+            WORDLIST,
+            BLANK_MODE_WILDCARD,
+        ],
+        error: |parameters, option| {
+            let method = match parameters.method {
+                Method::Search => "search",
+                Method::GeneratingFunction => "generating-function",
+                Method::Memoized => "memoized",
+            };
+            ParameterError::Method(method, option)
+        },
+    },
+    // Only the count output counts the sequences of draws instead of the hands.
+    Conflicts {
+        option: ORDERED,
+        conflicts: &[
+            OUTPUT_LIST,
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+            BLANK_MODE_WILDCARD,
+        ],
+        error: |_, option| ParameterError::Ordered(option),
+    },
+    // The top hands are searched from the highest score down, whatever the target score.
+    Conflicts {
+        option: TOP,
+        conflicts: &[
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+            BLANK_MODE_WILDCARD,
+        ],
+        error: |_, option| ParameterError::Top(option),
+    },
+    // Only the hands of the list output are drawn in alphabetical order.
+    Conflicts {
+        option: STREAM,
+        conflicts: &[
+            OUTPUT_COUNT,
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+            HAND_SIZE_RANGE,
+        ],
+        error: |_, option| ParameterError::Stream(option),
+    },
+    // Each hand of a deal is searched with its score and the tiles left by the others.
+    Conflicts {
+        option: DEAL,
+        conflicts: &[
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+            SHARD,
+            MAX_LIST_BYTES,
+            WORDLIST,
+            BLANK_MODE_WILDCARD,
+            INCLUDE,
+            EXCLUDE,
+        ],
+        error: |_, option| ParameterError::Deal(option),
+    },
+    // Only the outputs that count or list the hands expand the blanks.
+    Conflicts {
+        option: BLANK_MODE_WILDCARD,
+        conflicts: &[
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BINGOS,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+        ],
+        error: |_, option| ParameterError::BlankMode(option),
+    },
+    // The single tile of each letter would give the wrong number of ways to draw a hand.
+    Conflicts {
+        option: DISTINCT_LETTERS,
+        conflicts: &[OUTPUT_PROBABILITY, OUTPUT_DISTRIBUTION, OUTPUT_STATISTICS],
+        error: |_, option| ParameterError::DistinctLetters(option),
+    },
+    // The hands are checked against the word list one by one.
+    Conflicts {
+        option: WORDLIST,
+        conflicts: &[
+            OUTPUT_BY_SCORE,
+            OUTPUT_PROBABILITY,
+            OUTPUT_DISTRIBUTION,
+            OUTPUT_UNREACHABLE,
+            OUTPUT_BY_VALUES,
+            OUTPUT_STATISTICS,
+        ],
+        error: |_, option| ParameterError::Wordlist(option),
+    },
+];
+
+/// Returns the lowest and the highest score
+/// of a hand of `hand_size` tiles from the given tile distribution.
+///
+/// The distribution must contain at least `hand_size` tiles.
+fn score_bounds(tiles: &[CountedTile], hand_size: u32) -> (u32, u32) {
+    let tiles_by_value = group_tiles_by_value(tiles);
+
+    // Draw the tiles with the lowest (or highest) values first.
+    let score = |tiles_by_value: &mut dyn Iterator<Item = &TilesForValue>| {
+        let mut tiles_left = hand_size;
+        let mut score = 0;
+        for tiles_for_value in tiles_by_value {
+            let tiles_drawn = tiles_left.min(tiles_for_value.number_of_tiles);
+            score += tiles_for_value.value * tiles_drawn;
+            tiles_left -= tiles_drawn;
+        }
+
+        score
+    };
+
+    (
+        score(&mut tiles_by_value.iter()),
+        score(&mut tiles_by_value.iter().rev()),
+    )
+}
+
+/// The smallest score above the lowest score of a hand that no hand has.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Gap {
+    /// The smallest score that no hand has,
+    /// which is above the highest score if every score in between is reachable.
+    pub score: u32,
+
+    /// The lowest score of a hand.
+    pub lowest_score: u32,
+
+    /// The highest score of a hand.
+    pub highest_score: u32,
+}
+
+impl Display for Gap {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.score > self.highest_score {
+            write!(
+                fmt,
+                "{} (every score from {} to {} is reachable)",
+                self.score, self.lowest_score, self.highest_score
+            )
+        } else {
+            write!(
+                fmt,
+                "{} (the scores of a hand range from {} to {})",
+                self.score, self.lowest_score, self.highest_score
+            )
+        }
+    }
+}
+
+/// Which letters appear in the hands that have the target score.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LetterAnalysis {
+    /// The letters that appear in all the hands.
+    forced: BTreeSet<char>,
+
+    /// The letters that appear in some, but not all, of the hands.
+    some: BTreeSet<char>,
+
+    /// The letters that don't appear in any hand.
+    none: BTreeSet<char>,
+}
+
+impl Display for LetterAnalysis {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let render = |letters: &BTreeSet<char>| {
+            letters
+                .iter()
+                .map(|&letter| if letter == ' ' { '_' } else { letter })
+                .join(" ")
+        };
+
+        writeln!(fmt, "forced: {}", render(&self.forced))?;
+        writeln!(fmt, "some: {}", render(&self.some))?;
+        write!(fmt, "none: {}", render(&self.none))
+    }
+}
+
+/// The result of drawing random hands with `simulate`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Simulation {
+    /// The number of random hands that were drawn.
+    samples: usize,
+
+    /// The number of those hands that have the target score.
+    hits: usize,
+
+    /// The target score for a hand.
+    target_score: u32,
+}
+
+impl Simulation {
+    /// Returns the fraction of the random hands that have the target score.
+    pub fn frequency(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.samples as f64
+        }
+    }
+}
+
+impl Display for Simulation {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "samples: {}", self.samples)?;
+        write!(
+            fmt,
+            "hands with a score of {}: {} ({:.2}%)",
+            self.target_score,
+            self.hits,
+            100.0 * self.frequency()
+        )
+    }
+}
+
+/// The chance that a random hand drawn from the bag has the target score.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Probability {
+    /// The number of distinct hands that have the target score.
+    pub hands: SolutionCount,
+
+    /// The number of ways to draw these hands,
+    /// telling apart the tiles that have the same letter.
+    pub draws: u128,
+
+    /// The number of ways to draw a hand from the bag.
+    pub total_draws: u128,
+}
+
+impl Probability {
+    /// Returns the fraction of the draws that give a hand with the target score.
+    pub fn probability(&self) -> f64 {
+        if self.total_draws == 0 {
+            0.0
+        } else {
+            self.draws as f64 / self.total_draws as f64
+        }
+    }
+}
+
+impl Display for Probability {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{:.4e} ({} of {} draws, {} hands)",
+            self.probability(),
+            self.draws,
+            self.total_draws,
+            self.hands
+        )
+    }
+}
+
+/// The mean and the variance of the score of a random hand drawn from the bag,
+/// kept as exact sums over the draws.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScoreStatistics {
+    /// The number of ways to draw a hand from the bag.
+    pub draws: u128,
+
+    /// The sum of the scores of the draws.
+    pub score_sum: u128,
+
+    /// The sum of the squares of the scores of the draws.
+    pub square_sum: u128,
+
+    /// The target score, to compare with the mean.
+    pub target_score: u32,
+}
+
+impl ScoreStatistics {
+    /// Returns the mean score of a random hand.
+    pub fn mean(&self) -> f64 {
+        if self.draws == 0 {
+            0.0
+        } else {
+            self.score_sum as f64 / self.draws as f64
+        }
+    }
+
+    /// Returns the variance of the score of a random hand.
+    pub fn variance(&self) -> f64 {
+        if self.draws == 0 {
+            return 0.0;
+        }
+
+        // The sums are subtracted exactly when they fit, so that a single score has no variance.
+        match self
+            .draws
+            .checked_mul(self.square_sum)
+            .zip(self.score_sum.checked_mul(self.score_sum))
+        {
+            Some((squares, square_of_sum)) => {
+                (squares - square_of_sum) as f64 / (self.draws as f64).powi(2)
+            }
+            None => (self.square_sum as f64 / self.draws as f64 - self.mean().powi(2)).max(0.0),
+        }
+    }
+
+    /// Returns the standard deviation of the score of a random hand.
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Returns the number of standard deviations between the mean and the target score,
+    /// which is negative if the target score is below the mean,
+    /// or 0 if every hand has the same score.
+    pub fn z_score(&self) -> f64 {
+        let standard_deviation = self.standard_deviation();
+        if standard_deviation == 0.0 {
+            0.0
+        } else {
+            (f64::from(self.target_score) - self.mean()) / standard_deviation
+        }
+    }
+}
+
+impl Display for ScoreStatistics {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "draws: {}", self.draws)?;
+        writeln!(fmt, "mean: {:.4}", self.mean())?;
+        writeln!(fmt, "variance: {:.4}", self.variance())?;
+        writeln!(fmt, "standard deviation: {:.4}", self.standard_deviation())?;
+        write!(
+            fmt,
+            "target score {}: {:.4} standard deviations from the mean",
+            self.target_score,
+            self.z_score()
+        )
+    }
+}
+
+/// The point value of each letter of a tile distribution,
+/// to write the hands of the list outputs as their tiles.
+#[derive(Clone, Debug)]
+pub struct LetterValues {
+    values: HashMap<char, u32>,
+}
+
+impl LetterValues {
+    /// Reads the point values of the letters from the tile distribution of the parameters.
+    pub fn new(parameters: &Parameters) -> Result<Self, Error> {
+        Ok(Self::from_tiles(&tiles(parameters)?))
+    }
+
+    /// Reads the point values of the letters from a tile distribution.
+    fn from_tiles(tiles: &[CountedTile]) -> Self {
+        let values = tiles
+            .iter()
+            .map(|counted_tile| (counted_tile.tile.letter, counted_tile.tile.value))
+            .collect();
+        LetterValues { values }
+    }
+
+    /// Returns the point value of a letter, or 0 if the tile distribution doesn't have it.
+    fn value(&self, letter: char) -> u32 {
+        self.values.get(&letter).map_or(0, |&value| value)
+    }
+
+    /// Writes a hand of a list output as its tiles, in the order of the hand,
+    /// with the number of tiles of each letter and the total score.
+    ///
+    /// The hand must be left as drawn with `Parameters::set_structured`.
+    /// A blank tile, written as a space or as the letter it stands for (e.g. `[=S]`),
+    /// is written as `_`, with the letter it stands for, if any.
+    pub fn hand_tiles(&self, hand: &str) -> HandTiles {
+        let mut tiles: Vec<HandTile> = vec![];
+        let mut chars = hand.chars();
+        while let Some(letter) = chars.next() {
+            let stands_for = if letter == '[' {
+                // Skip the `=` and the `]` around the letter of a wildcard.
+                let stands_for = chars.nth(1);
+                chars.next();
+                stands_for
+            } else {
+                None
+            };
+            let value = match letter {
+                '[' => self.value(' '),
+                letter => self.value(letter),
+            };
+            let letter = match letter {
+                '[' | ' ' => '_',
+                letter => letter,
+            };
+            match tiles
+                .iter_mut()
+                .find(|tile| tile.letter == letter && tile.stands_for == stands_for)
+            {
+                Some(tile) => tile.count += 1,
+                None => tiles.push(HandTile {
+                    letter,
+                    value,
+                    count: 1,
+                    stands_for,
+                }),
+            }
+        }
+
+        let total = tiles.iter().map(|tile| tile.value * tile.count).sum();
+        HandTiles { tiles, total }
+    }
+}
+
+/// A hand written as its tiles.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandTiles {
+    /// The tiles of each letter of the hand.
+    pub tiles: Vec<HandTile>,
+
+    /// The score of the hand.
+    pub total: u32,
+}
+
+/// The tiles of a letter in a hand.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandTile {
+    /// The letter of the tiles, or `_` for blank tiles.
+    pub letter: char,
+
+    /// The point value of each tile.
+    pub value: u32,
+
+    /// The number of tiles.
+    pub count: u32,
+
+    /// The letter that the blank tiles stand for with `--blank-mode wildcard`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub stands_for: Option<char>,
+}
+
+/// The hands whose tiles have the same values.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValueGroup {
+    /// The values of the tiles, from the highest value down.
+    pub values: Vec<u32>,
+
+    /// The number of distinct hands whose tiles have these values.
+    pub hands: SolutionCount,
+}
+
+impl Display for ValueGroup {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}: {} hands",
+            self.values.iter().join("+"),
+            self.hands
+        )
+    }
+}
+
+/// Renders all the hands in a list like the list output,
+/// with `--show-values`, `--letters-only`, `--hand-format` and the colors,
+/// unless the hands are written as their tiles.
+fn render_hands(hands: &mut SolutionList, tiles: &[CountedTile], parameters: &Parameters) {
+    if parameters.structured {
+        return;
+    }
+
+    if parameters.show_values {
+        let letter_values = LetterValues::from_tiles(tiles);
+        for hand in hands.iter_mut() {
+            *hand = render_values(hand, &letter_values);
+        }
+    }
+
+    if parameters.letters_only {
+        apply_letters_only(hands);
+    }
+
+    apply_hand_format(hands, parameters.hand_format);
+    if parameters.color {
+        apply_colors(hands, tiles, parameters);
+    }
+}
+
+/// Renders all the hands in a list with `render_letters_only`.
+fn apply_letters_only(hands: &mut SolutionList) {
+    for hand in hands {
+        *hand = render_letters_only(hand);
+    }
+}
+
+/// Renders all the hands in a list with `render_hand`.
+fn apply_hand_format(hands: &mut SolutionList, hand_format: HandFormat) {
+    if let HandFormat::Plain = hand_format {
+        return;
+    }
+
+    for hand in hands {
+        *hand = render_hand(hand, hand_format);
+    }
+}
+
+/// Colors the letters of all the hands in a list by their point value,
+/// keeping the rest of the rendering (separators and multiplicities) as is.
+fn apply_colors(hands: &mut SolutionList, tiles: &[CountedTile], parameters: &Parameters) {
+    // Only the formats without separators show the blank tiles as spaces.
+    let blank = match parameters.hand_format {
+        _ if parameters.letters_only || parameters.show_values => '_',
+        HandFormat::Dashed | HandFormat::Spaced => '_',
+        HandFormat::Plain | HandFormat::Lower => ' ',
+    };
+    let values: BTreeMap<char, u32> = tiles
+        .iter()
+        .map(|counted_tile| (counted_tile.tile.letter, counted_tile.tile.value))
+        .collect();
+    for hand in hands {
+        // The letters that blank tiles stand for are not colored.
+        let mut in_blank = false;
+        *hand = hand
+            .chars()
+            .map(|letter| {
+                match letter {
+                    '[' => in_blank = true,
+                    ']' => in_blank = false,
+                    _ => {}
+                }
+                if in_blank || letter == ']' {
+                    return letter.to_string();
+                }
+
+                let tile_letter = match letter {
+                    letter if letter == blank => ' ',
+                    ' ' | '_' => return letter.to_string(),
+                    letter => letter.to_ascii_uppercase(),
+                };
+                let color = values
+                    .get(&tile_letter)
+                    .and_then(|&value| value_color(value));
+                color::paint(color, letter).to_string()
+            })
+            .collect();
+    }
+}
+
+/// Returns the color of the letters with the given point value,
+/// or `None` for the most common value, which is left as is.
+fn value_color(value: u32) -> Option<Color> {
+    match value {
+        0 => Some(Color::Dim),
+        1 => None,
+        2 => Some(Color::Green),
+        3 => Some(Color::Cyan),
+        4 => Some(Color::Blue),
+        5..=7 => Some(Color::Magenta),
+        8 | 9 => Some(Color::Yellow),
+        _ => Some(Color::Red),
+    }
+}
+
+/// Returns the hands that a hand stands for when its blank tiles are wildcards:
+/// one for each set of letters that the blanks can stand for,
+/// with each blank written as the letter it stands for (e.g. `A[=S]KJXQZ`).
+fn expand_blanks(hand: &str) -> SolutionList {
+    let blanks = hand.chars().filter(|&letter| letter == ' ').count();
+    ('A'..='Z')
+        .combinations_with_replacement(blanks)
+        .map(|blank_letters| {
+            let mut blank_letters = blank_letters.into_iter();
+            hand.chars()
+                .map(|letter| match letter {
+                    ' ' => format!("[={}]", blank_letters.next().unwrap()),
+                    letter => letter.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the number of hands that `expand_blanks` returns for a hand.
+fn wildcard_hands(hand: &str) -> SolutionCount {
+    let blanks = hand.chars().filter(|&letter| letter == ' ').count();
+    multisets(26, blanks as u32)
+}
+
+/// Renders a hand as its tiles separated by spaces,
+/// each with its point value as a subscript, followed by the score of the hand
+/// (e.g. `A₁ F₄ K₅ J₈ X₈ Q₁₀ Z₁₀ = 46`).
+///
+/// Blank tiles are rendered as `_`.
+fn render_values(hand: &str, letter_values: &LetterValues) -> String {
+    let subscript = |value: u32| -> String {
+        value
+            .to_string()
+            .chars()
+            .map(|digit| {
+                let digit = digit.to_digit(10).expect("a number is written with digits");
+                char::from_u32('₀' as u32 + digit).expect("the subscript digits are contiguous")
+            })
+            .collect()
+    };
+
+    let tiles = hand
+        .chars()
+        .map(|letter| {
+            let value = letter_values.value(letter);
+            let letter = if letter == ' ' { '_' } else { letter };
+            format!("{}{}", letter, subscript(value))
+        })
+        .join(" ");
+    let score: u32 = hand.chars().map(|letter| letter_values.value(letter)).sum();
+    format!("{} = {}", tiles, score)
+}
+
+/// Renders the letters of a hand in the given format.
+fn render_hand(hand: &str, hand_format: HandFormat) -> String {
+    let separated = |separator| {
+        hand.chars()
+            .map(|letter| if letter == ' ' { '_' } else { letter })
+            .join(separator)
+    };
+
+    match hand_format {
+        HandFormat::Plain => hand.to_owned(),
+        HandFormat::Dashed => separated("-"),
+        HandFormat::Spaced => separated(" "),
+        HandFormat::Lower => hand.to_lowercase(),
+    }
+}
+
+/// Renders a hand as its distinct letters in alphabetical order,
+/// annotating letters that occur more than once with their multiplicity.
+///
+/// Blank tiles are rendered as `_`.
+fn render_letters_only(hand: &str) -> String {
+    hand.chars()
+        .fold(BTreeMap::new(), |mut map, letter| {
+            *map.entry(letter).or_insert(0) += 1;
+            map
+        })
+        .into_iter()
+        .map(|(letter, occurrences)| {
+            let letter = if letter == ' ' { '_' } else { letter };
+            if occurrences > 1 {
+                format!("{}×{}", letter, occurrences)
+            } else {
+                letter.to_string()
+            }
+        })
+        .join(" ")
+}
+
+/// Builds `Parameters` without going through the command line.
+///
+/// Each method corresponds to the command-line option with the same name.
+#[derive(Debug)]
+pub struct ParametersBuilder {
+    /// The parameters built so far.
+    parameters: Parameters,
+
+    /// Whether the target score was set explicitly.
+    target_score_set: bool,
+}
+
+impl ParametersBuilder {
+    /// Sets the number of tiles in a hand.
+    pub fn hand_size(mut self, hand_size: u32) -> Self {
+        self.parameters.hand_size = HandSize::from(hand_size);
+        self
+    }
+
+    /// Sets a range of hand sizes to count the hands of each size.
+    pub fn hand_sizes(mut self, hand_sizes: RangeInclusive<u32>) -> Self {
+        self.parameters.hand_size = HandSize {
+            first: *hand_sizes.start(),
+            last: *hand_sizes.end(),
+        };
+        self
+    }
+
+    /// Sets whether to find the hands scoring at least the target score.
+    pub fn at_least(mut self, at_least: bool) -> Self {
+        self.parameters.at_least = at_least;
+        self
+    }
+
+    /// Sets the number of hands with the highest scores to list
+    /// instead of the hands with the target score.
+    pub fn top(mut self, top: usize) -> Self {
+        self.parameters.top = NonZeroUsize::new(top);
+        self
+    }
+
+    /// Sets whether the sequences of draws are counted instead of the hands.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.parameters.ordered = ordered;
+        self
+    }
+
+    /// Sets whether the hands of the list output are printed as soon as they are found.
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.parameters.stream = stream;
+        self
+    }
+
+    /// Sets the scores of the hands dealt to several players from the same bag.
+    pub fn deal(mut self, scores: impl IntoIterator<Item = u32>) -> Self {
+        self.parameters.deal = scores.into_iter().collect();
+        self
+    }
+
+    /// Sets whether to find the hands with the highest possible score
+    /// instead of the target score.
+    pub fn maximize(mut self, maximize: bool) -> Self {
+        self.parameters.maximize = maximize;
+        self
+    }
+
+    /// Sets the total score of the hands to find.
+    pub fn target_score(mut self, target_score: u32) -> Self {
+        self.parameters.target_score = target_score;
+        self.target_score_set = true;
+        self
+    }
+
+    /// Sets the target score as a percentage of the highest possible score.
+    pub fn target_percent(mut self, target_percent: u32) -> Self {
+        self.parameters.target_percent = Some(target_percent);
+        self
+    }
+
+    /// Sets the lowest score to report when grouping hands by score.
+    pub fn min_score(mut self, min_score: u32) -> Self {
+        self.parameters.min_score = Some(min_score);
+        self
+    }
+
+    /// Sets the highest score to report when grouping hands by score.
+    pub fn max_score(mut self, max_score: u32) -> Self {
+        self.parameters.max_score = Some(max_score);
+        self
+    }
+
+    /// Sets how the solution should be presented.
+    pub fn output(mut self, output: OutputFormat) -> Self {
+        self.parameters.output = output;
+        self
+    }
+
+    /// Sets the part of the search to search.
+    pub fn shard(mut self, shard: Shard) -> Self {
+        self.parameters.shard = Some(shard);
+        self
+    }
+
+    /// Sets the edition whose tile distribution is used.
+    pub fn edition(mut self, edition: Edition) -> Self {
+        self.parameters.edition = edition;
+        self
+    }
+
+    /// Sets the file to read the tile distribution from.
+    pub fn tiles(mut self, tiles: impl Into<PathBuf>) -> Self {
+        self.parameters.tiles = Some(tiles.into());
+        self
+    }
+
+    /// Sets whether to report on the tile distribution instead of solving the puzzle.
+    pub fn distribution_check(mut self, distribution_check: bool) -> Self {
+        self.parameters.distribution_check = distribution_check;
+        self
+    }
+
+    /// Sets whether to report how many subtrees of the search were pruned after solving.
+    pub fn explain_pruning(mut self, explain_pruning: bool) -> Self {
+        self.parameters.explain_pruning = explain_pruning;
+        self
+    }
+
+    /// Sets the number of tiles that the distribution check expects.
+    pub fn expected_total(mut self, expected_total: u32) -> Self {
+        self.parameters.expected_total = Some(expected_total);
+        self
+    }
+
+    /// Sets whether to show the hands as their tiles with their point values.
+    pub fn show_values(mut self, show_values: bool) -> Self {
+        self.parameters.show_values = show_values;
+        self
+    }
+
+    /// Sets whether to list the hands as letters only.
+    pub fn letters_only(mut self, letters_only: bool) -> Self {
+        self.parameters.letters_only = letters_only;
+        self
+    }
+
+    /// Sets how the letters of a hand should be written.
+    pub fn hand_format(mut self, hand_format: HandFormat) -> Self {
+        self.parameters.hand_format = hand_format;
+        self
+    }
+
+    /// Sets the largest size of the list of hands, in bytes.
+    pub fn max_list_bytes(mut self, max_list_bytes: usize) -> Self {
+        self.parameters.max_list_bytes = Some(max_list_bytes);
+        self
+    }
+
+    /// Sets whether the letters in the list outputs are colored by their point value.
+    pub fn color(mut self, color: bool) -> Self {
+        self.parameters.color = color;
+        self
+    }
+
+    /// Sets the number of hands in the list outputs after which the search stops.
+    pub fn max_solutions(mut self, max_solutions: NonZeroUsize) -> Self {
+        self.parameters.max_solutions = Some(max_solutions);
+        self
+    }
+
+    /// Sets the word list that the hands must form a word of.
+    pub fn wordlist(mut self, wordlist: impl Into<PathBuf>) -> Self {
+        self.parameters.wordlist = Some(wordlist.into());
+        self
+    }
+
+    /// Sets whether the hands can form a word with only some of their tiles.
+    pub fn shorter_words(mut self, shorter_words: bool) -> Self {
+        self.parameters.shorter_words = shorter_words;
+        self
+    }
+
+    /// Sets the tiles already out of the bag.
+    pub fn removed(mut self, removed: impl Into<String>) -> Self {
+        self.parameters.removed = Some(removed.into());
+        self
+    }
+
+    /// Sets the letters that the hands must have.
+    pub fn include(mut self, letters: impl IntoIterator<Item = char>) -> Self {
+        self.parameters.include = letters.into_iter().collect();
+        self
+    }
+
+    /// Sets the letters that the hands must not have.
+    pub fn exclude(mut self, letters: impl IntoIterator<Item = char>) -> Self {
+        self.parameters.exclude = letters.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the hands have no repeated letter.
+    pub fn distinct_letters(mut self, distinct_letters: bool) -> Self {
+        self.parameters.distinct_letters = distinct_letters;
+        self
+    }
+
+    /// Sets the number of sets of tiles mixed into the bag.
+    pub fn bags(mut self, bags: Bags) -> Self {
+        self.parameters.bags = bags;
+        self
+    }
+
+    /// Replaces the point value of a letter (a space for the blank tile).
+    pub fn value(mut self, letter: char, value: u32) -> Self {
+        self.parameters.values.push((letter, value));
+        self
+    }
+
+    /// Sets how the hands are counted.
+    pub fn method(mut self, method: Method) -> Self {
+        self.parameters.method = method;
+        self
+    }
+
+    /// Sets how blank tiles are treated.
+    pub fn blank_mode(mut self, blank_mode: BlankMode) -> Self {
+        self.parameters.blank_mode = blank_mode;
+        self
+    }
+
+    /// Sets whether to count the hands for every known edition.
+    pub fn compare_editions(mut self, compare_editions: bool) -> Self {
+        self.parameters.compare_editions = compare_editions;
+        self
+    }
+
+    /// Checks that the options can be used together
+    /// and returns the parameters.
+    pub fn build(self) -> Result<Parameters, BuildError> {
+        let parameters = self.parameters;
+        if parameters.target_percent.is_some() {
+            if self.target_score_set {
+                return Err(BuildError::Conflict("target_percent", "target_score"));
+            }
+
+            if parameters.compare_editions {
+                return Err(BuildError::Conflict("target_percent", "compare_editions"));
+            }
+
+            if parameters.target_percent > Some(100) {
+                return Err(BuildError::TooLarge("target_percent", 100));
+            }
+        }
+
+        if parameters.expected_total.is_some() && !parameters.distribution_check {
+            return Err(BuildError::MissingRequirement(
+                "expected_total",
+                "distribution_check",
+            ));
+        }
+
+        if parameters.shorter_words && parameters.wordlist.is_none() {
+            return Err(BuildError::MissingRequirement("shorter_words", "wordlist"));
+        }
+
+        if parameters.at_least {
+            if parameters.min_score.is_some() {
+                return Err(BuildError::Conflict("at_least", "min_score"));
+            }
+
+            if parameters.max_score.is_some() {
+                return Err(BuildError::Conflict("at_least", "max_score"));
+            }
+        }
+
+        if parameters.ordered {
+            let conflicts = [
+                ("compare_editions", parameters.compare_editions),
+                ("deal", !parameters.deal.is_empty()),
+                ("top", parameters.top.is_some()),
+                ("stream", parameters.stream),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("ordered", option));
+            }
+        }
+
+        if parameters.top.is_some() {
+            let conflicts = [
+                ("target_score", self.target_score_set),
+                ("target_percent", parameters.target_percent.is_some()),
+                ("min_score", parameters.min_score.is_some()),
+                ("max_score", parameters.max_score.is_some()),
+                ("at_least", parameters.at_least),
+                ("maximize", parameters.maximize),
+                ("deal", !parameters.deal.is_empty()),
+                ("stream", parameters.stream),
+                ("compare_editions", parameters.compare_editions),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("top", option));
+            }
+        }
+
+        if !parameters.deal.is_empty() {
+            let conflicts = [
+                ("target_score", self.target_score_set),
+                ("target_percent", parameters.target_percent.is_some()),
+                ("min_score", parameters.min_score.is_some()),
+                ("max_score", parameters.max_score.is_some()),
+                ("at_least", parameters.at_least),
+                ("maximize", parameters.maximize),
+                ("compare_editions", parameters.compare_editions),
+                ("stream", parameters.stream),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("deal", option));
+            }
+        }
+
+        if parameters.maximize {
+            let conflicts = [
+                ("target_score", self.target_score_set),
+                ("target_percent", parameters.target_percent.is_some()),
+                ("min_score", parameters.min_score.is_some()),
+                ("max_score", parameters.max_score.is_some()),
+                ("at_least", parameters.at_least),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("maximize", option));
+            }
+        }
+
+        if parameters.tiles.is_some() && !matches!(parameters.edition, Edition::English) {
+            return Err(BuildError::Conflict("edition", "tiles"));
+        }
+
+        if parameters.letters_only && !matches!(parameters.hand_format, HandFormat::Plain) {
+            return Err(BuildError::Conflict("hand_format", "letters_only"));
+        }
+
+        if parameters.show_values {
+            let conflicts = [
+                ("letters_only", parameters.letters_only),
+                (
+                    "hand_format",
+                    !matches!(parameters.hand_format, HandFormat::Plain),
+                ),
+                (
+                    "blank_mode",
+                    matches!(parameters.blank_mode, BlankMode::Wildcard),
+                ),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("show_values", option));
+            }
+        }
+
+        if let BlankMode::Wildcard = parameters.blank_mode {
+            let conflicts = [
+                ("letters_only", parameters.letters_only),
+                (
+                    "hand_format",
+                    !matches!(parameters.hand_format, HandFormat::Plain),
+                ),
+                ("wordlist", parameters.wordlist.is_some()),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("blank_mode", option));
+            }
+        }
+
+        if parameters.compare_editions {
+            // The editions are only counted at the target score.
+            let conflicts = [
+                ("edition", !matches!(parameters.edition, Edition::English)),
+                ("tiles", parameters.tiles.is_some()),
+                ("min_score", parameters.min_score.is_some()),
+                ("max_score", parameters.max_score.is_some()),
+                ("at_least", parameters.at_least),
+                ("maximize", parameters.maximize),
+                ("letters_only", parameters.letters_only),
+                (
+                    "hand_format",
+                    !matches!(parameters.hand_format, HandFormat::Plain),
+                ),
+                ("show_values", parameters.show_values),
+                ("max_list_bytes", parameters.max_list_bytes.is_some()),
+                ("wordlist", parameters.wordlist.is_some()),
+                ("stream", parameters.stream),
+                (
+                    "blank_mode",
+                    matches!(parameters.blank_mode, BlankMode::Wildcard),
+                ),
+            ];
+            if let Some(&(option, _)) = conflicts.iter().find(|&&(_, set)| set) {
+                return Err(BuildError::Conflict("compare_editions", option));
+            }
+        }
+
+        Ok(parameters)
+    }
+}
+
+/// The number of tiles in a hand, given as `7` on the command line,
+/// or a range of hand sizes, given as `2..=10`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandSize {
+    /// The smallest hand size.
+    first: u32,
+
+    /// The largest hand size, which is the same as the smallest one for a single hand size.
+    last: u32,
+}
+
+impl HandSize {
+    /// Returns whether there is more than one hand size.
+    fn is_range(&self) -> bool {
+        self.first != self.last
+    }
+}
+
+impl From<u32> for HandSize {
+    fn from(hand_size: u32) -> Self {
+        Self {
+            first: hand_size,
+            last: hand_size,
+        }
+    }
+}
+
+impl FromStr for HandSize {
+    type Err = HandSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, "..=");
+        match (parts.next(), parts.next()) {
+            (Some(first), Some(last)) => {
+                let first = first.trim().parse()?;
+                let last = last.trim().parse()?;
+                if first > last {
+                    return Err(HandSizeError::EmptyRange(first, last));
+                }
+
+                Ok(Self { first, last })
+            }
+            _ => Ok(Self::from(s.trim().parse::<u32>()?)),
+        }
+    }
+}
+
+impl Display for HandSize {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_range() {
+            write!(fmt, "{}..={}", self.first, self.last)
+        } else {
+            write!(fmt, "{}", self.first)
+        }
+    }
+}
+
+/// Serializes a single hand size as a number and a range like it is given on the command line.
+#[cfg(feature = "serde")]
+impl Serialize for HandSize {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_range() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.first)
+        }
+    }
+}
+
+/// The number of sets of tiles mixed into the bag,
+/// given as `2` on the command line,
+/// or for some letters, given as `E=3,S=2` or `2,E=3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bags {
+    /// The number of sets of the letters that are not in `letters`.
+    all: u32,
+
+    /// The number of sets of each letter that has its own.
+    letters: BTreeMap<char, u32>,
+}
+
+impl Bags {
+    /// Returns a single set of tiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of sets of every letter without its own.
+    pub fn all(mut self, bags: u32) -> Self {
+        self.all = bags;
+        self
+    }
+
+    /// Sets the number of sets of a letter (a space for the blank tile).
+    pub fn letter(mut self, letter: char, bags: u32) -> Self {
+        self.letters.insert(letter, bags);
+        self
+    }
+
+    /// Multiplies the occurrences of each letter of the tile distribution by its number of sets.
+    fn apply(
+        &self,
+        tiles: Cow<'static, [CountedTile]>,
+    ) -> Result<Cow<'static, [CountedTile]>, TilesError> {
+        if let Some(&letter) = self.letters.keys().find(|&&letter| {
+            !tiles
+                .iter()
+                .any(|counted_tile| counted_tile.tile.letter == letter)
+        }) {
+            return Err(TilesError::UnknownBagsLetter(letter));
+        }
+
+        if *self == Self::default() {
+            return Ok(tiles);
+        }
+
+        // A product that overflows saturates,
+        // so that the distribution is rejected as too large.
+        let mut tiles = tiles.into_owned();
+        for counted_tile in &mut tiles {
+            counted_tile.occurrences = counted_tile.occurrences.saturating_mul(
+                self.letters
+                    .get(&counted_tile.tile.letter)
+                    .copied()
+                    .unwrap_or(self.all),
+            );
+        }
+
+        Ok(Cow::Owned(tiles))
+    }
+}
+
+impl Default for Bags {
+    fn default() -> Self {
+        Self {
+            all: 1,
+            letters: BTreeMap::new(),
+        }
+    }
+}
+
+impl FromStr for Bags {
+    type Err = BagsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bags = Self::default();
+        for (index, part) in s.split(',').map(str::trim).enumerate() {
+            let mut fields = part.splitn(2, '=');
+            match (fields.next(), fields.next()) {
+                (Some(letter), Some(number)) => {
+                    let letter = parse_letter(letter.trim())
+                        .ok_or_else(|| BagsError::InvalidLetter(letter.trim().to_owned()))?;
+                    bags.letters.insert(letter, number.trim().parse()?);
+                }
+                // Only the first part can give the number of sets of the other letters.
+                (Some(number), None) if index == 0 => bags.all = number.parse()?,
+                _ => return Err(BagsError::MisplacedNumber(part.to_owned())),
+            }
+        }
+
+        Ok(bags)
+    }
+}
+
+impl Display for Bags {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let letters = self.letters.iter().map(|(&letter, bags)| {
+            let letter = if letter == ' ' { '_' } else { letter };
+            format!("{}={}", letter, bags)
+        });
+        if self.all == 1 && !self.letters.is_empty() {
+            write!(fmt, "{}", letters.format(","))
+        } else {
+            write!(
+                fmt,
+                "{}",
+                iter::once(self.all.to_string()).chain(letters).format(",")
+            )
+        }
+    }
+}
+
+/// Serializes the number of sets like it is given on the command line.
+#[cfg(feature = "serde")]
+impl Serialize for Bags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when parsing the number of sets of tiles.
+    #[derive(Debug)]
+    pub enum BagsError {
+        /// A number of sets is not a valid number.
+        ParseIntError(err: ParseIntError) {
+            cause(err)
+            display("{}", err)
+            from()
+        }
+
+        /// A letter is not a single character or `_`.
+        InvalidLetter(letter: String) {
+            display("`{}` is not a letter", letter)
+        }
+
+        /// The number of sets of the other letters is not given first.
+        MisplacedNumber(part: String) {
+            display("`{}` must be the first number of sets, or be given as `<letter>=<number>`", part)
+        }
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when parsing a hand size.
+    #[derive(Debug)]
+    pub enum HandSizeError {
+        /// A hand size is not a valid number.
+        ParseIntError(err: ParseIntError) {
+            cause(err)
+            display("{}", err)
+            from()
+        }
+
+        /// The first hand size of the range is larger than the last one.
+        EmptyRange(first: u32, last: u32) {
+            display("the range {}..={} has no hand sizes", first, last)
+        }
+    }
+}
+
+/// Choices for how the solution should be presented.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum OutputFormat {
+    /// Count the hands.
+    Count,
+
+    /// List the hands.
+    List,
+
+    /// List the hands for each score from `--min-score` to `--max-score`.
+    #[cfg_attr(feature = "clap", value(alias = "byscore"))]
+    ByScore,
+
+    /// Compute the chance that a random hand drawn from the bag has the target score.
+    Probability,
+
+    /// Count the hands and compute their chance for every score,
+    /// or for each score from `--min-score` to `--max-score`.
+    Distribution,
+
+    /// Find the smallest score above the lowest score of a hand that no hand has.
+    Unreachable,
+
+    /// List the words of `--wordlist` with as many letters as a hand
+    /// that have the target score and can be drawn from the bag.
+    Bingos,
+
+    /// Group the hands by the values of their tiles, from the highest value down,
+    /// and count the hands of each group.
+    #[cfg_attr(feature = "clap", value(alias = "byvalues"))]
+    ByValues,
+
+    /// Compute the mean and the variance of the score of a random hand drawn from the bag,
+    /// and how far the target score is from the mean.
+    #[cfg_attr(feature = "clap", value(alias = "stats"))]
+    Statistics,
+}
+
+/// The editions of Scrabble® whose tile distribution can be used.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Edition {
+    /// The standard English edition, with 100 tiles.
+    English,
+
+    /// Super Scrabble®, with 200 tiles.
+    Super,
+}
+
+impl Edition {
+    /// Returns the distribution of tiles in the edition.
+    fn tiles(self) -> &'static [CountedTile] {
+        match self {
+            Self::English => STANDARD_ENGLISH_SCRABBLE_TILES,
+            Self::Super => SUPER_SCRABBLE_TILES,
+        }
+    }
+}
+
+/// Choices for how the hands are counted.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Method {
+    /// Search the combinations of tile values that have the target score,
+    /// then the hands with each combination.
+    Search,
+
+    /// Read the count off the product of the generating functions of the letters.
+    GeneratingFunction,
+
+    /// Draw the tile values like the search,
+    /// remembering the count for each tile value, tiles left to draw and score left.
+    Memoized,
+}
+
+/// Choices for how blank tiles are treated.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum BlankMode {
+    /// A blank tile is a tile of its own, written as a space.
+    Tile,
+
+    /// A blank tile stands for any letter, and is written as that letter.
+    Wildcard,
+}
+
+/// Choices for how the letters of a hand should be written.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum HandFormat {
+    /// Write the letters together, in uppercase.
+    Plain,
+
+    /// Separate the letters with `-`.
+    Dashed,
+
+    /// Separate the letters with a space.
+    Spaced,
+
+    /// Write the letters together, in lowercase.
+    Lower,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Output {
+    /// The number of valid hands.
+    Count(SolutionCount),
+
+    /// The chance of drawing a valid hand.
+    Probability(Probability),
+
+    /// The number of hands and the chance of drawing one for each score that a hand can have.
+    Distribution(BTreeMap<u32, Probability>),
+
+    /// The smallest score above the lowest score of a hand that no hand has.
+    Unreachable(Gap),
+
+    /// The full list of valid hands.
+    List(SolutionList),
+
+    /// The full list of deals, with the hand of each player.
+    Deals(Vec<SolutionList>),
+
+    /// The words of the word list that a valid hand spells.
+    Bingos(Vec<String>),
+
+    /// The full list of hands for each score in a range.
+    ByScore(BTreeMap<u32, SolutionList>),
+
+    /// The hands with the highest scores, with their score, from the highest score down.
+    Top(Vec<(u32, String)>),
+
+    /// The number of hands for each combination of tile values that has the target score.
+    ByValues(Vec<ValueGroup>),
+
+    /// The mean and the variance of the score of a random hand.
+    Statistics(ScoreStatistics),
+
+    /// The number of sequences in which the valid hands can be drawn one tile at a time.
+    Sequences(u128),
+
+    /// The number of valid hands for each built-in edition.
+    Editions(Vec<(Cow<'static, str>, SolutionCount)>),
+
+    /// The number of valid hands for each hand size of a range.
+    ByHandSize(BTreeMap<u32, SolutionCount>),
+}
+
+impl Display for Output {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count(count) => {
+                write!(fmt, "{}", count)?;
+            }
+
+            Self::Sequences(sequences) => {
+                write!(fmt, "{}", sequences)?;
+            }
+
+            Self::Probability(probability) => {
+                write!(fmt, "{}", probability)?;
+            }
+
+            Self::Unreachable(gap) => {
+                write!(fmt, "{}", gap)?;
+            }
+
+            Self::Distribution(distribution) => {
+                let mut iter = distribution.iter();
+                if let Some((score, probability)) = iter.next() {
+                    write!(fmt, "{}: {}", score, probability)?;
+                    for (score, probability) in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}: {}", score, probability)?;
+                    }
+                }
+            }
+
+            Self::List(list) | Self::Bingos(list) => {
+                let mut iter = list.iter();
+                if let Some(item) = iter.next() {
+                    write!(fmt, "{}", item)?;
+                    for item in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}", item)?;
+                    }
+                }
+            }
+
+            Self::ByScore(hands_by_score) => {
+                let mut iter = hands_by_score
+                    .iter()
+                    .flat_map(|(score, list)| list.iter().map(move |item| (score, item)));
+                if let Some((score, item)) = iter.next() {
+                    write!(fmt, "{}: {}", score, item)?;
+                    for (score, item) in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}: {}", score, item)?;
+                    }
+                }
+            }
+
+            Self::Statistics(statistics) => write!(fmt, "{}", statistics)?,
+
+            Self::ByValues(groups) => {
+                let mut iter = groups.iter();
+                if let Some(group) = iter.next() {
+                    write!(fmt, "{}", group)?;
+                    for group in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}", group)?;
+                    }
+                }
+            }
+
+            Self::Top(hands) => {
+                let mut iter = hands.iter();
+                if let Some((score, hand)) = iter.next() {
+                    write!(fmt, "{}: {}", score, hand)?;
+                    for (score, hand) in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}: {}", score, hand)?;
+                    }
+                }
+            }
+
+            Self::Deals(deals) => {
+                let mut iter = deals.iter();
+                if let Some(hands) = iter.next() {
+                    write!(fmt, "{}", hands.join(" | "))?;
+                    for hands in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}", hands.join(" | "))?;
+                    }
+                }
+            }
+
+            Self::Editions(counts) => {
+                let mut iter = counts.iter();
+                if let Some((edition, count)) = iter.next() {
+                    write!(fmt, "{}: {}", edition, count)?;
+                    for (edition, count) in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}: {}", edition, count)?;
+                    }
+                }
+            }
+
+            Self::ByHandSize(counts) => {
+                let mut iter = counts.iter();
+                if let Some((hand_size, count)) = iter.next() {
+                    write!(fmt, "{}: {}", hand_size, count)?;
+                    for (hand_size, count) in iter {
+                        writeln!(fmt)?;
+                        write!(fmt, "{}: {}", hand_size, count)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The tile files and word lists loaded by the previous queries of a session,
+/// so that `repl` doesn't read and parse them again for each query.
+///
+/// The clones of a cache share its contents.
+/// The tables computed from the tiles are not kept,
+/// since computing them takes little time next to loading the files.
+#[derive(Clone, Debug, Default)]
+pub struct Cache(Arc<Mutex<CachedFiles>>);
+
+impl Cache {
+    /// Locks the cached files, even if a query panicked while holding them.
+    fn lock(&self) -> MutexGuard<'_, CachedFiles> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The contents of a `Cache`, by path,
+/// along with the modification time of the file when it was loaded.
+#[derive(Debug, Default)]
+struct CachedFiles {
+    tiles: HashMap<PathBuf, (SystemTime, Vec<CountedTile>)>,
+    wordlists: HashMap<PathBuf, (SystemTime, Arc<Wordlist>)>,
+}
+
+/// Returns the contents of the file at `path` from `entries`,
+/// or loads it with `load` if it isn't there
+/// or the file was modified since it was loaded.
+fn cached<T, E>(
+    entries: &mut HashMap<PathBuf, (SystemTime, T)>,
+    path: &Path,
+    load: impl FnOnce(&Path) -> Result<T, E>,
+) -> Result<T, E>
+where
+    T: Clone,
+{
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    match (modified, entries.get(path)) {
+        (Some(modified), Some((loaded, contents))) if *loaded == modified => {
+            debug!(path = %path.display(), "reusing the loaded file");
+            Ok(contents.clone())
+        }
+        _ => {
+            let contents = load(path)?;
+            // Without a modification time, a changed file couldn't be told apart.
+            if let Some(modified) = modified {
+                entries.insert(path.to_owned(), (modified, contents.clone()));
+            }
+            Ok(contents)
+        }
+    }
+}
+
+/// Reads a tile distribution from a file,
+/// in TOML or CSV if the file has that extension.
+fn load_tiles(path: &Path) -> Result<Vec<CountedTile>, TilesError> {
+    let contents = fs::read_to_string(path)?;
+    match path.extension().and_then(OsStr::to_str) {
+        Some(extension) if extension.eq_ignore_ascii_case("toml") => parse_tiles_toml(&contents),
+        Some(extension) if extension.eq_ignore_ascii_case("csv") => parse_tiles_csv(&contents),
+        _ => parse_tiles(&contents),
+    }
+}
+
+/// Parses the letter of a tile, `_` being a blank tile.
+fn parse_letter(field: &str) -> Option<char> {
+    match field {
+        "_" => Some(' '),
+        letter => letter.parse().ok(),
+    }
+}
+
+/// Parses a letter given on the command line, `_` being a blank tile.
+#[cfg(feature = "clap")]
+fn parse_letter_arg(s: &str) -> Result<char, String> {
+    parse_letter(s).ok_or_else(|| format!("`{}` is not a letter", s))
+}
+
+/// Parses a point value that replaces the value of a letter given on the command line
+/// as `<letter>=<value>`, `_` being a blank tile.
+#[cfg(feature = "clap")]
+fn parse_value_arg(s: &str) -> Result<(char, u32), String> {
+    let mut fields = s.splitn(2, '=');
+    match (fields.next(), fields.next()) {
+        (Some(letter), Some(value)) => {
+            let letter = parse_letter_arg(letter.trim())?;
+            let value = value.trim().parse().map_err(|err| format!("{}", err))?;
+            Ok((letter, value))
+        }
+        _ => Err(format!("`{}` must be given as `<letter>=<value>`", s)),
+    }
+}
+
+/// Parses a tile distribution.
+///
+/// See the documentation on `Parameters::tiles` for the format.
+fn parse_tiles(s: &str) -> Result<Vec<CountedTile>, TilesError> {
+    s.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let invalid_line = || TilesError::InvalidLine(line_number);
+            let mut fields = line.split_whitespace();
+            let letter = fields
+                .next()
+                .and_then(parse_letter)
+                .ok_or_else(invalid_line)?;
+            let mut next_number = || -> Result<u32, TilesError> {
+                fields
+                    .next()
+                    .and_then(|field| field.parse().ok())
+                    .ok_or_else(invalid_line)
+            };
+            let occurrences = next_number()?;
+            let value = next_number()?;
+            if fields.next().is_some() {
+                return Err(invalid_line());
+            }
+
+            Ok(CountedTile {
+                tile: Tile { letter, value },
+                occurrences,
+            })
+        })
+        .collect()
+}
+
+/// Parses a tile distribution written in TOML,
+/// as an array of tables with the letter, the occurrences and the value of each tile.
+fn parse_tiles_toml(s: &str) -> Result<Vec<CountedTile>, TilesError> {
+    let table: toml::Table = s.parse()?;
+    let tiles = match table.get("tiles") {
+        Some(toml::Value::Array(tiles)) => tiles,
+        _ => return Err(TilesError::MissingTiles),
+    };
+
+    tiles
+        .iter()
+        .enumerate()
+        .map(|(index, tile)| {
+            let number = |key| {
+                tile.get(key)
+                    .and_then(toml::Value::as_integer)
+                    .and_then(|number| u32::try_from(number).ok())
+            };
+            let counted_tile = CountedTile {
+                tile: Tile {
+                    letter: tile
+                        .get("letter")
+                        .and_then(toml::Value::as_str)
+                        .and_then(parse_letter)
+                        .ok_or(TilesError::InvalidTile(index + 1))?,
+                    value: number("value").ok_or(TilesError::InvalidTile(index + 1))?,
+                },
+                occurrences: number("occurrences").ok_or(TilesError::InvalidTile(index + 1))?,
+            };
+            Ok(counted_tile)
+        })
+        .collect()
+}
+
+/// Parses a tile distribution written in CSV,
+/// with a header and a row with the letter, the occurrences and the value of each tile.
+fn parse_tiles_csv(s: &str) -> Result<Vec<CountedTile>, TilesError> {
+    csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_reader(s.as_bytes())
+        .records()
+        .map(|record| {
+            let record = record?;
+            let line_number = record
+                .position()
+                .map_or(0, |position| position.line() as usize);
+            let invalid_line = || TilesError::InvalidLine(line_number);
+            let number = |index| -> Result<u32, TilesError> {
+                record
+                    .get(index)
+                    .and_then(|field| field.parse().ok())
+                    .ok_or_else(invalid_line)
+            };
+            if record.len() != 3 {
+                return Err(invalid_line());
+            }
+
+            Ok(CountedTile {
+                tile: Tile {
+                    letter: parse_letter(&record[0]).ok_or_else(invalid_line)?,
+                    value: number(2)?,
+                },
+                occurrences: number(1)?,
+            })
+        })
+        .collect()
+}
+
+quick_error! {
+    /// An error that can be returned when validating the parameters.
+    #[derive(Debug)]
+    pub enum ParameterError {
+        /// The hand size is 0.
+        EmptyHand {
+            display("the hand size must be at least 1")
+        }
+
+        /// The hand size is larger than the number of tiles in the distribution.
+        HandTooLarge(hand_size: u32, total_tiles: u32) {
+            display("a hand of {} tiles can't be drawn from {} tiles", hand_size, total_tiles)
+        }
+
+        /// No hand can have the target score.
+        UnreachableScore(target_score: u32, lowest_score: u32, highest_score: u32) {
+            display("the target score {} is not between the lowest score ({}) and the highest score ({}) of a hand", target_score, lowest_score, highest_score)
+        }
+
+        /// A range of hand sizes is given with an option that needs a single hand size.
+        HandSizeRange(option: &'static str) {
+            display("a range of hand sizes can't be used with {}", option)
+        }
+
+        /// The blank tiles are wildcards with an output that doesn't list or count the hands.
+        BlankMode(option: &'static str) {
+            display("`--blank-mode wildcard` can't be used with {}", option)
+        }
+
+        /// The hands are counted without a search
+        /// with an option that needs one.
+        Method(method: &'static str, option: &'static str) {
+            display("`--method {}` can't be used with {}", method, option)
+        }
+
+        /// The top hands are listed with an option that doesn't list the hands.
+        Top(option: &'static str) {
+            display("`--top` can't be used with {}", option)
+        }
+
+        /// The hands have distinct letters with an output that counts the ways to draw them.
+        DistinctLetters(option: &'static str) {
+            display("`--distinct-letters` can't be used with {}", option)
+        }
+
+        /// The sequences of draws are counted with an option that doesn't count hands.
+        Ordered(option: &'static str) {
+            display("`--ordered` can't be used with {}", option)
+        }
+
+        /// The hands are streamed with an output that isn't a list.
+        Stream(option: &'static str) {
+            display("`--stream` can't be used with {}", option)
+        }
+
+        /// Hands are dealt with an option that applies to a single hand.
+        Deal(option: &'static str) {
+            display("`--deal` can't be used with {}", option)
+        }
+
+        /// The bingos are listed without a word list.
+        MissingWordlist {
+            display("`--output bingos` needs a `--wordlist`")
+        }
+
+        /// A word list is given with an output that doesn't list or count the hands.
+        Wordlist(option: &'static str) {
+            display("`--wordlist` can't be used with {}", option)
+        }
+
+        /// A letter is both required and forbidden.
+        IncludedAndExcluded(letter: char) {
+            display("`{}` can't be both included and excluded", if *letter == ' ' { '_' } else { *letter })
+        }
+
+        /// A required letter has no tiles.
+        UnknownIncludedLetter(letter: char) {
+            display("the tile distribution has no `{}` tiles to include", if *letter == ' ' { '_' } else { *letter })
+        }
+
+        /// More letters are required than there are tiles in a hand.
+        TooManyIncludedLetters(letters: u32, hand_size: u32) {
+            display("{} letters can't be included in a hand of {} tiles", letters, hand_size)
+        }
+
+        /// The minimum score is greater than the maximum score.
+        EmptyScoreRange(min_score: u32, max_score: u32) {
+            display("the minimum score ({}) is greater than the maximum score ({})", min_score, max_score)
+        }
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when loading a tile distribution.
+    #[derive(Debug)]
+    pub enum TilesError {
+        /// The tiles file could not be read.
+        Io(err: io::Error) {
+            cause(err)
+            display("could not read the tiles file: {}", err)
+            from()
+        }
+
+        /// A line in the tiles file is not formatted correctly.
+        InvalidLine(line_number: usize) {
+            display("line {} of the tiles file is invalid (expected `<letter> <occurrences> <value>`)", line_number)
+        }
+
+        /// The TOML tiles file could not be parsed.
+        Toml(err: toml::de::Error) {
+            cause(err)
+            display("could not parse the tiles file: {}", err)
+            from()
+        }
+
+        /// The TOML tiles file has no `tiles` array.
+        MissingTiles {
+            display("the tiles file has no `[[tiles]]`")
+        }
+
+        /// A tile in the TOML tiles file is not formatted correctly.
+        InvalidTile(number: usize) {
+            display("tile {} of the tiles file is invalid (expected a `letter`, `occurrences` and a `value`)", number)
+        }
+
+        /// `--bags` gives the number of sets of a letter that has no tiles.
+        UnknownBagsLetter(letter: char) {
+            display("the tile distribution has no `{}` tiles to multiply", if *letter == ' ' { '_' } else { *letter })
+        }
+
+        /// `--removed` has a letter that has no tiles.
+        UnknownRemovedLetter(letter: char) {
+            display("the tile distribution has no `{}` tiles to remove", if *letter == ' ' { '_' } else { *letter })
+        }
+
+        /// `--value` gives the point value of a letter that has no tiles.
+        UnknownValueLetter(letter: char) {
+            display("the tile distribution has no `{}` tiles to give a value", if *letter == ' ' { '_' } else { *letter })
+        }
+
+        /// `--removed` has more tiles of a letter than the distribution.
+        TooManyRemoved(letter: char, removed: u32, occurrences: u32) {
+            display("{} `{}` tiles can't be removed from the {} in the tile distribution", removed, if *letter == ' ' { '_' } else { *letter }, occurrences)
+        }
+
+        /// The tile distribution has more tiles, or more points in total,
+        /// than a `u32` can count.
+        TooLarge(total_tiles: u64, total_value: u128) {
+            display("the tile distribution has {} tiles worth {} points, but at most {} of each are supported", total_tiles, total_value, u32::MAX)
+        }
+
+        /// The CSV tiles file could not be parsed.
+        Csv(err: csv::Error) {
+            cause(err)
+            display("could not parse the tiles file: {}", err)
+            from()
+        }
+    }
+}
+
+/// Summarizes a tile distribution to help catch data-entry errors.
+fn check_distribution(tiles: &[CountedTile], expected_total: Option<u32>) -> DistributionReport {
+    DistributionReport {
+        total_tiles: tiles
+            .iter()
+            .map(|counted_tile| u64::from(counted_tile.occurrences))
+            .sum(),
+        total_value: tiles
+            .iter()
+            .map(|counted_tile| {
+                u128::from(counted_tile.occurrences) * u128::from(counted_tile.tile.value)
+            })
+            .sum(),
+        expected_total,
+    }
+}
+
+/// The result of checking a tile distribution.
+#[derive(Debug)]
+pub struct DistributionReport {
+    /// The total number of tiles in the distribution.
+    ///
+    /// The totals are wider than the numbers in the distribution,
+    /// which come from a file and can be as large as a `u32`.
+    total_tiles: u64,
+
+    /// The sum of the point values of all tiles in the distribution.
+    total_value: u128,
+
+    /// The total number of tiles that the distribution should contain, if known.
+    expected_total: Option<u32>,
+}
+
+impl Display for DistributionReport {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "total tiles: {}, total value: {}",
+            self.total_tiles, self.total_value
+        )?;
+
+        if let Some(expected_total) = self
+            .expected_total
+            .filter(|&expected_total| u64::from(expected_total) != self.total_tiles)
+        {
+            writeln!(fmt)?;
+            write!(
+                fmt,
+                "warning: expected {} tiles, but the distribution contains {}",
+                expected_total, self.total_tiles
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! tiles {
+    ($($letter:tt x $occurrences:tt, value $value:tt)*) => {
+        [
+            $(
+                CountedTile {
+                    tile: Tile {
+                        letter: $letter,
+                        value: $value,
+                    },
+                    occurrences: $occurrences,
+                },
+            )*
+        ]
+    };
+}
+
+/// The distribution of tiles in a standard English edition of Scrabble®.
+static STANDARD_ENGLISH_SCRABBLE_TILES: &[CountedTile] = &tiles![
+    ' ' x  2, value 0
+    'A' x  9, value 1
+    'B' x  2, value 3
+    'C' x  2, value 3
+    'D' x  4, value 2
+    'E' x 12, value 1
+    'F' x  2, value 4
+    'G' x  3, value 2
+    'H' x  2, value 4
+    'I' x  9, value 1
+    'J' x  1, value 8
+    'K' x  1, value 5
+    'L' x  4, value 1
+    'M' x  2, value 3
+    'N' x  6, value 1
+    'O' x  8, value 1
+    'P' x  2, value 3
+    'Q' x  1, value 10
+    'R' x  6, value 1
+    'S' x  4, value 1
+    'T' x  6, value 1
+    'U' x  4, value 1
+    'V' x  2, value 4
+    'W' x  2, value 4
+    'X' x  1, value 8
+    'Y' x  2, value 4
+    'Z' x  1, value 10
+];
+
+/// The distribution of tiles in Super Scrabble®,
+/// which has twice as many blank tiles as the standard English edition.
+static SUPER_SCRABBLE_TILES: &[CountedTile] = &tiles![
+    ' ' x  4, value 0
+    'A' x 16, value 1
+    'B' x  4, value 3
+    'C' x  6, value 3
+    'D' x  8, value 2
+    'E' x 24, value 1
+    'F' x  4, value 4
+    'G' x  5, value 2
+    'H' x  5, value 4
+    'I' x 13, value 1
+    'J' x  2, value 8
+    'K' x  2, value 5
+    'L' x  7, value 1
+    'M' x  6, value 3
+    'N' x 13, value 1
+    'O' x 15, value 1
+    'P' x  4, value 3
+    'Q' x  2, value 10
+    'R' x 13, value 1
+    'S' x 10, value 1
+    'T' x 15, value 1
+    'U' x  7, value 1
+    'V' x  3, value 4
+    'W' x  4, value 4
+    'X' x  2, value 8
+    'Y' x  4, value 4
+    'Z' x  2, value 10
+];
+
+/// The built-in editions of Scrabble®.
+static EDITIONS: &[BuiltInEdition] = &[
+    BuiltInEdition {
+        name: "english",
+        tiles: STANDARD_ENGLISH_SCRABBLE_TILES,
+    },
+    BuiltInEdition {
+        name: "super",
+        tiles: SUPER_SCRABBLE_TILES,
+    },
+];
+
+/// A built-in edition of Scrabble®.
+#[derive(Debug)]
+struct BuiltInEdition {
+    /// The name of the edition.
+    name: &'static str,
+
+    /// The distribution of tiles in the edition.
+    tiles: &'static [CountedTile],
+}
+
+/// A tile from the Scrabble board game.
+#[derive(Clone, Debug, PartialEq)]
+struct Tile {
+    /// The letter on the tile (or a space for blank tiles).
+    letter: char,
+
+    /// The point value of the tile
+    /// in the English edition of Scrabble.
+    value: u32,
+}
+
+/// A tile along with the number of copies of that tile
+/// in the English edition of Scrabble.
+#[derive(Clone, Debug, PartialEq)]
+struct CountedTile {
+    /// The attributes of a tile.
+    tile: Tile,
+
+    /// The number of occurrences of that tile.
+    occurrences: u32,
+}
+
+/// Aggregates all the tiles for a particular tile value.
+#[derive(Debug)]
+struct TilesForValue {
+    /// The tile value.
+    value: u32,
+
+    /// The list of `CountedTile`s with the tile value.
+    counted_tiles: Vec<CountedTile>,
+
+    /// The total number of tiles with the tile value.
+    number_of_tiles: u32,
+
+    /// The number of tiles of each entry in `counted_tiles`
+    /// that the hands must have.
+    required_occurrences: Vec<u32>,
+}
+
+impl TilesForValue {
+    /// Returns the number of tiles with the tile value that the hands must have.
+    fn required_tiles(&self) -> u32 {
+        self.required_occurrences.iter().sum()
+    }
+}
+
+/// Implemented for types
+/// that can accumulate solutions as they are found
+/// and that represent possible output formats for the solution.
+trait SolutionAccumulator: Sized + Send {
+    /// Initializes a new accumulator.
+    fn new() -> Self;
+
+    /// Adds a solution to this accumulator.
+    ///
+    /// The solution is generated lazily
+    /// because some accumulators don't care about the particular value.
+    fn add_solution(&mut self, solution_fn: impl Fn() -> String);
+
+    /// Adds all the solutions from another accumulator to this accumulator.
+    fn add_solutions(&mut self, other: Self);
+
+    /// Returns the number of solutions in this accumulator.
+    fn number_of_solutions(&self) -> usize;
+
+    /// Keeps only the first `len` solutions in this accumulator.
+    fn truncate(&mut self, len: usize);
+
+    /// Returns the number of bytes taken up by the solutions' text.
+    fn len_in_bytes(&self) -> usize {
+        0
+    }
+
+    /// Performs the cartesian product
+    /// of partial solutions by tile value
+    /// to generate final solutions.
+    ///
+    /// Fails as soon as the solutions generated so far
+    /// take up more than `max_list_bytes`.
+    fn cartesian_product(
+        solutions_by_value: Vec<Self>,
+        max_list_bytes: Option<usize>,
+    ) -> Result<Self, Error>;
+
+    /// Transforms the accumulated solutions for presentation.
+    fn finish(&mut self) {}
+
+    /// Returns the solutions for a combination of tile values that was drawn.
+    ///
+    /// `abstract_tiles_drawn` holds the number of tiles drawn
+    /// for each entry in `tiles_by_value`.
+    fn from_combination(
+        tiles_by_value: &[TilesForValue],
+        abstract_tiles_drawn: &[u32],
+        max_list_bytes: Option<usize>,
+    ) -> Result<Self, Error> {
+        concrete_hands(tiles_by_value, abstract_tiles_drawn, max_list_bytes)
+    }
+}
+
+/// A `SolutionAccumulator` that simply counts the number of solutions.
+///
+/// This is more efficient than actually listing the solutions
+/// because the `cartesian_product` can be implemented
+/// as simply the product of the solution counts.
+type SolutionCount = u64;
+
+impl SolutionAccumulator for SolutionCount {
+    fn new() -> Self {
+        0
     }
 
     fn add_solution(&mut self, _: impl FnOnce() -> String) {
         *self += 1;
     }
 
-    fn add_solutions(&mut self, other: Self) {
-        *self += other;
+    fn add_solutions(&mut self, other: Self) {
+        *self += other;
+    }
+
+    fn number_of_solutions(&self) -> usize {
+        *self as usize
+    }
+
+    fn truncate(&mut self, len: usize) {
+        *self = (*self).min(len as u64);
+    }
+
+    fn cartesian_product(solutions_by_value: Vec<Self>, _: Option<usize>) -> Result<Self, Error> {
+        // We only need to multiply the counts together.
+        Ok(solutions_by_value.iter().product())
+    }
+}
+
+/// A `SolutionAccumulator` that counts the hands
+/// and the number of ways to draw them from the bag,
+/// telling apart the tiles that have the same letter.
+#[derive(Debug)]
+struct Draws {
+    /// The number of hands.
+    hands: SolutionCount,
+
+    /// The number of ways to draw the hands.
+    draws: u128,
+}
+
+impl SolutionAccumulator for Draws {
+    fn new() -> Self {
+        Draws { hands: 0, draws: 0 }
+    }
+
+    fn add_solution(&mut self, _: impl FnOnce() -> String) {
+        unreachable!("the draws are counted by combination of tile values")
+    }
+
+    fn add_solutions(&mut self, other: Self) {
+        self.hands += other.hands;
+        self.draws += other.draws;
+    }
+
+    fn number_of_solutions(&self) -> usize {
+        self.hands as usize
+    }
+
+    fn truncate(&mut self, _: usize) {
+        unreachable!("the draws are never limited")
+    }
+
+    fn cartesian_product(_: Vec<Self>, _: Option<usize>) -> Result<Self, Error> {
+        unreachable!("the draws are counted by combination of tile values")
+    }
+
+    fn from_combination(
+        tiles_by_value: &[TilesForValue],
+        abstract_tiles_drawn: &[u32],
+        _: Option<usize>,
+    ) -> Result<Self, Error> {
+        // Drawing `k` of the `n` tiles with a value can give any letters with that value,
+        // so there is no need to go through the letters to count the draws.
+        Ok(Draws {
+            hands: concrete_hands(tiles_by_value, abstract_tiles_drawn, None)?,
+            draws: tiles_by_value
+                .iter()
+                .zip(abstract_tiles_drawn)
+                .map(|(tiles_for_value, &tiles_drawn)| value_draws(tiles_for_value, tiles_drawn))
+                .product(),
+        })
+    }
+}
+
+/// A `SolutionAccumulator` that counts the hands
+/// for each combination of tile values that was drawn.
+type ValueGroups = Vec<ValueGroup>;
+
+impl SolutionAccumulator for ValueGroups {
+    fn new() -> Self {
+        vec![]
+    }
+
+    fn add_solution(&mut self, _: impl FnOnce() -> String) {
+        unreachable!("the hands are grouped by combination of tile values")
+    }
+
+    fn add_solutions(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn number_of_solutions(&self) -> usize {
+        self.iter().map(|group| group.hands as usize).sum()
+    }
+
+    fn truncate(&mut self, _: usize) {
+        unreachable!("the groups are never limited")
+    }
+
+    fn cartesian_product(_: Vec<Self>, _: Option<usize>) -> Result<Self, Error> {
+        unreachable!("the hands are grouped by combination of tile values")
+    }
+
+    fn finish(&mut self) {
+        // The groups with the highest values come first, like the values in each group.
+        self.sort_by(|a, b| b.values.cmp(&a.values));
+    }
+
+    fn from_combination(
+        tiles_by_value: &[TilesForValue],
+        abstract_tiles_drawn: &[u32],
+        _: Option<usize>,
+    ) -> Result<Self, Error> {
+        let values = tiles_by_value
+            .iter()
+            .zip(abstract_tiles_drawn)
+            .flat_map(|(tiles_for_value, &tiles_drawn)| {
+                iter::repeat_n(tiles_for_value.value, tiles_drawn as usize)
+            })
+            .sorted_by(|a, b| b.cmp(a))
+            .collect();
+        Ok(vec![ValueGroup {
+            values,
+            hands: concrete_hands(tiles_by_value, abstract_tiles_drawn, None)?,
+        }])
+    }
+}
+
+/// A `SolutionAccumulator` that counts the hands
+/// and the sequences in which they can be drawn one tile at a time,
+/// where the tiles with the same letter are alike.
+#[derive(Debug)]
+struct Sequences {
+    /// The number of hands.
+    hands: SolutionCount,
+
+    /// The number of sequences in which the hands can be drawn.
+    sequences: u128,
+}
+
+impl SolutionAccumulator for Sequences {
+    fn new() -> Self {
+        Sequences {
+            hands: 0,
+            sequences: 0,
+        }
+    }
+
+    fn add_solution(&mut self, solution_fn: impl FnOnce() -> String) {
+        // The hands that form a word are added one at a time.
+        self.hands += 1;
+        self.sequences = self
+            .sequences
+            .saturating_add(hand_sequences(&solution_fn()));
+    }
+
+    fn add_solutions(&mut self, other: Self) {
+        self.hands += other.hands;
+        self.sequences = self.sequences.saturating_add(other.sequences);
+    }
+
+    fn number_of_solutions(&self) -> usize {
+        self.hands as usize
+    }
+
+    fn truncate(&mut self, _: usize) {
+        unreachable!("the sequences are never limited")
+    }
+
+    fn cartesian_product(_: Vec<Self>, _: Option<usize>) -> Result<Self, Error> {
+        unreachable!("the sequences are counted by combination of tile values")
+    }
+
+    fn from_combination(
+        tiles_by_value: &[TilesForValue],
+        abstract_tiles_drawn: &[u32],
+        _: Option<usize>,
+    ) -> Result<Self, Error> {
+        // The tiles of each value can go anywhere among the tiles of the lower values,
+        // in any of their own sequences.
+        let mut drawn = 0;
+        let sequences = tiles_by_value.iter().zip(abstract_tiles_drawn).fold(
+            1u128,
+            |sequences, (tiles_for_value, &tiles_drawn)| {
+                drawn += tiles_drawn;
+                sequences
+                    .saturating_mul(binomial(drawn, tiles_drawn))
+                    .saturating_mul(value_sequences(tiles_for_value, tiles_drawn))
+            },
+        );
+        Ok(Sequences {
+            hands: concrete_hands(tiles_by_value, abstract_tiles_drawn, None)?,
+            sequences,
+        })
+    }
+}
+
+/// A `SolutionAccumulator` that list all hands that match the target score.
+type SolutionList = Vec<String>;
+
+impl SolutionAccumulator for SolutionList {
+    fn new() -> Self {
+        vec![]
+    }
+
+    fn add_solution(&mut self, solution_fn: impl FnOnce() -> String) {
+        self.push(solution_fn());
+    }
+
+    fn add_solutions(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn number_of_solutions(&self) -> usize {
+        self.len()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len);
+    }
+
+    fn len_in_bytes(&self) -> usize {
+        self.iter().map(String::len).sum()
+    }
+
+    fn cartesian_product(
+        solutions_by_value: Vec<Self>,
+        max_list_bytes: Option<usize>,
+    ) -> Result<Self, Error> {
+        // Check the size as the hands are generated,
+        // since the product can be much larger than the partial solutions.
+        let mut hands = vec![];
+        let mut len_in_bytes = 0;
+        for hand in solutions_by_value
+            .into_iter()
+            .multi_cartesian_product()
+            .map(|v| v.join(""))
+        {
+            len_in_bytes += hand.len();
+            check_max_list_bytes(max_list_bytes, len_in_bytes)?;
+            hands.push(hand);
+        }
+        Ok(hands)
+    }
+
+    fn finish(&mut self) {
+        // Sort the results for easier eyeballing.
+        self.sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    macro_rules! vec_of_strings {
+        ($($x:expr),*) => {
+            vec![
+                $(String::from($x),)*
+            ]
+        };
+
+        ($($x:expr,)*) => {
+            vec_of_strings![$($x),*]
+        }
+    }
+
+    /// Returns the parameters of the command line without any options,
+    /// to be changed with the struct update syntax.
+    fn default_parameters() -> Parameters {
+        Parameters::builder().build().unwrap()
+    }
+
+    #[test]
+    fn solution_count() {
+        let result = super::scrabble(default_parameters()).unwrap();
+
+        assert_eq!(result, Output::Count(138));
+    }
+
+    #[test]
+    fn solution_list() {
+        let result = super::scrabble(Parameters {
+            output: OutputFormat::List,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(
+            result,
+            Output::List(vec_of_strings![
+                "AFKJXQZ", "AHKJXQZ", "AVKJXQZ", "AWKJXQZ", "AYKJXQZ", "BBFJXQZ", "BBHJXQZ",
+                "BBVJXQZ", "BBWJXQZ", "BBYJXQZ", "BCFJXQZ", "BCHJXQZ", "BCVJXQZ", "BCWJXQZ",
+                "BCYJXQZ", "BMFJXQZ", "BMHJXQZ", "BMVJXQZ", "BMWJXQZ", "BMYJXQZ", "BPFJXQZ",
+                "BPHJXQZ", "BPVJXQZ", "BPWJXQZ", "BPYJXQZ", "CCFJXQZ", "CCHJXQZ", "CCVJXQZ",
+                "CCWJXQZ", "CCYJXQZ", "CMFJXQZ", "CMHJXQZ", "CMVJXQZ", "CMWJXQZ", "CMYJXQZ",
+                "CPFJXQZ", "CPHJXQZ", "CPVJXQZ", "CPWJXQZ", "CPYJXQZ", "DBKJXQZ", "DCKJXQZ",
+                "DFFJXQZ", "DFHJXQZ", "DFVJXQZ", "DFWJXQZ", "DFYJXQZ", "DHHJXQZ", "DHVJXQZ",
+                "DHWJXQZ", "DHYJXQZ", "DMKJXQZ", "DPKJXQZ", "DVVJXQZ", "DVWJXQZ", "DVYJXQZ",
+                "DWWJXQZ", "DWYJXQZ", "DYYJXQZ", "EFKJXQZ", "EHKJXQZ", "EVKJXQZ", "EWKJXQZ",
+                "EYKJXQZ", "GBKJXQZ", "GCKJXQZ", "GFFJXQZ", "GFHJXQZ", "GFVJXQZ", "GFWJXQZ",
+                "GFYJXQZ", "GHHJXQZ", "GHVJXQZ", "GHWJXQZ", "GHYJXQZ", "GMKJXQZ", "GPKJXQZ",
+                "GVVJXQZ", "GVWJXQZ", "GVYJXQZ", "GWWJXQZ", "GWYJXQZ", "GYYJXQZ", "IFKJXQZ",
+                "IHKJXQZ", "IVKJXQZ", "IWKJXQZ", "IYKJXQZ", "LFKJXQZ", "LHKJXQZ", "LVKJXQZ",
+                "LWKJXQZ", "LYKJXQZ", "MMFJXQZ", "MMHJXQZ", "MMVJXQZ", "MMWJXQZ", "MMYJXQZ",
+                "MPFJXQZ", "MPHJXQZ", "MPVJXQZ", "MPWJXQZ", "MPYJXQZ", "NFKJXQZ", "NHKJXQZ",
+                "NVKJXQZ", "NWKJXQZ", "NYKJXQZ", "OFKJXQZ", "OHKJXQZ", "OVKJXQZ", "OWKJXQZ",
+                "OYKJXQZ", "PPFJXQZ", "PPHJXQZ", "PPVJXQZ", "PPWJXQZ", "PPYJXQZ", "RFKJXQZ",
+                "RHKJXQZ", "RVKJXQZ", "RWKJXQZ", "RYKJXQZ", "SFKJXQZ", "SHKJXQZ", "SVKJXQZ",
+                "SWKJXQZ", "SYKJXQZ", "TFKJXQZ", "THKJXQZ", "TVKJXQZ", "TWKJXQZ", "TYKJXQZ",
+                "UFKJXQZ", "UHKJXQZ", "UVKJXQZ", "UWKJXQZ", "UYKJXQZ",
+            ])
+        );
+    }
+
+    #[test]
+    fn solution_count_matches_solution_list_length() {
+        // A small linear congruential generator with a fixed seed,
+        // so that the test is reproducible.
+        let mut seed: u32 = 0x2545_f491;
+        let mut next_random = |bound: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) % bound
+        };
+
+        for _ in 0..32 {
+            let hand_size = 1 + next_random(8);
+            let (lowest_score, highest_score) =
+                score_bounds(STANDARD_ENGLISH_SCRABBLE_TILES, hand_size);
+            let target_score = lowest_score + next_random(highest_score - lowest_score + 1);
+            let parameters = |output| Parameters {
+                hand_size: HandSize::from(hand_size),
+                target_score,
+                output,
+                ..default_parameters()
+            };
+
+            let count = match super::scrabble(parameters(OutputFormat::Count)).unwrap() {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            };
+
+            // Listing millions of hands would make the test too slow.
+            if count > 20_000 {
+                continue;
+            }
+
+            match super::scrabble(parameters(OutputFormat::List)).unwrap() {
+                Output::List(list) => assert_eq!(
+                    list.len() as u64,
+                    count,
+                    "hand size {}, target score {}",
+                    hand_size,
+                    target_score
+                ),
+                _ => panic!("expected a list"),
+            }
+        }
+    }
+
+    #[test]
+    fn solution_list_too_large() {
+        let result = super::scrabble(Parameters {
+            output: OutputFormat::List,
+            max_list_bytes: Some(100),
+            ..default_parameters()
+        });
+
+        assert!(matches!(result, Err(Error::ListTooLarge(100))));
+    }
+
+    #[test]
+    fn max_list_bytes_cartesian_product() {
+        let solutions_by_value = || vec![vec_of_strings!["A", "B"], vec_of_strings!["C", "D"]];
+
+        assert_eq!(
+            SolutionList::cartesian_product(solutions_by_value(), Some(8)).unwrap(),
+            vec_of_strings!["AC", "AD", "BC", "BD"]
+        );
+        // The product stops at the third hand, before the whole list is built.
+        assert!(matches!(
+            SolutionList::cartesian_product(solutions_by_value(), Some(5)),
+            Err(Error::ListTooLarge(5))
+        ));
+    }
+
+    #[test]
+    fn compare_editions() {
+        let result = super::scrabble(Parameters {
+            compare_editions: true,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        match result {
+            Output::Editions(counts) => {
+                let names: Vec<_> = counts.iter().map(|(name, _)| name.as_ref()).collect();
+                let expected_names: Vec<_> = EDITIONS.iter().map(|edition| edition.name).collect();
+                assert_eq!(names, expected_names);
+                assert!(counts.contains(&("english".into(), 138)));
+            }
+            _ => panic!("expected a comparison of editions"),
+        }
+    }
+
+    #[test]
+    fn target_percent() {
+        let parameters = |hand_size, target_percent| Parameters {
+            hand_size: HandSize::from(hand_size),
+            target_score: 0,
+            target_percent: Some(target_percent),
+            output: OutputFormat::List,
+            ..default_parameters()
+        };
+
+        let mut resolved = parameters(7, 100);
+        resolved.resolve_target_percent(STANDARD_ENGLISH_SCRABBLE_TILES);
+        assert_eq!(resolved.target_score, 49);
+
+        let mut resolved = parameters(7, 50);
+        resolved.resolve_target_percent(STANDARD_ENGLISH_SCRABBLE_TILES);
+        assert_eq!(resolved.target_score, 25);
+
+        // The percentage of a large highest score doesn't overflow.
+        let mut resolved = parameters(7, 100);
+        resolved.resolve_target_percent(&[CountedTile {
+            tile: Tile {
+                letter: 'A',
+                value: 600_000_000,
+            },
+            occurrences: 7,
+        }]);
+        assert_eq!(resolved.target_score, 4_200_000_000);
+
+        // The highest-scoring pair of tiles is Q and Z.
+        let result = super::scrabble(parameters(2, 100)).unwrap();
+        assert_eq!(result, Output::List(vec_of_strings!["QZ"]));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let parameters = |hand_size, target_score, min_score, max_score, output| Parameters {
+            hand_size: HandSize::from(hand_size),
+            target_score,
+            min_score,
+            max_score,
+            output,
+            ..default_parameters()
+        };
+
+        assert_eq!(score_bounds(STANDARD_ENGLISH_SCRABBLE_TILES, 7), (5, 49));
+
+        let result = super::scrabble(parameters(0, 0, None, None, OutputFormat::Count));
+        assert!(matches!(
+            result,
+            Err(Error::Parameters(ParameterError::EmptyHand))
+        ));
+
+        let result = super::scrabble(parameters(101, 46, None, None, OutputFormat::Count));
+        assert!(matches!(
+            result,
+            Err(Error::Parameters(ParameterError::HandTooLarge(101, 100)))
+        ));
+
+        let result = super::scrabble(parameters(7, 50, None, None, OutputFormat::List));
+        assert!(matches!(
+            result,
+            Err(Error::Parameters(ParameterError::UnreachableScore(
+                50, 5, 49
+            )))
+        ));
+
+        let result = super::scrabble(parameters(7, 46, Some(48), Some(44), OutputFormat::ByScore));
+        assert!(matches!(
+            result,
+            Err(Error::Parameters(ParameterError::EmptyScoreRange(48, 44)))
+        ));
+    }
+
+    #[test]
+    fn analyze_forced_letters() {
+        let analysis = super::analyze(default_parameters()).unwrap();
+
+        // Every hand has the four highest-valued tiles.
+        assert_eq!(
+            analysis.forced,
+            ['J', 'Q', 'X', 'Z'].iter().cloned().collect()
+        );
+        assert!(analysis.some.contains(&'K'));
+        assert!(analysis.none.contains(&' '));
+        assert_eq!(
+            analysis.forced.len() + analysis.some.len() + analysis.none.len(),
+            STANDARD_ENGLISH_SCRABBLE_TILES.len()
+        );
+    }
+
+    #[test]
+    fn builder() {
+        let parameters = Parameters::builder()
+            .hand_size(7)
+            .target_score(46)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::List(hands) => assert_eq!(hands.len(), 138),
+            _ => panic!("expected a list"),
+        }
+
+        let parameters = Parameters::builder().hand_size(2).target_percent(100);
+        assert_eq!(
+            super::scrabble(parameters.build().unwrap()).unwrap(),
+            Output::Count(1)
+        );
+    }
+
+    #[test]
+    fn score_range() {
+        fn count(parameters: ParametersBuilder) -> SolutionCount {
+            match super::scrabble(parameters.build().unwrap()).unwrap() {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            }
+        }
+
+        // The hands with a score in the range are found in a single search.
+        assert_eq!(count(Parameters::builder().target_score(45)), 314);
+        assert_eq!(
+            count(Parameters::builder().min_score(45).max_score(46)),
+            314 + 138
+        );
+
+        let parameters = Parameters::builder()
+            .min_score(45)
+            .max_score(46)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::List(hands) => assert_eq!(hands.len(), 314 + 138),
+            _ => panic!("expected a list"),
+        }
+    }
+
+    #[test]
+    fn probability() {
+        let probability = |hand_size, target_score| {
+            let parameters = Parameters::builder()
+                .hand_size(hand_size)
+                .target_score(target_score)
+                .output(OutputFormat::Probability)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::Probability(probability) => probability,
+                _ => panic!("expected a probability"),
+            }
+        };
+
+        // Only Q and Z make 20 points with 2 tiles.
+        let qz = probability(2, 20);
+        assert_eq!((qz.hands, qz.draws, qz.total_draws), (1, 1, 4950));
+
+        let probability = probability(7, 46);
+        assert_eq!(probability.hands, 138);
+        assert_eq!(probability.total_draws, binomial(100, 7));
+        assert_eq!(binomial(100, 7), 16_007_560_800);
+
+        // The draws of every hand of 2 tiles add up to all the draws.
+        let parameters = Parameters::builder()
+            .hand_size(2)
+            .min_score(0)
+            .max_score(20)
+            .output(OutputFormat::Probability)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::Probability(probability) => {
+                assert_eq!(probability.draws, probability.total_draws)
+            }
+            _ => panic!("expected a probability"),
+        }
+    }
+
+    #[test]
+    fn distribution() {
+        let parameters = Parameters::builder()
+            .output(OutputFormat::Distribution)
+            .build()
+            .unwrap();
+        let distribution = match super::scrabble(parameters).unwrap() {
+            Output::Distribution(distribution) => distribution,
+            _ => panic!("expected a distribution"),
+        };
+
+        assert_eq!(distribution[&46].hands, 138);
+        assert_eq!(distribution.keys().last(), Some(&49));
+        // Every draw has some score.
+        assert_eq!(
+            distribution
+                .values()
+                .map(|probability| probability.draws)
+                .sum::<u128>(),
+            binomial(100, 7)
+        );
+
+        let parameters = Parameters::builder()
+            .min_score(45)
+            .max_score(46)
+            .output(OutputFormat::Distribution)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::Distribution(distribution) => assert_eq!(
+                distribution
+                    .iter()
+                    .map(|(&score, probability)| (score, probability.hands))
+                    .collect::<Vec<_>>(),
+                vec![(45, 314), (46, 138)]
+            ),
+            _ => panic!("expected a distribution"),
+        }
+    }
+
+    #[test]
+    fn unreachable() {
+        let gap = |hand_size| {
+            let parameters = Parameters::builder()
+                .hand_size(hand_size)
+                .output(OutputFormat::Unreachable)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::Unreachable(gap) => gap,
+                _ => panic!("expected a gap"),
+            }
+        };
+
+        // No two tiles add up to 17: the 10-point tiles would need a 7-point tile,
+        // and the 8-point tiles a 9-point tile.
+        assert_eq!(
+            gap(2),
+            Gap {
+                score: 17,
+                lowest_score: 0,
+                highest_score: 20,
+            }
+        );
+
+        assert_eq!(gap(1).score, 6);
+    }
+
+    #[test]
+    fn bingos() {
+        let tiles = STANDARD_ENGLISH_SCRABBLE_TILES;
+        assert_eq!(word_score(tiles, "QUIZ"), Some(22));
+        // There is only one Z in the bag.
+        assert_eq!(word_score(tiles, "JAZZ"), None);
+
+        let wordlist: Wordlist = ["quiz", "jazz", "quip", "zax"].iter().cloned().collect();
+        let parameters = Parameters::builder()
+            .hand_size(4)
+            .min_score(20)
+            .max_score(29)
+            .output(OutputFormat::Bingos)
+            .build()
+            .unwrap();
+        assert_eq!(scrabble_bingos(&parameters, tiles, &wordlist), ["QUIZ"]);
+
+        let parameters = Parameters::builder()
+            .output(OutputFormat::Bingos)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            super::scrabble(parameters),
+            Err(Error::Parameters(ParameterError::MissingWordlist))
+        ));
+    }
+
+    #[test]
+    fn blank_mode() {
+        assert_eq!(expand_blanks("QZ"), ["QZ"]);
+        let hands = expand_blanks("  JZ");
+        assert_eq!(hands.len(), 351);
+        assert_eq!(hands[0], "[=A][=A]JZ");
+        assert_eq!(hands[1], "[=A][=B]JZ");
+        assert_eq!(wildcard_hands("  JZ"), 351);
+
+        let count = |blank_mode| {
+            let parameters = Parameters::builder()
+                .hand_size(2)
+                .target_score(10)
+                .blank_mode(blank_mode)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            }
+        };
+
+        // Q or Z with a blank stand for 26 hands each, besides DJ, DX, GJ and GX.
+        assert_eq!(count(BlankMode::Tile), 6);
+        assert_eq!(count(BlankMode::Wildcard), 2 * 26 + 4);
+    }
+
+    #[test]
+    fn bags() {
+        let count = |bags: &str| {
+            let parameters = Parameters::builder()
+                .hand_size(2)
+                .target_score(20)
+                .bags(bags.parse().unwrap())
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            }
+        };
+
+        // QZ, then QQ and ZZ too with two of each.
+        assert_eq!(count("1"), 1);
+        assert_eq!(count("2"), 3);
+        assert_eq!(count("Q=2"), 2);
+        assert_eq!(count("3,Z=1"), 2);
+
+        assert_eq!(
+            "2,E=3,_=0".parse::<Bags>().unwrap(),
+            Bags::new().all(2).letter('E', 3).letter(' ', 0)
+        );
+        assert_eq!(
+            "2,E=3,_=0".parse::<Bags>().unwrap().to_string(),
+            "2,_=0,E=3"
+        );
+        assert_eq!("E=3".parse::<Bags>().unwrap().to_string(), "E=3");
+        assert!("E=3,2".parse::<Bags>().is_err());
+        assert!(matches!(
+            Parameters::builder()
+                .bags(Bags::new().letter('!', 2))
+                .build()
+                .map_err(Error::from)
+                .and_then(super::scrabble),
+            Err(Error::Tiles(TilesError::UnknownBagsLetter('!')))
+        ));
+    }
+
+    #[test]
+    fn values() {
+        let hands = |parameters: ParametersBuilder| {
+            let parameters = parameters
+                .hand_size(2)
+                .target_score(15)
+                .output(OutputFormat::List)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::List(hands) => hands,
+                _ => panic!("expected a list"),
+            }
+        };
+
+        // Q pairs with the 3-point letters and the blank instead of the 5-point K.
+        assert_eq!(hands(Parameters::builder()), vec_of_strings!["KQ", "KZ"]);
+        assert_eq!(
+            hands(Parameters::builder().value('Q', 12).value(' ', 3)),
+            vec_of_strings![" Q", "BQ", "CQ", "KZ", "MQ", "PQ"]
+        );
+
+        #[cfg(feature = "clap")]
+        {
+            assert_eq!(parse_value_arg("_=3"), Ok((' ', 3)));
+            assert!(parse_value_arg("Q").is_err());
+        }
+        assert!(matches!(
+            Parameters::builder()
+                .value('!', 2)
+                .build()
+                .map_err(Error::from)
+                .and_then(super::scrabble),
+            Err(Error::Tiles(TilesError::UnknownValueLetter('!')))
+        ));
+    }
+
+    #[test]
+    fn distinct_letters() {
+        let list = |distinct_letters| {
+            let parameters = Parameters::builder()
+                .distinct_letters(distinct_letters)
+                .output(OutputFormat::List)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::List(hands) => hands,
+                _ => panic!("expected a list"),
+            }
+        };
+
+        // The search finds the hands that a filter would keep.
+        let expected: SolutionList = list(false)
+            .into_iter()
+            .filter(|hand| hand.chars().unique().count() == hand.len())
+            .collect();
+        assert_eq!(expected.len(), 108);
+        assert_eq!(list(true), expected);
+
+        let parameters = Parameters::builder()
+            .distinct_letters(true)
+            .output(OutputFormat::Probability)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            super::scrabble(parameters),
+            Err(Error::Parameters(ParameterError::DistinctLetters(
+                "`--output probability`"
+            )))
+        ));
+    }
+
+    #[test]
+    fn include_exclude() {
+        fn hands(parameters: ParametersBuilder) -> SolutionList {
+            let parameters = parameters.output(OutputFormat::List).build().unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::List(hands) => hands,
+                _ => panic!("expected a list"),
+            }
+        }
+
+        // The constrained search finds the hands that a filter would keep.
+        let all_hands = hands(Parameters::builder());
+        let check = |include: &[char], exclude: &[char]| {
+            let expected: SolutionList = all_hands
+                .iter()
+                .filter(|hand| {
+                    include.iter().all(|&letter| {
+                        hand.matches(letter).count()
+                            >= include.iter().filter(|&&other| other == letter).count()
+                    }) && !hand.contains(exclude)
+                })
+                .cloned()
+                .collect();
+            let parameters = Parameters::builder()
+                .include(include.iter().cloned())
+                .exclude(exclude.iter().cloned());
+            assert_eq!(hands(parameters), expected);
+        };
+        check(&['J'], &[]);
+        check(&['F', 'F'], &[]);
+        check(&['E', 'V'], &['Q']);
+        check(&[], &[' ', 'Z']);
+
+        // The draws and the counts by hand size are constrained too.
+        let parameters = Parameters::builder()
+            .hand_size(2)
+            .target_score(11)
+            .include(vec!['J'])
+            .output(OutputFormat::Probability)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            // JB, JC, JM and JP, with 2 of each of these letters.
+            Output::Probability(probability) => {
+                assert_eq!((probability.hands, probability.draws), (4, 8))
+            }
+            _ => panic!("expected a probability"),
+        }
+
+        let parameters = Parameters::builder()
+            .hand_sizes(2..=3)
+            .target_score(11)
+            .include(vec!['J'])
+            .exclude(vec!['B'])
+            .build()
+            .unwrap();
+        let counts = match super::scrabble(parameters).unwrap() {
+            Output::ByHandSize(counts) => counts,
+            _ => panic!("expected counts by hand size"),
+        };
+        assert_eq!(counts[&2], 3);
+
+        assert!(matches!(
+            super::scrabble(
+                Parameters::builder()
+                    .include(vec!['Q'])
+                    .exclude(vec!['Q'])
+                    .build()
+                    .unwrap()
+            ),
+            Err(Error::Parameters(ParameterError::IncludedAndExcluded('Q')))
+        ));
+    }
+
+    #[test]
+    fn removed() {
+        let count = |removed: &str| {
+            let parameters = Parameters::builder().removed(removed).build().unwrap();
+            super::scrabble(parameters).map(|output| match output {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            })
+        };
+
+        // Every hand scoring 46 has the only Q and Z.
+        assert_eq!(count("").unwrap(), 138);
+        assert_eq!(count("aeeinrt").unwrap(), 138);
+        // Without the Q, the highest score of a hand is 43.
+        assert!(matches!(
+            count("Q"),
+            Err(Error::Parameters(ParameterError::UnreachableScore(
+                46, 5, 43
+            )))
+        ));
+        assert!(matches!(
+            count("ZZ"),
+            Err(Error::Tiles(TilesError::TooManyRemoved('Z', 2, 1)))
+        ));
+        assert!(matches!(
+            count("!"),
+            Err(Error::Tiles(TilesError::UnknownRemovedLetter('!')))
+        ));
+
+        let tiles = remove_tiles(Cow::Borrowed(STANDARD_ENGLISH_SCRABBLE_TILES), "E_E").unwrap();
+        let occurrences = |letter| {
+            tiles
+                .iter()
+                .find(|counted_tile| counted_tile.tile.letter == letter)
+                .unwrap()
+                .occurrences
+        };
+        assert_eq!((occurrences('E'), occurrences(' ')), (10, 1));
+    }
+
+    #[test]
+    fn ordered() {
+        let ordered = |hand_size, target_score| {
+            let parameters = Parameters::builder()
+                .hand_size(hand_size)
+                .target_score(target_score)
+                .ordered(true)
+                .build()
+                .unwrap();
+            super::scrabble(parameters).unwrap()
+        };
+
+        // The hands with 2 of the same letter can be drawn in half as many orders.
+        assert_eq!(ordered(7, 46), Output::Sequences(619920));
+        assert_eq!(ordered(5, 10), Output::Sequences(779925));
+
+        assert_eq!(hand_sequences("EEQ"), 3);
+        assert_eq!(hand_sequences("  EEQ"), 30);
+
+        let parameters = Parameters::builder()
+            .ordered(true)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            parameters.validate(STANDARD_ENGLISH_SCRABBLE_TILES),
+            Err(ParameterError::Ordered("`--output list`"))
+        ));
+    }
+
+    #[test]
+    fn statistics() {
+        let statistics = |hand_size, target_score| {
+            let parameters = Parameters::builder()
+                .hand_size(hand_size)
+                .target_score(target_score)
+                .output(OutputFormat::Statistics)
+                .build()
+                .unwrap();
+            match super::scrabble(parameters).unwrap() {
+                Output::Statistics(statistics) => statistics,
+                output => panic!("unexpected output: {:?}", output),
+            }
+        };
+
+        // The mean is 7 times the mean value of a tile, 187 / 100,
+        // and the variance is that of drawing 7 tiles without replacement.
+        let seven = statistics(7, 46);
+        assert_eq!(seven.draws, binomial(100, 7));
+        assert_eq!(seven.score_sum * 100, seven.draws * 7 * 187);
+        // The squares of the values of the 100 tiles add up to 681.
+        let tile_variance = 681.0 / 100.0 - 1.87f64.powi(2);
+        let variance = 7.0 * tile_variance * 93.0 / 99.0;
+        assert!((seven.variance() - variance).abs() < 1e-9);
+
+        // Every tile is drawn, so every hand has the same score.
+        let all = statistics(100, 187);
+        assert_eq!(all.draws, 1);
+        assert_eq!(all.variance(), 0.0);
+        assert_eq!(all.z_score(), 0.0);
+    }
+
+    #[test]
+    fn hand_tiles() {
+        let letter_values = LetterValues::new(&Parameters::builder().build().unwrap()).unwrap();
+        let tile = |letter, value, count, stands_for| HandTile {
+            letter,
+            value,
+            count,
+            stands_for,
+        };
+
+        assert_eq!(
+            letter_values.hand_tiles("EEQZ "),
+            HandTiles {
+                tiles: vec![
+                    tile('E', 1, 2, None),
+                    tile('Q', 10, 1, None),
+                    tile('Z', 10, 1, None),
+                    tile('_', 0, 1, None),
+                ],
+                total: 22,
+            }
+        );
+
+        // A wildcard is a blank tile with the letter it stands for.
+        assert_eq!(
+            letter_values.hand_tiles("[=S][=S]Q"),
+            HandTiles {
+                tiles: vec![tile('_', 0, 2, Some('S')), tile('Q', 10, 1, None)],
+                total: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn by_values() {
+        let parameters = Parameters::builder()
+            .output(OutputFormat::ByValues)
+            .build()
+            .unwrap();
+        let group = |values: Vec<u32>, hands| ValueGroup { values, hands };
+        assert_eq!(
+            super::scrabble(parameters).unwrap(),
+            Output::ByValues(vec![
+                group(vec![10, 10, 8, 8, 5, 4, 1], 50),
+                group(vec![10, 10, 8, 8, 5, 3, 2], 8),
+                group(vec![10, 10, 8, 8, 4, 4, 2], 30),
+                group(vec![10, 10, 8, 8, 4, 3, 3], 50),
+            ])
+        );
+
+        // The hands are only counted by combination of tile values.
+        let parameters = Parameters::builder()
+            .output(OutputFormat::ByValues)
+            .wordlist("words.txt")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            parameters.validate(STANDARD_ENGLISH_SCRABBLE_TILES),
+            Err(ParameterError::Wordlist("`--output by-values`"))
+        ));
+    }
+
+    #[test]
+    fn top() {
+        let top = |hand_size, top| {
+            let parameters = Parameters::builder()
+                .hand_size(hand_size)
+                .top(top)
+                .build()
+                .unwrap();
+            super::scrabble(parameters).unwrap()
+        };
+
+        // The ties are broken in alphabetical order.
+        assert_eq!(
+            top(2, 4),
+            Output::Top(vec![
+                (20, "QZ".to_string()),
+                (18, "JQ".to_string()),
+                (18, "JZ".to_string()),
+                (18, "XQ".to_string()),
+            ])
+        );
+
+        // The top hands are the first hands with the highest score.
+        let highest = Parameters::builder()
+            .hand_size(7)
+            .target_score(49)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        let hands = match super::scrabble(highest).unwrap() {
+            Output::List(hands) => hands,
+            output => panic!("unexpected output: {:?}", output),
+        };
+        assert_eq!(
+            top(7, 3),
+            Output::Top(hands.into_iter().take(3).map(|hand| (49, hand)).collect())
+        );
+
+        // The top hands don't have a target score.
+        assert_eq!(
+            Parameters::builder()
+                .top(3)
+                .target_score(46)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("top", "target_score")
+        );
+    }
+
+    #[test]
+    fn deal() {
+        let deal = |scores: Vec<u32>, output| {
+            let parameters = Parameters::builder()
+                .hand_size(2)
+                .deal(scores)
+                .output(output)
+                .build()
+                .unwrap();
+            super::scrabble(parameters).unwrap()
+        };
+
+        // Only one player can have the Q and the Z.
+        assert_eq!(deal(vec![20, 20], OutputFormat::Count), Output::Count(0));
+        assert_eq!(deal(vec![20, 0], OutputFormat::Count), Output::Count(1));
+        assert_eq!(
+            deal(vec![20, 0], OutputFormat::List),
+            Output::Deals(vec![vec_of_strings!["QZ", "  "]])
+        );
+
+        // The X and the Z are in different hands, each with the J or the Q.
+        assert_eq!(deal(vec![18, 18], OutputFormat::Count), Output::Count(4));
+        assert_eq!(
+            deal(vec![18, 18], OutputFormat::List),
+            Output::Deals(vec![
+                vec_of_strings!["JQ", "XZ"],
+                vec_of_strings!["JZ", "XQ"],
+                vec_of_strings!["XQ", "JZ"],
+                vec_of_strings!["XZ", "JQ"],
+            ])
+        );
+
+        // Each hand has its own score instead of the target score.
+        assert_eq!(
+            Parameters::builder()
+                .deal(vec![46, 46])
+                .target_score(46)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("deal", "target_score")
+        );
+    }
+
+    #[test]
+    fn remaining_score_bounds() {
+        let tiles_by_value = group_tiles_by_value(STANDARD_ENGLISH_SCRABBLE_TILES);
+        let ten_points = tiles_by_value.len() - 1;
+        let mut abstract_tiles_drawn = vec![0; tiles_by_value.len()];
+
+        // The Q and the Z are the only tiles with 10 points.
+        assert_eq!(
+            super::remaining_score_bounds(&tiles_by_value, &abstract_tiles_drawn, ten_points, 2),
+            Some((20, 20))
+        );
+        abstract_tiles_drawn[ten_points] = 1;
+        assert_eq!(
+            super::remaining_score_bounds(&tiles_by_value, &abstract_tiles_drawn, ten_points, 1),
+            Some((10, 10))
+        );
+
+        // There aren't enough tiles left to fill the hand.
+        assert_eq!(
+            super::remaining_score_bounds(&tiles_by_value, &abstract_tiles_drawn, ten_points, 2),
+            None
+        );
+        assert_eq!(
+            super::remaining_score_bounds(&tiles_by_value, &abstract_tiles_drawn, 0, 100),
+            None
+        );
+    }
+
+    #[test]
+    fn at_least() {
+        fn count(parameters: ParametersBuilder) -> SolutionCount {
+            match super::scrabble(parameters.build().unwrap()).unwrap() {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            }
+        }
+
+        // The highest score of a hand of 7 tiles is 49.
+        let at_least = count(Parameters::builder().target_score(46).at_least(true));
+        let exact: SolutionCount = (46..=49)
+            .map(|score| count(Parameters::builder().target_score(score)))
+            .sum();
+        assert_eq!(at_least, exact);
+        assert_eq!(
+            Parameters::builder()
+                .at_least(true)
+                .min_score(40)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("at_least", "min_score")
+        );
+    }
+
+    #[test]
+    fn maximize() {
+        let parameters = Parameters::builder()
+            .maximize(true)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            // The highest score of a hand of 7 tiles is 49, with Q, Z, J, X, K and two 4-point tiles.
+            Output::List(hands) => assert_eq!(hands.len(), 15),
+            _ => panic!("expected a list"),
+        }
+
+        let parameters = Parameters::builder()
+            .hand_size(2)
+            .maximize(true)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::List(hands) => assert_eq!(hands.iter().collect::<Vec<_>>(), ["QZ"]),
+            _ => panic!("expected a list"),
+        }
+
+        assert_eq!(
+            Parameters::builder()
+                .maximize(true)
+                .target_score(40)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("maximize", "target_score")
+        );
+    }
+
+    #[test]
+    fn hand_size_range() {
+        let count =
+            |parameters: ParametersBuilder| match super::scrabble(parameters.build().unwrap())
+                .unwrap()
+            {
+                Output::Count(count) => count,
+                _ => panic!("expected a count"),
+            };
+
+        // The counts built for every hand size at once match the searches of each hand size.
+        let parameters = Parameters::builder()
+            .hand_sizes(1..=8)
+            .min_score(18)
+            .max_score(20)
+            .build()
+            .unwrap();
+        match super::scrabble(parameters).unwrap() {
+            Output::ByHandSize(counts) => {
+                assert_eq!(
+                    counts.keys().copied().collect::<Vec<_>>(),
+                    (1..=8).collect::<Vec<_>>()
+                );
+                for (&hand_size, &hand_count) in &counts {
+                    assert_eq!(
+                        hand_count,
+                        count(
+                            Parameters::builder()
+                                .hand_size(hand_size)
+                                .min_score(18)
+                                .max_score(20)
+                        ),
+                        "hand size {}",
+                        hand_size
+                    );
+                }
+            }
+            _ => panic!("expected counts by hand size"),
+        }
+
+        // The coefficients are only computed up to the highest score of a hand.
+        let parameters = |min_score, max_score| {
+            Parameters::builder()
+                .hand_sizes(2..=3)
+                .min_score(min_score)
+                .max_score(max_score)
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            super::scrabble(parameters(4_000_000_000, 4_000_000_000)).unwrap(),
+            Output::ByHandSize(vec![(2, 0), (3, 0)].into_iter().collect())
+        );
+        assert_eq!(
+            super::scrabble(parameters(25, 4_000_000_000)).unwrap(),
+            super::scrabble(parameters(25, 30)).unwrap()
+        );
+
+        assert_eq!("2..=10".parse::<HandSize>().unwrap().to_string(), "2..=10");
+        assert_eq!("7".parse::<HandSize>().unwrap(), HandSize::from(7));
+        assert!(matches!(
+            "10..=2".parse::<HandSize>(),
+            Err(HandSizeError::EmptyRange(10, 2))
+        ));
+
+        let parameters = Parameters::builder()
+            .hand_sizes(2..=10)
+            .output(OutputFormat::List)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            super::scrabble(parameters),
+            Err(Error::Parameters(ParameterError::HandSizeRange(_)))
+        ));
+    }
+
+    #[test]
+    fn super_scrabble() {
+        let report = check_distribution(SUPER_SCRABBLE_TILES, Some(200));
+        assert_eq!(report.total_tiles, 200);
+
+        let parameters = Parameters::builder()
+            .edition(Edition::Super)
+            .build()
+            .unwrap();
+        assert_eq!(super::scrabble(parameters).unwrap(), Output::Count(3801));
+
+        let parameters = Parameters::builder()
+            .edition(Edition::Super)
+            .tiles("tiles.txt")
+            .build();
+        assert!(matches!(
+            parameters,
+            Err(BuildError::Conflict("edition", "tiles"))
+        ));
     }
 
-    fn cartesian_product(solutions_by_value: Vec<Self>) -> Self {
-        // We only need to multiply the counts together.
-        solutions_by_value.iter().product()
+    #[test]
+    fn shard() {
+        let count: u64 = ["1/3", "2/3", "3/3"]
+            .iter()
+            .map(|shard| {
+                let parameters = Parameters::builder()
+                    .shard(shard.parse().unwrap())
+                    .build()
+                    .unwrap();
+                match super::scrabble(parameters).unwrap() {
+                    Output::Count(count) => count,
+                    _ => panic!("expected a count"),
+                }
+            })
+            .sum();
+        assert_eq!(count, 138);
     }
-}
 
-/// A `SolutionAccumulator` that list all hands that match the target score.
-type SolutionList = Vec<String>;
+    #[test]
+    fn simulation() {
+        let parameters = Parameters::builder()
+            .hand_size(2)
+            .target_score(2)
+            .build()
+            .unwrap();
+        let simulation = simulate(
+            parameters,
+            NonZeroUsize::new(10_000).unwrap(),
+            &mut StdRng::seed_from_u64(1),
+        )
+        .unwrap();
 
-impl SolutionAccumulator for SolutionList {
-    fn new() -> Self {
-        vec![]
+        // Two of the 68 one-point tiles, or a blank and one of the 7 two-point tiles,
+        // out of the 4950 pairs of tiles.
+        let exact = (68.0 * 67.0 / 2.0 + 2.0 * 7.0) / 4950.0;
+        assert_eq!(simulation.samples, 10_000);
+        assert!((simulation.frequency() - exact).abs() < 0.03);
     }
 
-    fn add_solution(&mut self, solution_fn: impl FnOnce() -> String) {
-        self.push(solution_fn());
+    #[test]
+    fn max_solutions() {
+        let five = NonZeroUsize::new(5).unwrap();
+        let parameters = |output| {
+            Parameters::builder()
+                .output(output)
+                .min_score(45)
+                .max_score(46)
+                .max_solutions(five)
+                .build()
+                .unwrap()
+        };
+
+        match super::scrabble(parameters(OutputFormat::List)).unwrap() {
+            Output::List(hands) => assert_eq!(hands.len(), 5),
+            _ => panic!("expected a list"),
+        }
+
+        match super::scrabble(parameters(OutputFormat::ByScore)).unwrap() {
+            Output::ByScore(hands_by_score) => {
+                assert_eq!(hands_by_score.values().map(Vec::len).sum::<usize>(), 5)
+            }
+            _ => panic!("expected a list by score"),
+        }
+
+        assert_eq!(
+            super::iter_hands(parameters(OutputFormat::List))
+                .unwrap()
+                .count(),
+            5
+        );
+
+        // The count isn't a list, so it isn't limited.
+        assert_eq!(
+            super::scrabble(parameters(OutputFormat::Count)).unwrap(),
+            Output::Count(314 + 138)
+        );
     }
 
-    fn add_solutions(&mut self, other: Self) {
-        self.extend(other);
+    #[test]
+    fn by_score_large_range() {
+        let parameters = Parameters::builder()
+            .output(OutputFormat::ByScore)
+            .min_score(48)
+            .max_score(4_000_000_000)
+            .build()
+            .unwrap();
+
+        // Only the scores that a hand can have are listed.
+        match super::scrabble(parameters).unwrap() {
+            Output::ByScore(hands_by_score) => assert_eq!(
+                hands_by_score
+                    .iter()
+                    .map(|(&score, hands)| (score, hands.len()))
+                    .collect::<Vec<_>>(),
+                [(48, 50), (49, 15)]
+            ),
+            _ => panic!("expected a list by score"),
+        }
     }
 
-    fn cartesian_product(solutions_by_value: Vec<Self>) -> Self {
-        solutions_by_value
-            .into_iter()
-            .multi_cartesian_product()
-            .map(|v| v.join(""))
-            .collect()
+    #[test]
+    fn builder_conflicts() {
+        assert_eq!(
+            Parameters::builder()
+                .target_score(46)
+                .target_percent(50)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("target_percent", "target_score")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .target_percent(4_000_000_000)
+                .build()
+                .unwrap_err(),
+            BuildError::TooLarge("target_percent", 100)
+        );
+        assert_eq!(
+            Parameters::builder()
+                .expected_total(100)
+                .build()
+                .unwrap_err(),
+            BuildError::MissingRequirement("expected_total", "distribution_check")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .letters_only(true)
+                .hand_format(HandFormat::Dashed)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("hand_format", "letters_only")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .compare_editions(true)
+                .tiles("tiles.txt")
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("compare_editions", "tiles")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .compare_editions(true)
+                .min_score(40)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("compare_editions", "min_score")
+        );
     }
 
-    fn finish(&mut self) {
-        // Sort the results for easier eyeballing.
-        self.sort();
+    #[test]
+    fn iter_hands() {
+        let parameters = || Parameters::builder().output(OutputFormat::List);
+        let mut all_hands = match super::scrabble(parameters().build().unwrap()).unwrap() {
+            Output::List(hands) => hands,
+            _ => panic!("expected a list"),
+        };
+        let mut hands: Vec<_> = super::iter_hands(parameters().build().unwrap())
+            .unwrap()
+            .collect();
+        hands.sort();
+        assert_eq!(hands, all_hands);
+
+        let parameters = || parameters().hand_format(HandFormat::Dashed);
+        all_hands = match super::scrabble(parameters().build().unwrap()).unwrap() {
+            Output::List(hands) => hands,
+            _ => panic!("expected a list"),
+        };
+        let hands: Vec<_> = super::iter_hands(parameters().build().unwrap())
+            .unwrap()
+            .take(3)
+            .collect();
+        assert_eq!(hands.len(), 3);
+        for hand in hands {
+            assert!(all_hands.contains(&hand), "{} is not a solution", hand);
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn iter_sorted_hands() {
+        let list = |parameters: ParametersBuilder| match super::scrabble(
+            parameters.output(OutputFormat::List).build().unwrap(),
+        )
+        .unwrap()
+        {
+            Output::List(hands) => hands,
+            _ => panic!("expected a list"),
+        };
+        let sorted = |parameters: ParametersBuilder| -> Vec<_> {
+            super::iter_sorted_hands(parameters.build().unwrap())
+                .unwrap()
+                .collect()
+        };
 
-    macro_rules! vec_of_strings {
-        ($($x:expr),*) => {
-            vec![
-                $(String::from($x),)*
-            ]
+        // The hands come out in the order of the list output.
+        let parameters = || Parameters::builder().hand_size(5).target_score(30);
+        assert_eq!(sorted(parameters()), list(parameters()));
+        let parameters = || parameters().include(vec!['Q']).exclude(vec!['Z']);
+        assert_eq!(sorted(parameters()), list(parameters()));
+        let parameters = || {
+            Parameters::builder()
+                .hand_size(3)
+                .min_score(25)
+                .max_score(28)
         };
+        assert_eq!(sorted(parameters()), list(parameters()));
+        let parameters = || Parameters::builder().shard("2/3".parse().unwrap());
+        assert_eq!(sorted(parameters()), list(parameters()));
+        let parameters = || {
+            Parameters::builder()
+                .hand_size(2)
+                .target_score(11)
+                .blank_mode(BlankMode::Wildcard)
+        };
+        assert_eq!(sorted(parameters()), list(parameters()));
 
-        ($($x:expr,)*) => {
-            vec_of_strings![$($x),*]
+        // The first hands are kept, unlike the list output which keeps the first ones found.
+        let mut parameters = parameters().build().unwrap();
+        parameters.set_max_solutions(NonZeroUsize::new(3));
+        let hands: Vec<_> = super::iter_sorted_hands(parameters).unwrap().collect();
+        assert_eq!(hands, vec_of_strings!["AQ", "AZ", "BJ"]);
+    }
+
+    #[test]
+    fn stream() {
+        let parameters = || {
+            Parameters::builder()
+                .output(OutputFormat::List)
+                .stream(true)
+        };
+        let mut written = vec![];
+        let output = Scrabble::solve_to(parameters().build().unwrap(), &mut written).unwrap();
+        assert_eq!(output, Output::Count(138));
+        let written = String::from_utf8(written).unwrap();
+        let hands: Vec<_> = written.lines().map(str::to_owned).collect();
+        assert_eq!(
+            Output::List(hands),
+            super::scrabble(parameters().build().unwrap()).unwrap()
+        );
+
+        assert_eq!(
+            Scrabble::solve_to(
+                parameters().output(OutputFormat::Count).build().unwrap(),
+                &mut io::sink()
+            )
+            .unwrap_err()
+            .to_string(),
+            "`--stream` can't be used with `--output count`"
+        );
+    }
+
+    #[test]
+    fn write_hands() {
+        let parameters = || Parameters::builder().output(OutputFormat::List);
+        let all_hands = match super::scrabble(parameters().build().unwrap()).unwrap() {
+            Output::List(hands) => hands,
+            _ => panic!("expected a list"),
+        };
+
+        let mut written = vec![];
+        let count = super::write_hands(parameters().build().unwrap(), &mut written).unwrap();
+        assert_eq!(count, 138);
+
+        let written = String::from_utf8(written).unwrap();
+        let mut hands: Vec<_> = written.lines().map(str::to_owned).collect();
+        hands.sort();
+        assert_eq!(hands, all_hands);
+    }
+
+    #[test]
+    fn interrupted() {
+        let parameters = |interrupt: &Interrupt| Parameters {
+            output: OutputFormat::List,
+            interrupt: interrupt.clone(),
+            ..default_parameters()
+        };
+
+        let all_hands = match super::scrabble(parameters(&Interrupt::new())).unwrap() {
+            Output::List(hands) => hands,
+            _ => panic!("expected a list"),
+        };
+
+        let interrupt = Interrupt::new();
+        let result = std::thread::scope(|scope| {
+            let search = scope.spawn(|| super::scrabble(parameters(&interrupt)));
+            scope.spawn(|| interrupt.trigger());
+            search.join().unwrap()
+        });
+
+        match result.unwrap() {
+            Output::List(hands) => {
+                assert!(hands.len() <= all_hands.len());
+                for hand in hands {
+                    assert!(all_hands.contains(&hand), "{} is not a solution", hand);
+                }
+            }
+            _ => panic!("expected a list"),
         }
+
+        // A search that is interrupted before it starts finds nothing.
+        let interrupt = Interrupt::new();
+        interrupt.trigger();
+        assert_eq!(
+            super::scrabble(parameters(&interrupt)).unwrap(),
+            Output::List(vec![])
+        );
     }
 
     #[test]
-    fn solution_count() {
-        let result = super::scrabble(Parameters {
-            hand_size: 7,
-            target_score: 46,
-            output: OutputFormat::Count,
+    fn progress() {
+        let parameters = |progress: &Progress| Parameters {
+            progress: progress.clone(),
+            ..default_parameters()
+        };
+
+        // The standard tiles have 8 distinct values, counting the blanks.
+        let progress = Progress::new();
+        progress.request_snapshot();
+        super::scrabble(parameters(&progress)).unwrap();
+        assert_eq!(progress.length(), 3432);
+        assert_eq!(progress.position(), progress.length());
+        // The combinations that can't reach the target score are pruned before they are visited.
+        assert_eq!(
+            progress.snapshot(),
+            "tile values 1 4 5 8 8 10 10 (score 46)"
+        );
+
+        let progress = Progress::new();
+        assert_eq!(
+            super::iter_hands(parameters(&progress)).unwrap().count(),
+            138
+        );
+        assert_eq!(progress.length(), 3432);
+        assert_eq!(progress.position(), progress.length());
+    }
+
+    #[test]
+    fn explain_pruning() {
+        let mut parameters = Parameters::builder().explain_pruning(true).build().unwrap();
+        let progress = Progress::new();
+        parameters.set_progress(progress.clone());
+
+        assert!(parameters.explains_pruning());
+        assert_eq!(super::scrabble(parameters).unwrap(), Output::Count(138));
+        assert!(progress.pruned() > 0);
+        assert!(progress.explored() > 0);
+    }
+
+    #[test]
+    fn cached_tiles() {
+        let path = std::env::temp_dir().join(format!("mpmp-cached-{}.txt", std::process::id()));
+        fs::write(&path, "A 7 1").unwrap();
+        let mut entries = HashMap::new();
+        let loads = std::cell::Cell::new(0);
+        let mut load = || {
+            cached(&mut entries, &path, |path| {
+                loads.set(loads.get() + 1);
+                load_tiles(path)
+            })
+        };
+
+        assert_eq!(load().unwrap()[0].occurrences, 7);
+        assert_eq!(load().unwrap()[0].occurrences, 7);
+        assert_eq!(loads.get(), 1);
+
+        // A file that changed is loaded again.
+        fs::write(&path, "A 8 1").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(load().unwrap()[0].occurrences, 8);
+        assert_eq!(loads.get(), 2);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn multisets() {
+        assert_eq!(super::multisets(7, 7), 1716);
+        assert_eq!(super::multisets(7, 0), 1);
+        assert_eq!(super::multisets(0, 0), 1);
+        assert_eq!(super::multisets(0, 3), 0);
+        assert_eq!(super::multisets(1, 3), 1);
+        assert_eq!(super::multisets(1000, 1000), u64::MAX);
+    }
+
+    #[test]
+    fn distribution_check_standard() {
+        let report = check_distribution(STANDARD_ENGLISH_SCRABBLE_TILES, Some(100));
+
+        assert_eq!(report.total_tiles, 100);
+        assert_eq!(report.total_value, 187);
+        assert!(!report.to_string().contains("warning"));
+    }
+
+    #[test]
+    fn distribution_check_wrong_file() {
+        // The occurrences for E were mistyped as 2 instead of 12.
+        let tiles = parse_tiles(
+            "
+            # A truncated distribution with a typo.
+            _ 2 0
+            A 9 1
+            E 2 1
+            Z 1 10
+            ",
+        )
+        .unwrap();
+        let report = check_distribution(&tiles, Some(24));
+
+        assert_eq!(report.total_tiles, 14);
+        assert_eq!(report.total_value, 21);
+        assert!(report
+            .to_string()
+            .contains("warning: expected 24 tiles, but the distribution contains 14"));
+    }
+
+    #[test]
+    fn distribution_check_large_numbers() {
+        let tiles = parse_tiles(
+            "
+            A 4294967295 4294967295
+            B 4294967295 4294967295
+            ",
+        )
+        .unwrap();
+        let report = check_distribution(&tiles, None);
+
+        assert_eq!(report.total_tiles, 2 * u64::from(u32::MAX));
+        assert_eq!(report.total_value, 2 * u128::from(u32::MAX).pow(2));
+
+        // The search rejects the distributions whose totals don't fit in a `u32`.
+        let solve = |parameters: ParametersBuilder| {
+            parameters
+                .build()
+                .map_err(Error::from)
+                .and_then(super::scrabble)
+        };
+        assert!(matches!(
+            solve(Parameters::builder().value('E', u32::MAX)),
+            Err(Error::Tiles(TilesError::TooLarge(100, _)))
+        ));
+        assert!(matches!(
+            solve(Parameters::builder().bags(Bags::new().all(u32::MAX))),
+            Err(Error::Tiles(TilesError::TooLarge(_, _)))
+        ));
+    }
+
+    #[test]
+    fn parse_tiles_invalid_line() {
+        let result = parse_tiles("A 9 1\nB two 3\n");
+
+        assert!(matches!(result, Err(TilesError::InvalidLine(2))));
+    }
+
+    #[test]
+    fn parse_tiles_toml() {
+        let tiles = super::parse_tiles_toml(
+            r#"
+            [[tiles]]
+            letter = "_"
+            occurrences = 2
+            value = 0
+
+            [[tiles]]
+            letter = "A"
+            occurrences = 9
+            value = 1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(tiles, &STANDARD_ENGLISH_SCRABBLE_TILES[..2]);
+        assert!(matches!(
+            super::parse_tiles_toml("[[tiles]]\nletter = \"A\"\noccurrences = -1\nvalue = 1\n"),
+            Err(TilesError::InvalidTile(1))
+        ));
+        assert!(matches!(
+            super::parse_tiles_toml("letter = \"A\"\n"),
+            Err(TilesError::MissingTiles)
+        ));
+    }
+
+    #[test]
+    fn parse_tiles_csv() {
+        let tiles = super::parse_tiles_csv(
+            "letter,occurrences,value\n# The blank tiles.\n_,2,0\nA, 9, 1\n",
+        )
+        .unwrap();
+
+        assert_eq!(tiles, &STANDARD_ENGLISH_SCRABBLE_TILES[..2]);
+        assert!(matches!(
+            super::parse_tiles_csv("letter,occurrences,value\nA,9,1\nB,two,3\n"),
+            Err(TilesError::InvalidLine(3))
+        ));
+    }
+
+    #[test]
+    fn counting_methods() {
+        let solve = |parameters: ParametersBuilder, method| {
+            super::scrabble(parameters.method(method).build().unwrap()).unwrap()
+        };
+
+        // The generating function and the memoized count count the same hands as the search.
+        let parameters_list: Vec<Box<dyn Fn() -> ParametersBuilder>> = vec![
+            Box::new(Parameters::builder),
+            Box::new(|| Parameters::builder().hand_size(5).target_score(30)),
+            Box::new(|| {
+                Parameters::builder()
+                    .hand_size(3)
+                    .min_score(25)
+                    .max_score(28)
+            }),
+            Box::new(|| {
+                Parameters::builder()
+                    .hand_size(9)
+                    .target_score(20)
+                    .include(vec!['Z', ' '])
+                    .exclude(vec!['Q'])
+            }),
+            Box::new(|| Parameters::builder().output(OutputFormat::Probability)),
+        ];
+        for parameters in parameters_list {
+            let output = solve(parameters(), Method::Search);
+            assert_eq!(solve(parameters(), Method::GeneratingFunction), output);
+            assert_eq!(solve(parameters(), Method::Memoized), output);
+        }
+
+        // The whole bag is a single hand.
+        for &method in &[Method::GeneratingFunction, Method::Memoized] {
+            assert_eq!(
+                solve(
+                    Parameters::builder().hand_size(100).target_score(187),
+                    method
+                ),
+                Output::Count(1)
+            );
+        }
+
+        // The coefficients stop at the highest score of a hand.
+        assert_eq!(
+            solve(
+                Parameters::builder().min_score(40).max_score(4_000_000_000),
+                Method::GeneratingFunction
+            ),
+            Output::Count(8728)
+        );
+
+        assert!(matches!(
+            Parameters::builder()
+                .method(Method::GeneratingFunction)
+                .output(OutputFormat::List)
+                .build()
+                .unwrap()
+                .validate(STANDARD_ENGLISH_SCRABBLE_TILES),
+            Err(ParameterError::Method(
+                "generating-function",
+                "`--output list`"
+            ))
+        ));
+    }
+
+    #[test]
+    fn counting_methods_large_scores() {
+        let solve = |parameters: ParametersBuilder, method| {
+            super::scrabble(parameters.method(method).build().unwrap()).unwrap()
+        };
+
+        // Only the scores up to the highest score of a hand are counted.
+        for &method in &[Method::Search, Method::GeneratingFunction, Method::Memoized] {
+            assert_eq!(
+                solve(
+                    Parameters::builder().min_score(40).max_score(4_000_000_000),
+                    method
+                ),
+                Output::Count(8728)
+            );
+            assert!(matches!(
+                super::scrabble(
+                    Parameters::builder()
+                        .target_score(u32::MAX)
+                        .method(method)
+                        .build()
+                        .unwrap()
+                ),
+                Err(Error::Parameters(ParameterError::UnreachableScore(
+                    u32::MAX,
+                    5,
+                    49
+                )))
+            ));
+        }
+
+        // The hands of 90 tiles are too many to search, but the counting methods agree.
+        let parameters = || {
+            Parameters::builder()
+                .hand_size(90)
+                .min_score(150)
+                .max_score(u32::MAX)
+        };
+        let output = solve(parameters(), Method::GeneratingFunction);
+        assert_eq!(solve(parameters(), Method::Memoized), output);
+        assert_ne!(output, Output::Count(0));
+    }
+
+    #[test]
+    fn parallel_search() {
+        let solve = |threads, parameters: ParametersBuilder| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| super::scrabble(parameters.build().unwrap()).unwrap())
+        };
+
+        // The hands of 14 tiles are searched on several threads when there are some,
+        // and they are the same as on a single thread.
+        let parameters = || Parameters::builder().hand_size(14).target_score(25);
+        assert_eq!(solve(4, parameters()), Output::Count(143_129_345));
+        assert_eq!(solve(1, parameters()), Output::Count(143_129_345));
+
+        let parameters = || {
+            Parameters::builder()
+                .hand_size(14)
+                .min_score(71)
+                .max_score(72)
+                .output(OutputFormat::ByScore)
+        };
+        assert_eq!(solve(4, parameters()), solve(1, parameters()));
+
+        let parameters = || {
+            Parameters::builder()
+                .hand_size(14)
+                .target_score(72)
+                .output(OutputFormat::List)
+        };
+        match solve(4, parameters()) {
+            Output::List(hands) => assert_eq!(hands.len(), 10257),
+            _ => panic!("expected a list"),
+        }
+        assert_eq!(solve(4, parameters()), solve(1, parameters()));
+    }
+
+    #[test]
+    fn tile_model_is_shareable_across_threads() {
+        let parameters = default_parameters();
+        let tiles_by_value = group_tiles_by_value(STANDARD_ENGLISH_SCRABBLE_TILES);
+
+        // The tile model is immutable during the search,
+        // so several threads can search with the same model.
+        let results: Vec<SolutionCount> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut solution_count = 0;
+                        let flow = draw_abstract(
+                            &parameters,
+                            &tiles_by_value,
+                            &(46..=46),
+                            &mut |_, solutions: SolutionCount| {
+                                solution_count += solutions;
+                                Ok(ControlFlow::Continue(()))
+                            },
+                            &mut vec![0; tiles_by_value.len()],
+                            0,
+                            0,
+                            0,
+                        )
+                        .unwrap();
+                        assert!(flow.is_continue());
+                        solution_count
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
         });
 
-        assert_eq!(result, Output::Count(138));
+        assert_eq!(results, [138, 138]);
     }
 
     #[test]
-    fn solution_list() {
+    fn solution_list_letters_only() {
         let result = super::scrabble(Parameters {
-            hand_size: 7,
-            target_score: 46,
             output: OutputFormat::List,
-        });
+            letters_only: true,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        match result {
+            Output::List(hands) => {
+                assert_eq!(hands.len(), 138);
+                assert_eq!(hands[0], "A F J K Q X Z");
+                assert!(hands.contains(&String::from("B×2 F J Q X Z")));
+            }
+            _ => panic!("expected a list"),
+        }
+    }
+
+    #[test]
+    fn hand_formats() {
+        assert_eq!(render_hand("AFKJXQZ", HandFormat::Plain), "AFKJXQZ");
+        assert_eq!(render_hand("AFKJXQZ", HandFormat::Dashed), "A-F-K-J-X-Q-Z");
+        assert_eq!(render_hand("A QZ", HandFormat::Spaced), "A _ Q Z");
+        assert_eq!(render_hand("AFKJXQZ", HandFormat::Lower), "afkjxqz");
+    }
+
+    #[test]
+    fn colors() {
+        let mut hands = vec_of_strings![" AQ"];
+        let parameters = Parameters::builder().build().unwrap();
+        apply_colors(&mut hands, STANDARD_ENGLISH_SCRABBLE_TILES, &parameters);
+        assert_eq!(hands, vec_of_strings!["\x1b[2m \x1b[0mA\x1b[31mQ\x1b[0m"]);
 
+        let mut hands = vec_of_strings!["_×2 Q"];
+        let parameters = Parameters::builder().letters_only(true).build().unwrap();
+        apply_colors(&mut hands, STANDARD_ENGLISH_SCRABBLE_TILES, &parameters);
+        assert_eq!(hands, vec_of_strings!["\x1b[2m_\x1b[0m×2 \x1b[31mQ\x1b[0m"]);
+    }
+
+    #[test]
+    fn render_values() {
+        let letter_values = LetterValues::from_tiles(STANDARD_ENGLISH_SCRABBLE_TILES);
         assert_eq!(
-            result,
-            Output::List(vec_of_strings![
-                "AFKJXQZ", "AHKJXQZ", "AVKJXQZ", "AWKJXQZ", "AYKJXQZ", "BBFJXQZ", "BBHJXQZ",
-                "BBVJXQZ", "BBWJXQZ", "BBYJXQZ", "BCFJXQZ", "BCHJXQZ", "BCVJXQZ", "BCWJXQZ",
-                "BCYJXQZ", "BMFJXQZ", "BMHJXQZ", "BMVJXQZ", "BMWJXQZ", "BMYJXQZ", "BPFJXQZ",
-                "BPHJXQZ", "BPVJXQZ", "BPWJXQZ", "BPYJXQZ", "CCFJXQZ", "CCHJXQZ", "CCVJXQZ",
-                "CCWJXQZ", "CCYJXQZ", "CMFJXQZ", "CMHJXQZ", "CMVJXQZ", "CMWJXQZ", "CMYJXQZ",
-                "CPFJXQZ", "CPHJXQZ", "CPVJXQZ", "CPWJXQZ", "CPYJXQZ", "DBKJXQZ", "DCKJXQZ",
-                "DFFJXQZ", "DFHJXQZ", "DFVJXQZ", "DFWJXQZ", "DFYJXQZ", "DHHJXQZ", "DHVJXQZ",
-                "DHWJXQZ", "DHYJXQZ", "DMKJXQZ", "DPKJXQZ", "DVVJXQZ", "DVWJXQZ", "DVYJXQZ",
-                "DWWJXQZ", "DWYJXQZ", "DYYJXQZ", "EFKJXQZ", "EHKJXQZ", "EVKJXQZ", "EWKJXQZ",
-                "EYKJXQZ", "GBKJXQZ", "GCKJXQZ", "GFFJXQZ", "GFHJXQZ", "GFVJXQZ", "GFWJXQZ",
-                "GFYJXQZ", "GHHJXQZ", "GHVJXQZ", "GHWJXQZ", "GHYJXQZ", "GMKJXQZ", "GPKJXQZ",
-                "GVVJXQZ", "GVWJXQZ", "GVYJXQZ", "GWWJXQZ", "GWYJXQZ", "GYYJXQZ", "IFKJXQZ",
-                "IHKJXQZ", "IVKJXQZ", "IWKJXQZ", "IYKJXQZ", "LFKJXQZ", "LHKJXQZ", "LVKJXQZ",
-                "LWKJXQZ", "LYKJXQZ", "MMFJXQZ", "MMHJXQZ", "MMVJXQZ", "MMWJXQZ", "MMYJXQZ",
-                "MPFJXQZ", "MPHJXQZ", "MPVJXQZ", "MPWJXQZ", "MPYJXQZ", "NFKJXQZ", "NHKJXQZ",
-                "NVKJXQZ", "NWKJXQZ", "NYKJXQZ", "OFKJXQZ", "OHKJXQZ", "OVKJXQZ", "OWKJXQZ",
-                "OYKJXQZ", "PPFJXQZ", "PPHJXQZ", "PPVJXQZ", "PPWJXQZ", "PPYJXQZ", "RFKJXQZ",
-                "RHKJXQZ", "RVKJXQZ", "RWKJXQZ", "RYKJXQZ", "SFKJXQZ", "SHKJXQZ", "SVKJXQZ",
-                "SWKJXQZ", "SYKJXQZ", "TFKJXQZ", "THKJXQZ", "TVKJXQZ", "TWKJXQZ", "TYKJXQZ",
-                "UFKJXQZ", "UHKJXQZ", "UVKJXQZ", "UWKJXQZ", "UYKJXQZ",
-            ])
+            super::render_values("AFKJXQZ", &letter_values),
+            "A₁ F₄ K₅ J₈ X₈ Q₁₀ Z₁₀ = 46"
+        );
+        assert_eq!(
+            super::render_values(" QZ", &letter_values),
+            "_₀ Q₁₀ Z₁₀ = 20"
         );
+
+        assert_eq!(
+            Parameters::builder()
+                .show_values(true)
+                .letters_only(true)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("show_values", "letters_only")
+        );
+    }
+
+    #[test]
+    fn render_letters_only_blanks() {
+        assert_eq!(render_letters_only("  QZ"), "_×2 Q Z");
+    }
+
+    #[test]
+    fn solution_list_by_score() {
+        let list = super::scrabble(Parameters {
+            output: OutputFormat::List,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        let result = super::scrabble(Parameters {
+            min_score: Some(44),
+            max_score: Some(48),
+            output: OutputFormat::ByScore,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        match (list, result) {
+            (Output::List(list), Output::ByScore(hands_by_score)) => {
+                assert_eq!(
+                    hands_by_score.keys().cloned().collect::<Vec<_>>(),
+                    [44, 45, 46, 47, 48]
+                );
+                assert_eq!(hands_by_score[&46].len(), 138);
+                assert_eq!(hands_by_score[&46], list);
+            }
+            _ => panic!("unexpected output format"),
+        }
     }
 }
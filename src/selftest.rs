@@ -0,0 +1,115 @@
+//! The `selftest` subcommand, which checks that the puzzles still give their known answers.
+//!
+//! Each puzzle is solved with its default parameters, like the `all` subcommand does,
+//! and the result is compared with the answer given by Matt Parker
+//! that `list` shows in its JSON catalog,
+//! so that a build of the program can be checked without the test suite.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use serde_json::json;
+
+use matt_parkers_maths_puzzles::{
+    card::Card,
+    puzzle_info,
+    scrabble::{self, Scrabble},
+    spinning_table::SpinningTable,
+    Error, Solver,
+};
+
+use crate::{Context, Verbosity};
+
+/// The result of checking a puzzle's answer.
+#[derive(Debug, Serialize)]
+struct Check {
+    /// The puzzle's name.
+    puzzle: &'static str,
+
+    /// The known answer.
+    expected: String,
+
+    /// The answer that was found, or the error that prevented finding it.
+    actual: String,
+
+    /// Whether the answer that was found is the known one.
+    passed: bool,
+}
+
+impl Check {
+    /// Compares the answer found for a puzzle with the known answer.
+    fn new(puzzle: &'static str, actual: Result<String, Error>) -> Self {
+        let expected = puzzle_info::puzzles()
+            .iter()
+            .find(|info| info.name == puzzle)
+            .expect("unknown puzzle")
+            .answer
+            .to_string();
+        let actual = actual.unwrap_or_else(|err| format!("error: {}", err));
+        Check {
+            puzzle,
+            passed: actual == expected,
+            expected,
+            actual,
+        }
+    }
+}
+
+/// Solves the puzzles and compares their answers with the known ones,
+/// writing a report to `out`.
+///
+/// Returns whether all the answers are correct.
+pub fn run_selftest(
+    context: &Context,
+    json: bool,
+    verbosity: Verbosity,
+    out: &mut impl Write,
+) -> Result<bool, Error> {
+    let checks = vec![
+        Check::new(
+            SpinningTable::NAME,
+            SpinningTable::solve(context.default_parameters())
+                .map(|output| format!("{} solutions", output.number_of_solutions)),
+        ),
+        Check::new(
+            Scrabble::NAME,
+            Scrabble::solve(context.default_parameters()).map(|output| match output {
+                scrabble::Output::Count(count) => format!("{} hands", count),
+                _ => unreachable!("the default Scrabble output is a count"),
+            }),
+        ),
+        Check::new(
+            Card::NAME,
+            Card::solve(context.default_parameters())
+                .map(|solution| format!("{} flips: {:?}", solution.flips.len(), solution.flips)),
+        ),
+    ];
+
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if json {
+        let report = json!({
+            "passed": failed == 0,
+            "checks": checks,
+        });
+        writeln!(out, "{:#}", report)?;
+        return Ok(failed == 0);
+    }
+
+    if verbosity != Verbosity::Quiet {
+        for check in &checks {
+            if check.passed {
+                writeln!(out, "{}: ok ({})", check.puzzle, check.actual)?;
+            } else {
+                writeln!(
+                    out,
+                    "{}: FAILED (expected {}, got {})",
+                    check.puzzle, check.expected, check.actual
+                )?;
+            }
+        }
+    }
+
+    writeln!(out, "{} passed, {} failed", checks.len() - failed, failed)?;
+    Ok(failed == 0)
+}
@@ -0,0 +1,121 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    num::{NonZeroUsize, ParseIntError},
+    str::FromStr,
+};
+
+use quick_error::quick_error;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// A part of a search, given as `i/n` on the command line,
+/// that searches every `n`th branch at the top of the search, starting with the `i`th.
+///
+/// The `n` parts of a search together find the same solutions as the whole search,
+/// so it can be split across machines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// The number of the part, starting from 1.
+    index: usize,
+
+    /// The number of parts.
+    count: NonZeroUsize,
+}
+
+impl Shard {
+    /// Returns whether the branch at the given index (starting from 0)
+    /// at the top of the search belongs to this part.
+    pub fn contains(&self, branch: usize) -> bool {
+        branch % self.count.get() == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = ShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '/');
+        match (parts.next(), parts.next()) {
+            (Some(index), Some(count)) => {
+                let index = index.trim().parse()?;
+                let count = NonZeroUsize::new(count.trim().parse()?).ok_or(ShardError::NoParts)?;
+                if index < 1 || index > count.get() {
+                    return Err(ShardError::OutOfRange(index, count.get()));
+                }
+
+                Ok(Self { index, count })
+            }
+            _ => Err(ShardError::MissingSeparator),
+        }
+    }
+}
+
+impl Display for Shard {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Serializes the shard like it is given on the command line.
+#[cfg(feature = "serde")]
+impl Serialize for Shard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when parsing a shard.
+    #[derive(Debug)]
+    pub enum ShardError {
+        /// A number is not a valid number.
+        ParseIntError(err: ParseIntError) {
+            cause(err)
+            display("{}", err)
+            from()
+        }
+
+        /// The `/` between the part number and the number of parts is missing.
+        MissingSeparator {
+            display("expected `i/n`")
+        }
+
+        /// The number of parts is 0.
+        NoParts {
+            display("the number of parts must be at least 1")
+        }
+
+        /// The part number is not between 1 and the number of parts.
+        OutOfRange(index: usize, count: usize) {
+            display("the part number {} is not between 1 and {}", index, count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let shard: Shard = "2/3".parse().unwrap();
+
+        assert_eq!(shard.to_string(), "2/3");
+        let branches: Vec<_> = (0..7).filter(|&branch| shard.contains(branch)).collect();
+        assert_eq!(branches, [1, 4]);
+        assert!(matches!(
+            "2".parse::<Shard>(),
+            Err(ShardError::MissingSeparator)
+        ));
+        assert!(matches!("1/0".parse::<Shard>(), Err(ShardError::NoParts)));
+        assert!(matches!(
+            "0/3".parse::<Shard>(),
+            Err(ShardError::OutOfRange(0, 3))
+        ));
+        assert!(matches!(
+            "4/3".parse::<Shard>(),
+            Err(ShardError::OutOfRange(4, 3))
+        ));
+    }
+}
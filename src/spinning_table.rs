@@ -1,6 +1,45 @@
-use std::num::NonZeroUsize;
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    num::{NonZeroUsize, ParseIntError},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-use structopt::StructOpt;
+use quick_error::quick_error;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use serde_json::{json, Value};
+
+#[cfg(feature = "clap")]
+use clap::{Parser, ValueEnum};
+
+use tracing::{debug, debug_span, trace, Span};
+
+#[cfg(feature = "clap")]
+use crate::value_parser::non_zero_usize;
+
+use crate::{
+    builder::BuildError,
+    color::{self, Color},
+    interrupt::Interrupt,
+    progress::Progress,
+    shard::Shard,
+    Error, Solver,
+};
+
+/// How often the state of the search is saved with `--checkpoint`.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The number of permutations between two looks at the clock
+/// to decide whether a checkpoint is due.
+const CHECKPOINT_CLOCK_PERIOD: u64 = 1 << 16;
 
 /// Produces the solution to the spinning table puzzle.
 ///
@@ -11,106 +50,1663 @@ use structopt::StructOpt;
 /// Find an arrangement the other six investors could make
 /// such that there is no rotation
 /// that puts at least two of the investors in the correct seat.
-pub fn spinning_table(parameters: Parameters) -> Vec<Vec<usize>> {
-    // Let the first investor (1)
-    // sit in the correct place (1, or index 0 in the vector).
-    // Try all permutations of the remaining seats.
+pub fn spinning_table(parameters: Parameters) -> Result<Output, Error> {
+    spinning_table_to(parameters, &mut io::stdout())
+}
+
+/// Produces the solution to the spinning table puzzle
+/// like `spinning_table`,
+/// but writes the solutions to `out` instead of stdout with `--stream`.
+///
+/// `out` is flushed after each solution, so that it shows up as soon as it is found.
+pub fn spinning_table_to(
+    parameters: Parameters,
+    out: &mut impl io::Write,
+) -> Result<Output, Error> {
+    let number_of_seats = parameters.number_of_seats.get();
+    if !parameters.names.is_empty() && parameters.names.len() != number_of_seats {
+        return Err(FixError::WrongNumberOfNames(parameters.names.len(), number_of_seats).into());
+    }
+
+    // The solutions are written as JSON lines as soon as they are found,
+    // unless they need to be shuffled first.
+    let stream = parameters.stream || (parameters.ndjson && !parameters.shuffle);
+    // With `--shuffle`, all the solutions are found before they are limited,
+    // so that the solutions that are kept are a random sample.
+    let max_solutions = parameters
+        .max_solutions
+        .filter(|_| !parameters.shuffle)
+        .map_or(usize::MAX, NonZeroUsize::get);
+    let mut solutions_iter = iter_solutions(&parameters)?;
+    let mut solutions = vec![];
+    let mut number_of_solutions = 0;
+    if let Some(path) = &parameters.resume {
+        let checkpoint = Checkpoint::load(path)?;
+        solutions_iter.restore(&checkpoint)?;
+        solutions = checkpoint.solutions;
+        number_of_solutions = checkpoint.number_of_solutions;
+    }
+
+    let resumed_permutations = solutions_iter.permutations;
+    let resumed_solutions = number_of_solutions;
+
+    if parameters.checkpoint.is_some() {
+        solutions_iter.checkpoint_clock = Some((CHECKPOINT_INTERVAL, Instant::now()));
+    }
+
+    let presentation = Presentation {
+        names: parameters.names,
+        format: parameters.output,
+        color: parameters.color,
+        ndjson: parameters.ndjson,
+    };
+    while number_of_solutions < max_solutions {
+        let seats = match solutions_iter.step() {
+            Some(Step::Solution(seats)) => seats,
+            Some(Step::Checkpoint) => {
+                if let Some(path) = &parameters.checkpoint {
+                    solutions_iter
+                        .checkpoint(&solutions, number_of_solutions)
+                        .save(path)?;
+                }
+
+                continue;
+            }
+            None => break,
+        };
+
+        if stream {
+            let mut rendered = String::new();
+            presentation
+                .write_solution(&mut rendered, number_of_solutions, &seats)
+                .expect("writing to a String cannot fail");
+            writeln!(out, "{}", rendered)?;
+            // Show each solution as soon as it is found.
+            out.flush()?;
+        } else {
+            solutions.push(seats);
+        }
+
+        number_of_solutions += 1;
+    }
+
+    parameters
+        .progress
+        .explore(solutions_iter.permutations - resumed_permutations);
+    parameters
+        .progress
+        .find((number_of_solutions - resumed_solutions) as u64);
+
+    // Save the final state too, so that an interrupted search resumes where it stopped
+    // and a finished search isn't searched again.
+    if let Some(path) = &parameters.checkpoint {
+        solutions_iter
+            .checkpoint(&solutions, number_of_solutions)
+            .save(path)?;
+    }
+
+    if parameters.shuffle {
+        let mut rng = match parameters.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        solutions.shuffle(&mut rng);
+        if let Some(max_solutions) = parameters.max_solutions {
+            solutions.truncate(max_solutions.get());
+            number_of_solutions = solutions.len();
+        }
+    }
+
+    Ok(Output {
+        solutions,
+        number_of_solutions,
+        streamed: stream,
+        presentation,
+    })
+}
+
+/// Returns an iterator over the solutions to the spinning table puzzle,
+/// in the same order as `spinning_table`.
+///
+/// The solutions are found as the iterator is advanced,
+/// so they don't need to be held in memory.
+/// The presentation options (`--names`, `--output`, `--stream`, `--shuffle`)
+/// and the limit on the number of solutions are ignored.
+pub fn iter_solutions(parameters: &Parameters) -> Result<Solutions, Error> {
+    let number_of_seats = parameters.number_of_seats.get();
+    let fixes = fixes(parameters);
+    let fixed_seats = fixed_seats(number_of_seats, fixes)?;
+
+    // Build a vector with the investor numbers that are not fixed.
+    // This is the vector in which permutations will occur.
+    let free_investors: Vec<_> = (1..=number_of_seats)
+        .filter(|investor| !fixed_seats.contains(investor))
+        .collect();
+
+    // Build a vector with the indices of the seats that are not fixed.
+    let free_seat_indices: Vec<_> = (0..number_of_seats)
+        .filter(|&seat_index| fixed_seats[seat_index] == 0)
+        .collect();
+
+    // Each permutation of the free investors is a step of the search.
+    let free_investors_len = free_investors.len();
+    let number_of_permutations = factorial(free_investors_len);
+    parameters.progress.start(number_of_permutations);
+
+    let span = debug_span!("spinning_table", number_of_seats);
+    span.in_scope(|| {
+        debug!(
+            fixed = ?fixes,
+            permutations = number_of_permutations,
+            "searching the permutations of the free investors"
+        )
+    });
+
+    Ok(Solutions {
+        counters: vec![0; free_investors.len()],
+        level: None,
+        free_investors,
+        free_seat_indices,
+        seats: fixed_seats,
+        rotations: None,
+        include_redundant_solutions: parameters.include_redundant_solutions,
+        interrupt: parameters.interrupt.clone(),
+        progress: parameters.progress.clone(),
+        permutations: 0,
+        solutions: 0,
+        span,
+        checkpoint_clock: None,
+        shard: parameters.shard,
+        branch_size: factorial(free_investors_len.saturating_sub(1)),
+        branch_effect: match parameters.shard {
+            Some(_) => heap_effect(free_investors_len.saturating_sub(1)),
+            None => vec![],
+        },
+    })
+}
+
+/// Returns the investors pinned to a seat:
+/// the ones given with `--fix`, none with `--no-anchor`,
+/// or the first investor (1) in the correct place (1, or index 0 in the vector) by default.
+fn fixes(parameters: &Parameters) -> &[Fix] {
+    const DEFAULT_FIXES: &[Fix] = &[Fix {
+        investor: 1,
+        seat: 1,
+    }];
+
+    if parameters.no_anchor {
+        // Permute all the investors,
+        // which finds every rotation of every solution directly.
+        &[]
+    } else if parameters.fix.is_empty() {
+        DEFAULT_FIXES
+    } else {
+        &parameters.fix
+    }
+}
+
+/// Places the fixed investors in their seats;
+/// a 0 marks a seat that is not fixed.
+fn fixed_seats(number_of_seats: usize, fixes: &[Fix]) -> Result<Vec<usize>, FixError> {
+    let mut fixed_seats = vec![0; number_of_seats];
+    for fix in fixes {
+        if fix.investor == 0 || fix.investor > number_of_seats {
+            return Err(FixError::InvestorOutOfRange(fix.investor));
+        }
+
+        if fix.seat == 0 || fix.seat > number_of_seats {
+            return Err(FixError::SeatOutOfRange(fix.seat));
+        }
+
+        if fixed_seats.contains(&fix.investor) {
+            return Err(FixError::InvestorFixedTwice(fix.investor));
+        }
+
+        if fixed_seats[fix.seat - 1] != 0 {
+            return Err(FixError::SeatFixedTwice(fix.seat));
+        }
+
+        fixed_seats[fix.seat - 1] = fix.investor;
+    }
+
+    Ok(fixed_seats)
+}
+
+/// Returns where the elements of a slice of `len` elements end up
+/// after visiting all their permutations with Heap's algorithm,
+/// as the index before the permutations of the element at each index.
+fn heap_effect(len: usize) -> Vec<usize> {
+    let mut effect = vec![0; len.min(1)];
+    for len in 2..=len {
+        // Heap's algorithm visits the permutations of the first `len - 1` elements,
+        // then swaps the last element with another one, `len - 1` times,
+        // and visits the permutations of the first `len - 1` elements once more.
+        let mut elements: Vec<_> = (0..len).collect();
+        for swap in 0..len {
+            let permuted: Vec<_> = effect.iter().map(|&index| elements[index]).collect();
+            elements[..len - 1].copy_from_slice(&permuted);
+            if swap < len - 1 {
+                let other = if len % 2 == 1 { 0 } else { swap };
+                elements.swap(other, len - 1);
+            }
+        }
+
+        effect = elements;
+    }
+
+    effect
+}
+
+/// Returns the number of permutations of `n` investors,
+/// or `u64::MAX` if there are more than that.
+fn factorial(n: usize) -> u64 {
+    (1..=n as u64)
+        .try_fold(1, u64::checked_mul)
+        .unwrap_or(u64::MAX)
+}
+
+/// An iterator over the solutions to the spinning table puzzle.
+///
+/// This is returned by `iter_solutions`.
+#[derive(Debug)]
+pub struct Solutions {
+    /// The investor numbers that are not fixed,
+    /// in the order of the current permutation.
+    free_investors: Vec<usize>,
+
+    /// The state of Heap's algorithm:
+    /// the number of swaps done so far at each level.
+    counters: Vec<usize>,
+
+    /// The level at which Heap's algorithm resumes,
+    /// or `None` before the first permutation.
+    level: Option<usize>,
+
+    /// The indices of the seats that are not fixed.
+    free_seat_indices: Vec<usize>,
+
+    /// The investor number in each seat for the current permutation.
+    seats: Vec<usize>,
+
+    /// The last solution found and the number of its rotations produced so far,
+    /// when the redundant solutions are included.
+    rotations: Option<(Vec<usize>, usize)>,
+
+    /// Whether to produce every rotation of each solution.
+    include_redundant_solutions: bool,
+
+    /// Stops the search early.
+    interrupt: Interrupt,
+
+    /// Reports how many permutations have been visited.
+    progress: Progress,
+
+    /// The number of permutations visited so far.
+    permutations: u64,
+
+    /// The number of solutions found so far, not counting the rotations.
+    solutions: u64,
+
+    /// The span in which the events of the search are logged.
+    span: Span,
+
+    /// How often `step` stops to let a checkpoint be saved
+    /// and when it last stopped for one (or when the search started),
+    /// or `None` if no checkpoints are saved.
+    ///
+    /// The clock is only read when checkpoints are saved,
+    /// since it is not available in web pages.
+    checkpoint_clock: Option<(Duration, Instant)>,
+
+    /// The part of the search to search, if it is split.
+    shard: Option<Shard>,
+
+    /// The number of consecutive permutations
+    /// that have the same investor in the last free seat.
+    branch_size: u64,
+
+    /// Where the investors in the free seats but the last one end up
+    /// after all the permutations of a branch, as returned by `heap_effect`,
+    /// so that the branches of the other parts of the search can be skipped
+    /// without visiting their permutations.
+    branch_effect: Vec<usize>,
+}
+
+impl Solutions {
+    /// Moves to the next permutation of the free investors
+    /// with the iterative form of Heap's algorithm,
+    /// which visits the permutations in the same order as `permutohedron::heap_recursive`.
+    ///
+    /// Returns `false` once all the permutations have been visited.
+    fn next_permutation(&mut self) -> bool {
+        let mut level = match self.level {
+            Some(level) => level,
+            None => {
+                // The first permutation is the initial order.
+                self.level = Some(1);
+                return true;
+            }
+        };
+
+        while level < self.free_investors.len() {
+            if self.counters[level] < level {
+                let other = if level % 2 == 0 {
+                    0
+                } else {
+                    self.counters[level]
+                };
+                self.free_investors.swap(other, level);
+                self.counters[level] += 1;
+                self.level = Some(1);
+                return true;
+            }
+
+            self.counters[level] = 0;
+            level += 1;
+        }
+
+        self.level = Some(level);
+        false
+    }
+}
+
+impl Solutions {
+    /// Moves from the first permutation of a branch to its last permutation
+    /// without visiting the permutations in between,
+    /// so that the next permutation is the first one of the next branch.
+    fn skip_branch(&mut self) {
+        let last_level = self.free_investors.len().saturating_sub(1);
+        if last_level == 0 {
+            // There is only one permutation in the branch.
+            return;
+        }
+
+        let permuted: Vec<_> = self
+            .branch_effect
+            .iter()
+            .map(|&index| self.free_investors[index])
+            .collect();
+        self.free_investors[..last_level].copy_from_slice(&permuted);
+        for counter in &mut self.counters[..last_level] {
+            *counter = 0;
+        }
+
+        // Resume at the top of the search, which moves to the next branch.
+        self.level = Some(last_level);
+        self.permutations += self.branch_size - 1;
+        self.progress.advance(self.branch_size - 1);
+    }
+
+    /// Searches until the next solution is found
+    /// or until a checkpoint is due with `--checkpoint`.
+    ///
+    /// Returns `None` once the search is finished or interrupted.
+    fn step(&mut self) -> Option<Step> {
+        let span = self.span.clone();
+        let _entered = span.enter();
+        loop {
+            // The redundant solutions
+            // are simply the distinct rotations of the initial solution.
+            if let Some((seats, rotation)) = &mut self.rotations {
+                if *rotation < seats.len() {
+                    let mut rotated = seats.clone();
+                    rotated.rotate_right(*rotation);
+                    *rotation += 1;
+                    return Some(Step::Solution(rotated));
+                }
+
+                self.rotations = None;
+            }
+
+            // Only look at the clock every so often,
+            // since the permutations are much faster to visit.
+            if let Some((checkpoint_interval, last_checkpoint)) = &mut self.checkpoint_clock {
+                if self.permutations.is_multiple_of(CHECKPOINT_CLOCK_PERIOD)
+                    && last_checkpoint.elapsed() >= *checkpoint_interval
+                {
+                    *last_checkpoint = Instant::now();
+                    return Some(Step::Checkpoint);
+                }
+            }
+
+            // Stop the search if it was interrupted,
+            // keeping the solutions found so far.
+            if self.interrupt.is_triggered() || !self.next_permutation() {
+                debug!(
+                    permutations = self.permutations,
+                    rejected = self.permutations - self.solutions,
+                    solutions = self.solutions,
+                    interrupted = self.interrupt.is_triggered(),
+                    "search finished"
+                );
+                return None;
+            }
+
+            self.permutations += 1;
+            self.progress.advance(1);
+
+            // Heap's algorithm only changes the investor in the last free seat
+            // after all the permutations of the other investors,
+            // so the branches at the top of the search are consecutive.
+            if let Some(shard) = self.shard {
+                let branch = (self.permutations - 1) / self.branch_size;
+                if !shard.contains(branch as usize) {
+                    self.skip_branch();
+                    continue;
+                }
+            }
+
+            // Fill the free seats of the full sequence of seats,
+            // reusing the same vector for every permutation.
+            for (&seat_index, &investor) in self.free_seat_indices.iter().zip(&self.free_investors)
+            {
+                self.seats[seat_index] = investor;
+            }
+
+            let seats = &self.seats;
+            self.progress
+                .record_snapshot(|| SeatArrangement::new(seats).to_string());
+
+            if is_valid_solution(&self.seats) {
+                self.solutions += 1;
+                trace!(seats = ?self.seats, "found a solution");
+                let seats = self.seats.clone();
+                if !self.include_redundant_solutions {
+                    return Some(Step::Solution(seats));
+                }
+
+                self.rotations = Some((seats, 0));
+            }
+        }
+    }
+
+    /// Captures the state of the search,
+    /// along with the solutions that were found before it.
+    fn checkpoint(&self, solutions: &[Vec<usize>], number_of_solutions: usize) -> Checkpoint {
+        let mut fixed_seats = self.seats.clone();
+        for &seat_index in &self.free_seat_indices {
+            fixed_seats[seat_index] = 0;
+        }
+
+        Checkpoint {
+            fixed_seats,
+            include_redundant_solutions: self.include_redundant_solutions,
+            shard: self.shard,
+            free_investors: self.free_investors.clone(),
+            counters: self.counters.clone(),
+            level: self.level,
+            permutations: self.permutations,
+            valid_permutations: self.solutions,
+            rotations: self.rotations.clone(),
+            solutions: solutions.to_vec(),
+            number_of_solutions,
+        }
+    }
+
+    /// Moves the search to the state captured by a checkpoint.
+    ///
+    /// The checkpoint must have been saved with the same fixed seats,
+    /// the same `--include-redundant-solutions` and the same `--shard`.
+    fn restore(&mut self, checkpoint: &Checkpoint) -> Result<(), CheckpointError> {
+        let mut fixed_seats = self.seats.clone();
+        for &seat_index in &self.free_seat_indices {
+            fixed_seats[seat_index] = 0;
+        }
+
+        let mut sorted_investors = checkpoint.free_investors.clone();
+        sorted_investors.sort_unstable();
+        if checkpoint.fixed_seats != fixed_seats
+            || checkpoint.include_redundant_solutions != self.include_redundant_solutions
+            || checkpoint.shard != self.shard
+            || sorted_investors != self.free_investors
+            || checkpoint.counters.len() != self.counters.len()
+        {
+            return Err(CheckpointError::Mismatch);
+        }
+
+        self.free_investors = checkpoint.free_investors.clone();
+        self.counters = checkpoint.counters.clone();
+        self.level = checkpoint.level;
+        self.permutations = checkpoint.permutations;
+        self.solutions = checkpoint.valid_permutations;
+        self.rotations = checkpoint.rotations.clone();
+        self.progress.advance(checkpoint.permutations);
+        debug!(
+            permutations = self.permutations,
+            "resumed the search from a checkpoint"
+        );
+        Ok(())
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            if let Step::Solution(seats) = self.step()? {
+                return Some(seats);
+            }
+        }
+    }
+}
+
+/// What `Solutions::step` stopped at.
+#[derive(Debug)]
+enum Step {
+    /// A solution was found.
+    Solution(Vec<usize>),
+
+    /// It is time to save a checkpoint.
+    Checkpoint,
+}
+
+/// The spinning table puzzle.
+#[derive(Clone, Copy, Debug)]
+pub struct SpinningTable;
+
+impl Solver for SpinningTable {
+    const NAME: &'static str = "spinning-table";
+    type Parameters = Parameters;
+    type Output = Output;
+
+    fn solve(parameters: Parameters) -> Result<Output, Error> {
+        spinning_table(parameters)
+    }
+
+    fn solve_to(parameters: Parameters, mut out: &mut dyn io::Write) -> Result<Output, Error> {
+        spinning_table_to(parameters, &mut out)
+    }
+}
+
+/// Counts the solutions for each number of seats from 1 to `--number-of-seats`,
+/// treating arrangements that are rotations of each other as the same solution,
+/// then also treating arrangements that are reflections of each other as the same solution.
+pub fn dihedral_count(parameters: DihedralCountParameters) -> DihedralCounts {
+    let rows = (1..=parameters.number_of_seats.get())
+        .map(|number_of_seats| {
+            let canonical_solutions = spinning_table(Parameters {
+                number_of_seats: NonZeroUsize::new(number_of_seats).unwrap(),
+                include_redundant_solutions: false,
+                fix: vec![],
+                no_anchor: false,
+                shard: None,
+                names: vec![],
+                output: OutputFormat::List,
+                stream: false,
+                shuffle: false,
+                seed: None,
+                interrupt: Interrupt::default(),
+                progress: Progress::default(),
+                max_solutions: None,
+                color: false,
+                ndjson: false,
+                checkpoint: None,
+                resume: None,
+            })
+            .expect("the default fix is valid for any number of seats")
+            .solutions;
+
+            // The canonical solutions have the first investor in the first seat,
+            // so they are their own canonical rotation.
+            let rotation_redundant: BTreeSet<_> = canonical_solutions
+                .iter()
+                .flat_map(|solution| rotations(solution.clone()))
+                .collect();
+            let dihedral_canonical: BTreeSet<_> = canonical_solutions
+                .iter()
+                .map(|solution| {
+                    solution
+                        .clone()
+                        .min(canonical_rotation(&reflection(solution)))
+                })
+                .collect();
+            let dihedral_redundant: BTreeSet<_> = canonical_solutions
+                .iter()
+                .flat_map(|solution| {
+                    rotations(solution.clone()).chain(rotations(reflection(solution)))
+                })
+                .collect();
+
+            DihedralCountRow {
+                number_of_seats,
+                rotation_canonical: canonical_solutions.len(),
+                rotation_redundant: rotation_redundant.len(),
+                dihedral_canonical: dihedral_canonical.len(),
+                dihedral_redundant: dihedral_redundant.len(),
+            }
+        })
+        .collect();
+
+    DihedralCounts { rows }
+}
+
+/// Returns all the rotations of an arrangement of investors.
+fn rotations(seats: Vec<usize>) -> impl Iterator<Item = Vec<usize>> {
+    (0..seats.len()).map(move |rotation| {
+        let mut rotated = seats.clone();
+        rotated.rotate_right(rotation);
+        rotated
+    })
+}
+
+/// Returns the rotation of an arrangement of investors
+/// that has the first investor in the first seat.
+fn canonical_rotation(seats: &[usize]) -> Vec<usize> {
+    let mut rotated = seats.to_vec();
+    if let Some(seat_index) = seats.iter().position(|&investor| investor == 1) {
+        rotated.rotate_left(seat_index);
+    }
+
+    rotated
+}
+
+/// Returns the mirror image of an arrangement of investors.
+///
+/// The table is mirrored around the first seat,
+/// so seat k becomes seat n + 2 - k (and seat 1 stays in place),
+/// and the investors are renumbered the same way
+/// so that an investor who was in their correct seat still is.
+/// The mirror image of a valid solution is therefore also a valid solution.
+fn reflection(seats: &[usize]) -> Vec<usize> {
+    let number_of_seats = seats.len();
+    let mirror = |number: usize| (number_of_seats + 1 - number) % number_of_seats + 1;
+    let mut reflected = vec![0; number_of_seats];
+    for (seat, &investor) in (1..).zip(seats.iter()) {
+        reflected[mirror(seat) - 1] = mirror(investor);
+    }
+
+    reflected
+}
+
+/// Seats the investors that are not fixed at random `samples` times
+/// and counts the arrangements that are solutions,
+/// which estimates the number of solutions without visiting every arrangement.
+///
+/// The presentation options, `--include-redundant-solutions`
+/// and the limit on the number of solutions are ignored.
+/// The sampling stops early if it is interrupted.
+pub fn simulate(
+    parameters: &Parameters,
+    samples: NonZeroUsize,
+    rng: &mut impl Rng,
+) -> Result<Simulation, Error> {
+    let number_of_seats = parameters.number_of_seats.get();
+    let mut seats = fixed_seats(number_of_seats, fixes(parameters))?;
+    let mut free_investors: Vec<_> = (1..=number_of_seats)
+        .filter(|investor| !seats.contains(investor))
+        .collect();
+    let free_seat_indices: Vec<_> = (0..number_of_seats)
+        .filter(|&seat_index| seats[seat_index] == 0)
+        .collect();
+
+    parameters.progress.start(samples.get() as u64);
+    let mut simulation = Simulation {
+        samples: 0,
+        solutions: 0,
+        arrangements: factorial(free_investors.len()),
+    };
+    while simulation.samples < samples.get() && !parameters.interrupt.is_triggered() {
+        free_investors.shuffle(rng);
+        for (&seat_index, &investor) in free_seat_indices.iter().zip(&free_investors) {
+            seats[seat_index] = investor;
+        }
+
+        if is_valid_solution(&seats) {
+            simulation.solutions += 1;
+        }
+
+        simulation.samples += 1;
+        parameters.progress.advance(1);
+    }
+
+    debug!(
+        samples = simulation.samples,
+        solutions = simulation.solutions,
+        "simulation finished"
+    );
+    Ok(simulation)
+}
+
+/// Determines whether the given arrangement of investors is a valid solution.
+///
+/// An arrangement is valid if,
+/// for all rotations of the arrangement,
+/// there are not two or more investors in the correct seat.
+fn is_valid_solution(seats: &[usize]) -> bool {
+    (0..seats.len()).all(|rotation| number_of_correctly_seated_investors(seats, rotation) < 2)
+}
+
+/// Returns the number of correctly seated investors
+/// in the given arrangement of investors
+/// after rotating the table by `rotation` seats to the right.
+fn number_of_correctly_seated_investors(seats: &[usize], rotation: usize) -> usize {
+    // Generate the sequence of seat numbers
+    // under each investor after the rotation
+    (1..=seats.len())
+        .cycle()
+        .skip(rotation)
+        // Zip it with the sequence of investor numbers
+        .zip(seats.iter().cloned())
+        // Select only the items where the two numbers are equal,
+        // which indicates a correctly seated investor
+        .filter(|(seat_number, investor_number)| seat_number == investor_number)
+        // Count them
+        .count()
+}
+
+/// Parameters for solving variants of the spinning table puzzle.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Parameters {
+    /// The number of seats at the table.
+    #[cfg_attr(feature = "clap", arg(short = 'n', long, default_value = "7", value_parser = non_zero_usize()))]
+    number_of_seats: NonZeroUsize,
+
+    /// If set, redundant solutions are included in the result.
+    ///
+    /// The redundant solutions are the rotations of each solution,
+    /// which move every investor, so they can't be combined with `--fix`.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with = "fix"))]
+    include_redundant_solutions: bool,
+
+    /// Investors to pin to specific seats,
+    /// as a comma-separated list of `investor=seat` pairs (e.g. `1=1,3=5`).
+    ///
+    /// The remaining investors are permuted over the remaining seats.
+    /// Defaults to `1=1`.
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ','))]
+    fix: Vec<Fix>,
+
+    /// If set, no investor is pinned to a seat
+    /// and all the arrangements of the investors are tried,
+    /// which includes the redundant solutions.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with_all = ["fix", "include_redundant_solutions"]))]
+    no_anchor: bool,
+
+    /// Searches only the `i`th of `n` parts of the search (e.g. `2/4`),
+    /// so that the search can be split across machines.
+    ///
+    /// The parts are split by the investor in the last free seat,
+    /// which is the choice that the search changes least often.
+    /// The solutions of the `n` parts together are the solutions of the whole search.
+    #[cfg_attr(feature = "clap", arg(long))]
+    shard: Option<Shard>,
+
+    /// Names of the investors,
+    /// as a comma-separated list (e.g. `Alice,Bob,Carol`).
+    ///
+    /// If set, solutions show the investors' names instead of their numbers.
+    /// There must be exactly one name per seat.
+    #[cfg_attr(feature = "clap", arg(long, value_delimiter = ','))]
+    names: Vec<String>,
+
+    /// How the solutions will be presented.
+    ///
+    /// `dot` emits one GraphViz graph per solution,
+    /// with an edge from each investor's seat to their correct seat.
+    /// Use `--quiet` to omit the label that precedes the graphs.
+    #[cfg_attr(feature = "clap", arg(long, value_enum, ignore_case = true, default_value_t = OutputFormat::List))]
+    output: OutputFormat,
+
+    /// If set, each solution is printed as soon as it is found
+    /// instead of after the search,
+    /// and the number of solutions is printed last.
+    ///
+    /// The solutions are printed in the order in which they are found,
+    /// which is the same order as without this flag.
+    #[cfg_attr(feature = "clap", arg(long))]
+    stream: bool,
+
+    /// If set, the solutions are presented in a random order.
+    ///
+    /// All the solutions are still found before they are shuffled,
+    /// so the ones kept by `--max-solutions` are a random sample.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with = "stream"))]
+    shuffle: bool,
+
+    /// The seed for the random order of `--shuffle`,
+    /// to get the same order on every run.
+    ///
+    /// Defaults to a different seed on every run.
+    #[cfg_attr(feature = "clap", arg(long, requires = "shuffle"))]
+    seed: Option<u64>,
+
+    /// Stops the search early.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    interrupt: Interrupt,
+
+    /// Reports how far the search has gone.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    progress: Progress,
+
+    /// The number of solutions after which the search stops.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    max_solutions: Option<NonZeroUsize>,
+
+    /// Whether the investors in their correct seat are highlighted in the list output.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    color: bool,
+
+    /// Whether each solution is written as a line of JSON, as soon as it is found.
+    #[cfg_attr(feature = "clap", arg(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ndjson: bool,
+
+    /// A file in which the state of the search is saved
+    /// every 30 seconds and when the search stops,
+    /// so that it can be resumed with `--resume`.
+    ///
+    /// Only the spinning table search can be saved:
+    /// the Scrabble® search runs its branches on several threads at once,
+    /// so it has no single position to resume from.
+    /// A long Scrabble® search can be split with its `--shard` instead.
+    #[cfg_attr(feature = "clap", arg(long))]
+    checkpoint: Option<PathBuf>,
+
+    /// A file saved by `--checkpoint` from which to resume the search.
+    ///
+    /// The options that change the search must be the same as when it was saved.
+    /// It can be the same file as `--checkpoint`.
+    /// The solutions that were streamed before the checkpoint aren't printed again.
+    #[cfg_attr(feature = "clap", arg(long))]
+    resume: Option<PathBuf>,
+}
+
+/// Returns the defaults of the command line.
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            number_of_seats: NonZeroUsize::new(7).unwrap(),
+            include_redundant_solutions: false,
+            fix: vec![],
+            no_anchor: false,
+            shard: None,
+            names: vec![],
+            output: OutputFormat::List,
+            stream: false,
+            shuffle: false,
+            seed: None,
+            interrupt: Interrupt::default(),
+            progress: Progress::default(),
+            max_solutions: None,
+            color: false,
+            ndjson: false,
+            checkpoint: None,
+            resume: None,
+        }
+    }
+}
+
+impl Parameters {
+    /// Starts building parameters from the defaults of the command line.
+    pub fn builder() -> ParametersBuilder {
+        ParametersBuilder {
+            parameters: Parameters::default(),
+        }
+    }
+
+    /// Returns the number of seats at the table.
+    pub fn number_of_seats(&self) -> usize {
+        self.number_of_seats.get()
+    }
+
+    /// Sets the number of solutions after which the search stops,
+    /// or removes the limit.
+    ///
+    /// This is the global `--max-solutions` option of the command line.
+    pub fn set_max_solutions(&mut self, max_solutions: Option<NonZeroUsize>) {
+        self.max_solutions = max_solutions;
+    }
+
+    /// Sets whether the investors in their correct seat are highlighted in the list output.
+    ///
+    /// This is the global `--color` option of the command line.
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
+    /// Sets whether each solution is written as a line of JSON, as soon as it is found.
+    ///
+    /// This is the global `--format ndjson` option of the command line.
+    /// The solutions are only written at the end with `--shuffle`.
+    pub fn set_ndjson(&mut self, ndjson: bool) {
+        self.ndjson = ndjson;
+    }
+
+    /// Sets the flag that stops the search early,
+    /// such as the one that the command line sets when Ctrl-C is pressed.
+    pub fn set_interrupt(&mut self, interrupt: Interrupt) {
+        self.interrupt = interrupt;
+    }
+
+    /// Sets the counters that report how far the search has gone,
+    /// which `--progress` and `--stats` read on the command line.
+    pub fn set_progress(&mut self, progress: Progress) {
+        self.progress = progress;
+    }
+}
+
+/// Builds `Parameters` without going through the command line.
+///
+/// Each method corresponds to the command-line option with the same name.
+#[derive(Debug)]
+pub struct ParametersBuilder {
+    /// The parameters built so far.
+    parameters: Parameters,
+}
+
+impl ParametersBuilder {
+    /// Sets the number of seats at the table.
+    pub fn number_of_seats(mut self, number_of_seats: NonZeroUsize) -> Self {
+        self.parameters.number_of_seats = number_of_seats;
+        self
+    }
+
+    /// Sets the number of solutions after which the search stops.
+    pub fn max_solutions(mut self, max_solutions: NonZeroUsize) -> Self {
+        self.parameters.max_solutions = Some(max_solutions);
+        self
+    }
+
+    /// Sets whether the investors in their correct seat are highlighted.
+    pub fn color(mut self, color: bool) -> Self {
+        self.parameters.color = color;
+        self
+    }
+
+    /// Sets whether each solution is written as a line of JSON.
+    pub fn ndjson(mut self, ndjson: bool) -> Self {
+        self.parameters.ndjson = ndjson;
+        self
+    }
+
+    /// Sets the file in which the state of the search is saved.
+    pub fn checkpoint(mut self, checkpoint: impl Into<PathBuf>) -> Self {
+        self.parameters.checkpoint = Some(checkpoint.into());
+        self
+    }
+
+    /// Sets the file from which to resume the search.
+    pub fn resume(mut self, resume: impl Into<PathBuf>) -> Self {
+        self.parameters.resume = Some(resume.into());
+        self
+    }
+
+    /// Sets whether redundant solutions are included in the result.
+    pub fn include_redundant_solutions(mut self, include_redundant_solutions: bool) -> Self {
+        self.parameters.include_redundant_solutions = include_redundant_solutions;
+        self
+    }
+
+    /// Sets the investors to pin to specific seats.
+    pub fn fix(mut self, fix: impl IntoIterator<Item = Fix>) -> Self {
+        self.parameters.fix = fix.into_iter().collect();
+        self
+    }
+
+    /// Sets whether to try all the arrangements without pinning any investor.
+    pub fn no_anchor(mut self, no_anchor: bool) -> Self {
+        self.parameters.no_anchor = no_anchor;
+        self
+    }
+
+    /// Sets the part of the search to search.
+    pub fn shard(mut self, shard: Shard) -> Self {
+        self.parameters.shard = Some(shard);
+        self
+    }
+
+    /// Sets the names of the investors.
+    pub fn names(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.parameters.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets how the solutions will be presented.
+    pub fn output(mut self, output: OutputFormat) -> Self {
+        self.parameters.output = output;
+        self
+    }
+
+    /// Sets whether each solution is printed as soon as it is found.
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.parameters.stream = stream;
+        self
+    }
+
+    /// Sets whether the solutions are presented in a random order.
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.parameters.shuffle = shuffle;
+        self
+    }
+
+    /// Sets the seed for the random order of `shuffle`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.parameters.seed = Some(seed);
+        self
+    }
+
+    /// Checks that the options can be used together
+    /// and returns the parameters.
+    pub fn build(self) -> Result<Parameters, BuildError> {
+        let parameters = self.parameters;
+        if parameters.no_anchor {
+            if !parameters.fix.is_empty() {
+                return Err(BuildError::Conflict("no_anchor", "fix"));
+            }
+
+            if parameters.include_redundant_solutions {
+                return Err(BuildError::Conflict(
+                    "no_anchor",
+                    "include_redundant_solutions",
+                ));
+            }
+        }
+
+        if parameters.include_redundant_solutions && !parameters.fix.is_empty() {
+            return Err(BuildError::Conflict("include_redundant_solutions", "fix"));
+        }
+
+        if parameters.shuffle && parameters.stream {
+            return Err(BuildError::Conflict("shuffle", "stream"));
+        }
+
+        if parameters.seed.is_some() && !parameters.shuffle {
+            return Err(BuildError::MissingRequirement("seed", "shuffle"));
+        }
+
+        Ok(parameters)
+    }
+}
+
+/// Choices for how the solutions should be presented.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OutputFormat {
+    /// List the arrangements of investor numbers.
+    List,
+
+    /// Emit one GraphViz graph per solution.
+    Dot,
+}
+
+/// The solutions to the spinning table puzzle, along with how to present them.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Output {
+    /// The arrangements of investor numbers, by seat.
+    ///
+    /// This is empty if the solutions were streamed.
+    pub solutions: Vec<Vec<usize>>,
+
+    /// The number of solutions that were found.
+    pub number_of_solutions: usize,
+
+    /// Whether the solutions were printed as soon as they were found.
+    pub streamed: bool,
+
+    /// How the solutions are presented.
+    presentation: Presentation,
+}
+
+impl Output {
+    /// Returns the solutions,
+    /// to be displayed with the investors' names if they were given.
+    pub fn arrangements(&self) -> impl Iterator<Item = SeatArrangement<'_>> {
+        self.solutions.iter().map(move |seats| SeatArrangement {
+            seats,
+            names: &self.presentation.names,
+            color: self.presentation.color,
+        })
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        for (index, solution) in self.solutions.iter().enumerate() {
+            if index != 0 {
+                writeln!(fmt)?;
+            }
+
+            self.presentation.write_solution(fmt, index, solution)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How the solutions to the spinning table puzzle are presented.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Presentation {
+    /// The names of the investors, by investor number,
+    /// or empty to show the investor numbers.
+    names: Vec<String>,
+
+    /// The format of the solutions.
+    format: OutputFormat,
+
+    /// Whether the investors in their correct seat are highlighted.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    color: bool,
+
+    /// Whether each solution is written as a line of JSON instead of in `format`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ndjson: bool,
+}
+
+impl Presentation {
+    /// Writes the solution at the given index in the sequence of solutions.
+    fn write_solution(
+        &self,
+        fmt: &mut impl fmt::Write,
+        index: usize,
+        solution: &[usize],
+    ) -> fmt::Result {
+        if self.ndjson {
+            let seats = if self.names.is_empty() {
+                json!(solution)
+            } else {
+                json!(solution
+                    .iter()
+                    .map(|&investor| &self.names[investor - 1])
+                    .collect::<Vec<_>>())
+            };
+            return write!(fmt, "{}", json!({ "seats": seats }));
+        }
+
+        match self.format {
+            OutputFormat::List => write!(
+                fmt,
+                "{}",
+                SeatArrangement {
+                    seats: solution,
+                    names: &self.names,
+                    color: self.color,
+                }
+            ),
+
+            OutputFormat::Dot => {
+                // Each seat is a node.
+                // Following the edges from an investor's seat to their correct seat
+                // traces the cycles of the permutation.
+                writeln!(fmt, "digraph solution_{} {{", index + 1)?;
+                for seat in 1..=solution.len() {
+                    writeln!(fmt, "    seat_{} [label=\"{}\"];", seat, seat)?;
+                }
+
+                for (seat, &investor) in (1..).zip(solution.iter()) {
+                    // The label is a quoted string, in which quotes and backslashes are escaped.
+                    let mut label = String::new();
+                    write_investor(&mut label, &self.names, investor)?;
+                    let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+                    writeln!(
+                        fmt,
+                        "    seat_{} -> seat_{} [label=\"{}\"];",
+                        seat, investor, label
+                    )?;
+                }
+                write!(fmt, "}}")
+            }
+        }
+    }
+}
+
+/// Writes the name of an investor, or their number if no names were given.
+fn write_investor(fmt: &mut impl fmt::Write, names: &[String], investor: usize) -> fmt::Result {
+    if names.is_empty() {
+        write!(fmt, "{}", investor)
+    } else {
+        write!(fmt, "{}", names[investor - 1])
+    }
+}
+
+/// An arrangement of the investors around the table,
+/// displayed as the list of investors by seat.
+#[derive(Clone, Copy, Debug)]
+pub struct SeatArrangement<'a> {
+    /// The investor numbers, by seat.
+    seats: &'a [usize],
+
+    /// The names of the investors, by investor number,
+    /// or empty to show the investor numbers.
+    names: &'a [String],
+
+    /// Whether the investors in their correct seat are highlighted.
+    color: bool,
+}
+
+impl<'a> SeatArrangement<'a> {
+    /// Wraps an arrangement of investor numbers, by seat, for display.
+    pub fn new(seats: &'a [usize]) -> Self {
+        Self {
+            seats,
+            names: &[],
+            color: false,
+        }
+    }
+
+    /// Returns the investors by seat,
+    /// each rendered as their name if names were given or as their number otherwise.
+    pub fn investors(&self) -> impl Iterator<Item = String> + 'a {
+        let names = self.names;
+        self.seats.iter().map(move |&investor| {
+            let mut rendered = String::new();
+            write_investor(&mut rendered, names, investor)
+                .expect("writing to a String cannot fail");
+            rendered
+        })
+    }
+}
+
+impl Display for SeatArrangement<'_> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "[")?;
+        for (seat_index, &investor) in self.seats.iter().enumerate() {
+            if seat_index != 0 {
+                write!(fmt, ", ")?;
+            }
+
+            let mut rendered = String::new();
+            write_investor(&mut rendered, self.names, investor)?;
+            let correctly_seated = self.color && investor == seat_index + 1;
+            write!(
+                fmt,
+                "{}",
+                color::paint(correctly_seated.then_some(Color::Green), rendered)
+            )?;
+        }
+        write!(fmt, "]")
+    }
+}
+
+/// Parameters for counting the solutions to the spinning table puzzle
+/// up to rotation and reflection.
+#[derive(Debug)]
+#[cfg_attr(feature = "clap", derive(Parser))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DihedralCountParameters {
+    /// The largest number of seats at the table.
+    #[cfg_attr(feature = "clap", arg(short = 'n', long, default_value = "7", value_parser = non_zero_usize()))]
+    number_of_seats: NonZeroUsize,
+}
+
+/// Returns the defaults of the command line.
+impl Default for DihedralCountParameters {
+    fn default() -> Self {
+        Self {
+            number_of_seats: NonZeroUsize::new(7).unwrap(),
+        }
+    }
+}
 
-    let mut solutions = vec![];
+/// The number of solutions to the spinning table puzzle
+/// for each number of seats.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DihedralCounts {
+    /// The counts for each number of seats, in increasing order.
+    rows: Vec<DihedralCountRow>,
+}
 
-    // Build a vector with investor numbers 2 up to and including n,
-    // where n is the number of seats.
-    // This is the vector in which permutations will occur.
-    let mut seats_after_first: Vec<_> = (2..=parameters.number_of_seats.get()).collect();
+/// The number of solutions to the spinning table puzzle
+/// for a number of seats.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct DihedralCountRow {
+    /// The number of seats at the table.
+    number_of_seats: usize,
 
-    // Allocate another vector that will be used
-    // in the closure to store all investor numbers, including 1.
-    // (We could allocate one on each call of the closure,
-    // but that would be more costly.)
-    let mut seats = vec![0; parameters.number_of_seats.get()];
+    /// The number of solutions, up to rotation.
+    rotation_canonical: usize,
 
-    permutohedron::heap_recursive(&mut seats_after_first, |seats_after_first| {
-        // Initialize the full sequence of seats.
-        // The first seat is always occupied by investor number 1.
-        // (`is_valid_solution` rotates `seats` in place
-        // and may leave investor number 1 in another seat.)
-        seats[0] = 1;
-        seats[1..].copy_from_slice(seats_after_first);
+    /// The number of solutions, including all rotations.
+    rotation_redundant: usize,
 
-        if is_valid_solution(&mut seats) {
-            solutions.push(seats.clone());
+    /// The number of solutions, up to rotation and reflection.
+    dihedral_canonical: usize,
 
-            if parameters.include_redundant_solutions {
-                // The redundant solutions
-                // are simply the distinct rotations of the initial solution.
-                for _ in 1..seats.len() {
-                    seats.rotate_right(1);
-                    solutions.push(seats.clone());
-                }
-            }
+    /// The number of solutions, including all rotations and reflections.
+    dihedral_redundant: usize,
+}
+
+impl Display for DihedralCounts {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{:>5} {:>10} {:>10} {:>10} {:>10}",
+            "seats", "rotation", "(all)", "dihedral", "(all)"
+        )?;
+        for row in &self.rows {
+            writeln!(fmt)?;
+            write!(
+                fmt,
+                "{:>5} {:>10} {:>10} {:>10} {:>10}",
+                row.number_of_seats,
+                row.rotation_canonical,
+                row.rotation_redundant,
+                row.dihedral_canonical,
+                row.dihedral_redundant
+            )?;
         }
-    });
 
-    solutions
+        Ok(())
+    }
 }
 
-/// Determines whether the given arrangement of investors is a valid solution.
-///
-/// An arrangement is valid if,
-/// for all rotations of the arrangement,
-/// there are not two or more investors in the correct seat.
-fn is_valid_solution(seats: &mut [usize]) -> bool {
-    for _ in 0..seats.len() {
-        if number_of_correctly_seated_investors(seats) >= 2 {
-            return false;
-        }
+/// The result of seating the investors at random with `simulate`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Simulation {
+    /// The number of random arrangements that were drawn.
+    samples: usize,
+
+    /// The number of those arrangements that are solutions.
+    solutions: usize,
+
+    /// The number of arrangements of the investors that are not fixed,
+    /// or `u64::MAX` if there are more than that.
+    arrangements: u64,
+}
 
-        // Rotate the seats by 1 for the next iteration.
-        // It is important to rotate on the last iteration as well,
-        // because if this is a valid solution,
-        // we should return the seats to their initial arrangement.
-        seats.rotate_right(1);
+impl Simulation {
+    /// Returns the fraction of the random arrangements that are solutions.
+    pub fn frequency(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.solutions as f64 / self.samples as f64
+        }
     }
 
-    true
+    /// Returns the number of solutions that the frequency predicts
+    /// among all the arrangements.
+    ///
+    /// Without `--include-redundant-solutions`, this is comparable to the solution count.
+    pub fn estimated_solutions(&self) -> f64 {
+        self.frequency() * self.arrangements as f64
+    }
 }
 
-/// Returns the number of correctly seated investors
-/// in the given arrangement of investors.
-fn number_of_correctly_seated_investors(seats: &[usize]) -> usize {
-    // Generate the sequence of seat numbers
-    (1usize..)
-        // Zip it with the sequence of investor numbers
-        .zip(seats.iter().cloned())
-        // Select only the items where the two numbers are equal,
-        // which indicates a correctly seated investor
-        .filter(|(seat_number, investor_number)| seat_number == investor_number)
-        // Count them
-        .count()
+impl Display for Simulation {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "samples: {}", self.samples)?;
+        writeln!(
+            fmt,
+            "solutions: {} ({:.2}%)",
+            self.solutions,
+            100.0 * self.frequency()
+        )?;
+        write!(
+            fmt,
+            "estimated number of solutions: {:.1} of {} arrangements",
+            self.estimated_solutions(),
+            self.arrangements
+        )
+    }
 }
 
-/// Parameters for solving variants of the spinning table puzzle.
-#[derive(StructOpt)]
-pub struct Parameters {
-    /// The number of seats at the table.
-    #[structopt(short = "n", long, default_value = "7")]
-    number_of_seats: NonZeroUsize,
+/// The state of a spinning table search saved by `--checkpoint`,
+/// along with the solutions found before it.
+#[derive(Debug, PartialEq)]
+struct Checkpoint {
+    /// The investor in each fixed seat, or 0 for the seats that aren't fixed.
+    fixed_seats: Vec<usize>,
 
-    /// If set, redundant solutions are included in the result.
-    #[structopt(long)]
+    /// Whether the rotations of each solution are included.
     include_redundant_solutions: bool,
+
+    /// The part of the search that is searched, if it is split.
+    shard: Option<Shard>,
+
+    /// The state of `Solutions::free_investors`.
+    free_investors: Vec<usize>,
+
+    /// The state of `Solutions::counters`.
+    counters: Vec<usize>,
+
+    /// The state of `Solutions::level`.
+    level: Option<usize>,
+
+    /// The number of permutations visited so far.
+    permutations: u64,
+
+    /// The number of permutations that were solutions, not counting the rotations.
+    valid_permutations: u64,
+
+    /// The state of `Solutions::rotations`,
+    /// when the search stopped before all the rotations of a solution were produced.
+    rotations: Option<(Vec<usize>, usize)>,
+
+    /// The solutions found so far, unless they were streamed.
+    solutions: Vec<Vec<usize>>,
+
+    /// The number of solutions found so far.
+    number_of_solutions: usize,
+}
+
+impl Checkpoint {
+    /// Reads a checkpoint from a file.
+    fn load(path: &Path) -> Result<Self, CheckpointError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| CheckpointError::Io(path.to_owned(), err))?;
+        serde_json::from_str(&contents)
+            .ok()
+            .as_ref()
+            .and_then(Self::from_json)
+            .ok_or_else(|| CheckpointError::Invalid(path.to_owned()))
+    }
+
+    /// Writes the checkpoint to a file.
+    fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        // Write to a temporary file first,
+        // so that the previous checkpoint survives if the program stops while saving.
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        fs::write(&temporary_path, self.to_json().to_string())
+            .and_then(|()| fs::rename(&temporary_path, path))
+            .map_err(|err| CheckpointError::Io(path.to_owned(), err))?;
+        debug!(permutations = self.permutations, path = ?path, "saved a checkpoint");
+        Ok(())
+    }
+
+    /// Converts the checkpoint to the JSON document that is saved.
+    fn to_json(&self) -> Value {
+        json!({
+            "puzzle": SpinningTable::NAME,
+            "fixed_seats": self.fixed_seats,
+            "include_redundant_solutions": self.include_redundant_solutions,
+            "shard": self.shard.map(|shard| shard.to_string()),
+            "free_investors": self.free_investors,
+            "counters": self.counters,
+            "level": self.level,
+            "permutations": self.permutations,
+            "valid_permutations": self.valid_permutations,
+            "rotations": self.rotations.as_ref().map(|(seats, rotation)| {
+                json!({ "seats": seats, "rotation": rotation })
+            }),
+            "solutions": self.solutions,
+            "number_of_solutions": self.number_of_solutions,
+        })
+    }
+
+    /// Reads a checkpoint from a JSON document saved by `save`,
+    /// or returns `None` if it isn't one.
+    fn from_json(document: &Value) -> Option<Self> {
+        let numbers = |value: &Value| -> Option<Vec<usize>> {
+            value
+                .as_array()?
+                .iter()
+                .map(|number| Some(number.as_u64()? as usize))
+                .collect()
+        };
+
+        if document.get("puzzle")?.as_str()? != SpinningTable::NAME {
+            return None;
+        }
+
+        Some(Self {
+            fixed_seats: numbers(document.get("fixed_seats")?)?,
+            include_redundant_solutions: document.get("include_redundant_solutions")?.as_bool()?,
+            // The checkpoints saved before `--shard` existed don't have it.
+            shard: match document.get("shard") {
+                None | Some(Value::Null) => None,
+                Some(shard) => Some(shard.as_str()?.parse().ok()?),
+            },
+            free_investors: numbers(document.get("free_investors")?)?,
+            counters: numbers(document.get("counters")?)?,
+            level: match document.get("level")? {
+                Value::Null => None,
+                level => Some(level.as_u64()? as usize),
+            },
+            permutations: document.get("permutations")?.as_u64()?,
+            valid_permutations: document.get("valid_permutations")?.as_u64()?,
+            // The checkpoints saved before the rotations were saved don't have them.
+            rotations: match document.get("rotations") {
+                None | Some(Value::Null) => None,
+                Some(rotations) => Some((
+                    numbers(rotations.get("seats")?)?,
+                    rotations.get("rotation")?.as_u64()? as usize,
+                )),
+            },
+            solutions: document
+                .get("solutions")?
+                .as_array()?
+                .iter()
+                .map(numbers)
+                .collect::<Option<_>>()?,
+            number_of_solutions: document.get("number_of_solutions")?.as_u64()? as usize,
+        })
+    }
+}
+
+/// An investor pinned to a specific seat.
+#[derive(Clone, Copy, Debug)]
+pub struct Fix {
+    /// The investor number.
+    investor: usize,
+
+    /// The seat number.
+    seat: usize,
+}
+
+impl FromStr for Fix {
+    type Err = FixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(investor), Some(seat)) => Ok(Self {
+                investor: investor.trim().parse()?,
+                seat: seat.trim().parse()?,
+            }),
+            _ => Err(FixError::MissingSeparator),
+        }
+    }
+}
+
+impl Display for Fix {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}={}", self.investor, self.seat)
+    }
+}
+
+/// Serializes the pin like it is given on the command line.
+#[cfg(feature = "serde")]
+impl Serialize for Fix {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when pinning or naming investors.
+    #[derive(Debug)]
+    pub enum FixError {
+        /// An investor or seat number is not a valid number.
+        ParseIntError(err: ParseIntError) {
+            cause(err)
+            display("{}", err)
+            from()
+        }
+
+        /// The `=` between the investor number and the seat number is missing.
+        MissingSeparator {
+            display("expected `investor=seat`")
+        }
+
+        /// The investor number is not between 1 and the number of seats.
+        InvestorOutOfRange(investor: usize) {
+            display("there is no investor {} at the table", investor)
+        }
+
+        /// The seat number is not between 1 and the number of seats.
+        SeatOutOfRange(seat: usize) {
+            display("there is no seat {} at the table", seat)
+        }
+
+        /// The same investor is pinned more than once.
+        InvestorFixedTwice(investor: usize) {
+            display("investor {} is pinned more than once", investor)
+        }
+
+        /// More than one investor is pinned to the same seat.
+        SeatFixedTwice(seat: usize) {
+            display("more than one investor is pinned to seat {}", seat)
+        }
+
+        /// The number of investor names doesn't match the number of seats.
+        WrongNumberOfNames(names: usize, seats: usize) {
+            display("{} names were given for {} seats", names, seats)
+        }
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when saving or resuming from a checkpoint.
+    #[derive(Debug)]
+    pub enum CheckpointError {
+        /// The checkpoint file could not be read or written.
+        Io(path: PathBuf, err: io::Error) {
+            cause(err)
+            display("could not access the checkpoint {}: {}", path.display(), err)
+        }
+
+        /// The file is not a spinning table checkpoint.
+        Invalid(path: PathBuf) {
+            display("{} is not a spinning table checkpoint", path.display())
+        }
+
+        /// The checkpoint was saved for a different search.
+        Mismatch {
+            display("the checkpoint was saved with different options")
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::rngs::StdRng;
+
     use super::*;
 
+    /// Returns the parameters of the command line without any options,
+    /// to be changed with the struct update syntax.
+    fn default_parameters() -> Parameters {
+        Parameters::builder().build().unwrap()
+    }
+
     #[test]
     fn without_redundant_solutions() {
-        let result = super::spinning_table(Parameters {
-            number_of_seats: NonZeroUsize::new(7).unwrap(),
-            include_redundant_solutions: false,
-        });
+        let result = super::spinning_table(default_parameters())
+            .unwrap()
+            .solutions;
 
         assert_eq!(
             result,
@@ -138,12 +1734,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validity_check_leaves_seats_unchanged() {
+        let valid = [1, 4, 7, 5, 3, 2, 6];
+        let invalid = [1, 2, 3, 4, 5, 6, 7];
+        let mut seats = valid;
+        assert!(is_valid_solution(&seats));
+        assert_eq!(seats, valid);
+
+        seats = invalid;
+        assert!(!is_valid_solution(&seats));
+        assert_eq!(seats, invalid);
+
+        // Each investor is seated correctly in exactly one of the 7 rotations,
+        // so every rotation of a valid solution seats exactly one investor correctly.
+        for rotation in 0..7 {
+            assert_eq!(number_of_correctly_seated_investors(&valid, rotation), 1);
+        }
+    }
+
     #[test]
     fn with_redundant_solutions() {
         let result = super::spinning_table(Parameters {
-            number_of_seats: NonZeroUsize::new(7).unwrap(),
             include_redundant_solutions: true,
-        });
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
 
         assert_eq!(result.len(), 133);
     }
@@ -152,10 +1769,493 @@ mod tests {
     fn with_even_number_of_seats() {
         let result = super::spinning_table(Parameters {
             number_of_seats: NonZeroUsize::new(8).unwrap(),
-            include_redundant_solutions: false,
-        });
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
 
         // There are no solutions for an even number of seats.
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn without_anchor() {
+        let mut result = super::spinning_table(Parameters {
+            no_anchor: true,
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
+
+        let mut redundant = super::spinning_table(Parameters {
+            include_redundant_solutions: true,
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
+
+        assert_eq!(result.len(), 133);
+        result.sort();
+        redundant.sort();
+        assert_eq!(result, redundant);
+    }
+
+    #[test]
+    fn with_first_investor_fixed() {
+        let default = super::spinning_table(default_parameters())
+            .unwrap()
+            .solutions;
+
+        let result = super::spinning_table(Parameters {
+            fix: vec!["1=1".parse().unwrap()],
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
+
+        assert_eq!(result.len(), default.len());
+    }
+
+    #[test]
+    fn with_several_investors_fixed() {
+        let result = super::spinning_table(Parameters {
+            fix: vec!["1=1".parse().unwrap(), "3=5".parse().unwrap()],
+            ..default_parameters()
+        })
+        .unwrap()
+        .solutions;
+
+        assert!(!result.is_empty());
+        for solution in result {
+            assert_eq!(solution[0], 1);
+            assert_eq!(solution[4], 3);
+        }
+    }
+
+    #[test]
+    fn with_contradictory_fixes() {
+        let result = super::spinning_table(Parameters {
+            fix: vec!["1=1".parse().unwrap(), "2=1".parse().unwrap()],
+            ..default_parameters()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::Fix(FixError::SeatFixedTwice(1)))
+        ));
+
+        let result = super::spinning_table(Parameters {
+            fix: vec!["1=1".parse().unwrap(), "1=2".parse().unwrap()],
+            ..default_parameters()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::Fix(FixError::InvestorFixedTwice(1)))
+        ));
+    }
+
+    #[test]
+    fn with_names() {
+        let names = ["Alice", "Bob", "Carol", "Dave", "Eve", "Frank", "Grace"];
+        let result = super::spinning_table(Parameters {
+            names: names.iter().map(|&name| name.to_owned()).collect(),
+            ..default_parameters()
+        })
+        .unwrap();
+
+        assert_eq!(result.solutions[0], [1, 4, 7, 5, 3, 2, 6]);
+        assert_eq!(
+            result.to_string().lines().next(),
+            Some("[Alice, Dave, Grace, Eve, Carol, Bob, Frank]"),
+        );
+    }
+
+    #[test]
+    fn seat_arrangements() {
+        let names = ["Alice", "Bob", "Carol", "Dave", "Eve", "Frank", "Grace"];
+        let result = super::spinning_table(
+            Parameters::builder()
+                .names(names.iter().copied())
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let first = result.arrangements().next().unwrap();
+        assert_eq!(
+            first.to_string(),
+            "[Alice, Dave, Grace, Eve, Carol, Bob, Frank]"
+        );
+        assert_eq!(
+            SeatArrangement::new(&result.solutions[0]).to_string(),
+            "[1, 4, 7, 5, 3, 2, 6]"
+        );
+    }
+
+    #[test]
+    fn with_wrong_number_of_names() {
+        let result = super::spinning_table(Parameters {
+            names: vec!["Alice".to_owned(), "Bob".to_owned()],
+            ..default_parameters()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::Fix(FixError::WrongNumberOfNames(2, 7)))
+        ));
+    }
+
+    #[test]
+    fn dot_output() {
+        let result = super::spinning_table(Parameters {
+            output: OutputFormat::Dot,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        let dot = result.to_string();
+        let first_graph = &dot[..dot.find("\ndigraph").unwrap()];
+        assert!(first_graph.starts_with("digraph solution_1 {\n"));
+        assert!(first_graph.ends_with("}"));
+        assert_eq!(first_graph.matches("[label=").count(), 14);
+        assert_eq!(first_graph.matches(" -> ").count(), 7);
+        // The first solution is [1, 4, 7, 5, 3, 2, 6].
+        assert!(first_graph.contains("    seat_2 -> seat_4 [label=\"4\"];\n"));
+        assert_eq!(dot.matches("digraph").count(), result.solutions.len());
+    }
+
+    #[test]
+    fn dot_output_escapes_names() {
+        let result = super::spinning_table(Parameters {
+            number_of_seats: NonZeroUsize::new(3).unwrap(),
+            names: vec![
+                "Ada \"the Countess\" Lovelace".to_owned(),
+                "C:\\Bob".to_owned(),
+                "Carol".to_owned(),
+            ],
+            output: OutputFormat::Dot,
+            ..default_parameters()
+        })
+        .unwrap();
+
+        let dot = result.to_string();
+        assert!(dot.contains("[label=\"Ada \\\"the Countess\\\" Lovelace\"];\n"));
+        assert!(dot.contains("[label=\"C:\\\\Bob\"];\n"));
+    }
+
+    #[test]
+    fn shuffled() {
+        let shuffled = |seed| {
+            super::spinning_table(Parameters {
+                shuffle: true,
+                seed: Some(seed),
+                ..default_parameters()
+            })
+            .unwrap()
+            .solutions
+        };
+
+        let mut first = shuffled(1);
+        let mut second = shuffled(2);
+        assert_ne!(first[..5], second[..5]);
+        assert_eq!(first, shuffled(1));
+
+        first.sort();
+        second.sort();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 19);
+    }
+
+    #[test]
+    fn shuffled_sample() {
+        let sample = |seed| {
+            let parameters = Parameters::builder()
+                .shuffle(true)
+                .seed(seed)
+                .max_solutions(NonZeroUsize::new(3).unwrap())
+                .build()
+                .unwrap();
+            let output = super::spinning_table(parameters).unwrap();
+            assert_eq!(output.number_of_solutions, 3);
+
+            let mut solutions = output.solutions;
+            solutions.sort();
+            solutions
+        };
+
+        let all: Vec<_> = super::iter_solutions(&Parameters::builder().build().unwrap())
+            .unwrap()
+            .collect();
+        let first = sample(1);
+        let second = sample(2);
+        assert_ne!(first, second);
+        assert!(first
+            .iter()
+            .chain(&second)
+            .all(|solution| all.contains(solution)));
+    }
+
+    #[test]
+    fn permutations_match_heap_recursive() {
+        for number_of_investors in 0..=6 {
+            let investors: Vec<_> = (1..=number_of_investors).collect();
+
+            let mut expected = vec![];
+            permutohedron::heap_recursive(&mut investors.clone(), |permutation| {
+                expected.push(permutation.to_vec())
+            });
+
+            let mut solutions = Solutions {
+                counters: vec![0; number_of_investors],
+                level: None,
+                free_investors: investors,
+                free_seat_indices: vec![],
+                seats: vec![],
+                rotations: None,
+                include_redundant_solutions: false,
+                interrupt: Interrupt::default(),
+                progress: Progress::default(),
+                permutations: 0,
+                solutions: 0,
+                span: Span::none(),
+                checkpoint_clock: None,
+                shard: None,
+                branch_size: factorial(number_of_investors.saturating_sub(1)),
+                branch_effect: heap_effect(number_of_investors.saturating_sub(1)),
+            };
+            let mut actual = vec![];
+            while solutions.next_permutation() {
+                actual.push(solutions.free_investors.clone());
+            }
+
+            assert_eq!(actual, expected);
+
+            // `--shard` relies on the last investor only changing between branches
+            // and skips the branches with `heap_effect`.
+            for branch in actual.chunks(solutions.branch_size as usize) {
+                assert!(branch
+                    .iter()
+                    .all(|permutation| permutation.last() == branch[0].last()));
+
+                let first = &branch[0];
+                let skipped: Vec<_> = solutions
+                    .branch_effect
+                    .iter()
+                    .map(|&index| first[index])
+                    .chain(first.last().copied())
+                    .collect();
+                assert_eq!(&skipped, branch.last().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn ndjson() {
+        let parameters = Parameters::builder()
+            .number_of_seats(NonZeroUsize::new(5).unwrap())
+            .names(vec!["A", "B", "C", "D", "E"])
+            .ndjson(true)
+            .build()
+            .unwrap();
+        let mut out = vec![];
+        let output = super::spinning_table_to(parameters, &mut out).unwrap();
+
+        assert!(output.streamed);
+        assert_eq!(output.number_of_solutions, 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap().lines().next(),
+            Some(r#"{"seats":["A","C","E","B","D"]}"#),
+        );
+    }
+
+    #[test]
+    fn stream_flushes_each_solution() {
+        /// A writer that records how many lines were written when it was last flushed.
+        #[derive(Default)]
+        struct Flushed {
+            written: Vec<u8>,
+            lines_flushed: Vec<usize>,
+        }
+
+        impl io::Write for Flushed {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.written.write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.lines_flushed
+                    .push(self.written.iter().filter(|&&b| b == b'\n').count());
+                Ok(())
+            }
+        }
+
+        let parameters = Parameters::builder()
+            .number_of_seats(NonZeroUsize::new(5).unwrap())
+            .stream(true)
+            .build()
+            .unwrap();
+        let mut out = Flushed::default();
+        let output = super::spinning_table_to(parameters, &mut out).unwrap();
+
+        assert_eq!(output.number_of_solutions, 3);
+        assert_eq!(out.lines_flushed, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn iter_solutions() {
+        let parameters = || Parameters::builder().include_redundant_solutions(true);
+        let solutions = super::spinning_table(parameters().build().unwrap())
+            .unwrap()
+            .solutions;
+        let iterated: Vec<_> = super::iter_solutions(&parameters().build().unwrap())
+            .unwrap()
+            .collect();
+
+        assert_eq!(iterated, solutions);
+    }
+
+    #[test]
+    fn builder() {
+        let parameters = Parameters::builder()
+            .number_of_seats(NonZeroUsize::new(7).unwrap())
+            .fix(vec!["1=1".parse().unwrap()])
+            .names(vec![
+                "Alice", "Bob", "Carol", "Dave", "Eve", "Frank", "Grace",
+            ])
+            .build()
+            .unwrap();
+        let result = super::spinning_table(parameters).unwrap();
+
+        assert_eq!(result.number_of_solutions, 19);
+        assert_eq!(
+            result.to_string().lines().next(),
+            Some("[Alice, Dave, Grace, Eve, Carol, Bob, Frank]"),
+        );
+
+        assert_eq!(
+            Parameters::builder()
+                .no_anchor(true)
+                .include_redundant_solutions(true)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("no_anchor", "include_redundant_solutions")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .include_redundant_solutions(true)
+                .fix(vec!["1=1".parse().unwrap()])
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("include_redundant_solutions", "fix")
+        );
+        assert_eq!(
+            Parameters::builder()
+                .shuffle(true)
+                .stream(true)
+                .build()
+                .unwrap_err(),
+            BuildError::Conflict("shuffle", "stream")
+        );
+        assert_eq!(
+            Parameters::builder().seed(1).build().unwrap_err(),
+            BuildError::MissingRequirement("seed", "shuffle")
+        );
+    }
+
+    #[test]
+    fn checkpoint() {
+        let parameters = Parameters::builder().build().unwrap();
+        let expected: Vec<_> = super::iter_solutions(&parameters).unwrap().collect();
+
+        // Stop after a few solutions, then resume from a checkpoint in a new search.
+        let mut solutions = super::iter_solutions(&parameters).unwrap();
+        let found: Vec<_> = solutions.by_ref().take(5).collect();
+        let checkpoint = solutions.checkpoint(&found, found.len());
+        let checkpoint = Checkpoint::from_json(&checkpoint.to_json()).unwrap();
+        let mut resumed = super::iter_solutions(&parameters).unwrap();
+        resumed.restore(&checkpoint).unwrap();
+
+        assert_eq!(checkpoint.solutions, &expected[..5]);
+        assert_eq!(resumed.collect::<Vec<_>>(), &expected[5..]);
+
+        let other_parameters = Parameters::builder()
+            .number_of_seats(NonZeroUsize::new(8).unwrap())
+            .build()
+            .unwrap();
+        let mut other = super::iter_solutions(&other_parameters).unwrap();
+        assert!(matches!(
+            other.restore(&checkpoint),
+            Err(CheckpointError::Mismatch)
+        ));
+
+        // Stop in the middle of the rotations of a solution.
+        let parameters = Parameters::builder()
+            .include_redundant_solutions(true)
+            .build()
+            .unwrap();
+        let expected: Vec<_> = super::iter_solutions(&parameters).unwrap().collect();
+        let mut solutions = super::iter_solutions(&parameters).unwrap();
+        let found: Vec<_> = solutions.by_ref().take(10).collect();
+        let checkpoint = solutions.checkpoint(&found, found.len());
+        let checkpoint = Checkpoint::from_json(&checkpoint.to_json()).unwrap();
+        let mut resumed = super::iter_solutions(&parameters).unwrap();
+        resumed.restore(&checkpoint).unwrap();
+
+        assert_eq!(resumed.collect::<Vec<_>>(), &expected[10..]);
+    }
+
+    #[test]
+    fn shard() {
+        let parameters = Parameters::builder()
+            .number_of_seats(NonZeroUsize::new(8).unwrap())
+            .build()
+            .unwrap();
+        let mut expected: Vec<_> = super::iter_solutions(&parameters).unwrap().collect();
+        expected.sort();
+
+        let mut found = Vec::new();
+        for shard in ["1/3", "2/3", "3/3"] {
+            let parameters = Parameters::builder()
+                .number_of_seats(NonZeroUsize::new(8).unwrap())
+                .shard(shard.parse().unwrap())
+                .build()
+                .unwrap();
+            found.extend(super::iter_solutions(&parameters).unwrap());
+        }
+        found.sort();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn simulation() {
+        let parameters = Parameters::builder().build().unwrap();
+        let exact = super::iter_solutions(&parameters).unwrap().count() as f64;
+        let simulation = simulate(
+            &parameters,
+            NonZeroUsize::new(20_000).unwrap(),
+            &mut StdRng::seed_from_u64(1),
+        )
+        .unwrap();
+
+        assert_eq!(simulation.samples, 20_000);
+        assert_eq!(simulation.arrangements, 720);
+        assert!((simulation.estimated_solutions() - exact).abs() < 0.2 * exact);
+    }
+
+    #[test]
+    fn dihedral_counts() {
+        let counts = dihedral_count(DihedralCountParameters {
+            number_of_seats: NonZeroUsize::new(7).unwrap(),
+        });
+
+        let row = &counts.rows[6];
+        assert_eq!(row.number_of_seats, 7);
+        assert_eq!(row.rotation_canonical, 19);
+        assert_eq!(row.rotation_redundant, 133);
+        // The reflections of the solutions are solutions too.
+        assert_eq!(row.dihedral_redundant, row.rotation_redundant);
+        assert!(row.dihedral_canonical <= row.rotation_canonical);
+        assert!(2 * row.dihedral_canonical >= row.rotation_canonical);
+
+        assert_eq!(reflection(&[1, 4, 7, 5, 3, 2, 6]), [1, 3, 7, 6, 4, 2, 5]);
+        assert!(is_valid_solution(&[1, 3, 7, 6, 4, 2, 5]));
+    }
 }
@@ -0,0 +1,226 @@
+//! The `--tui` mode, which shows the search live in the terminal.
+
+use std::{
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Layout},
+    widgets::{Block, Gauge, Paragraph},
+    Frame, Terminal,
+};
+
+use matt_parkers_maths_puzzles::{
+    card::{self, Card},
+    progress::Progress,
+    scrabble::Scrabble,
+    spinning_table::SpinningTable,
+    Error, Solver,
+};
+
+use crate::{run, Context, Options, Puzzle};
+
+/// How often the screen is redrawn during a search.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long each state of the cards is shown.
+const FLIP_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Runs the command while showing the search on stderr,
+/// then writes the output of the command to `out`.
+///
+/// For the card puzzle, the state of the cards is shown after each flip.
+/// Pressing `q`, Esc or Ctrl-C stops the search early
+/// or skips the rest of the flips.
+pub fn run_with_tui(
+    mut options: Options,
+    context: &Context,
+    out: &mut impl Write,
+) -> Result<(), Error> {
+    options.tui = false;
+    let title = match &options.puzzle {
+        Puzzle::SpinningTable(_) => SpinningTable::NAME,
+        Puzzle::Scrabble(_) => Scrabble::NAME,
+        Puzzle::Card(_) => Card::NAME,
+        _ => env!("CARGO_PKG_NAME"),
+    };
+
+    let (result, output) = {
+        let mut screen = Screen::enter()?;
+        if let Puzzle::Card(parameters) = &options.puzzle {
+            let solution = Card::solve(parameters.clone())?;
+            show_flips(&mut screen, &solution)?;
+        }
+
+        watch_search(&mut screen, title, options, context)?
+    };
+
+    // The screen has been restored, so the output can be written.
+    out.write_all(&output)?;
+    result
+}
+
+/// The terminal in raw mode on the alternate screen,
+/// which is restored when this is dropped.
+struct Screen(Terminal<CrosstermBackend<io::Stderr>>);
+
+impl Screen {
+    /// Switches stderr to raw mode on the alternate screen.
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stderr(), EnterAlternateScreen)?;
+        Ok(Self(Terminal::new(CrosstermBackend::new(io::stderr()))?))
+    }
+
+    /// Draws a frame.
+    fn draw(&mut self, render: impl FnOnce(&mut Frame<'_>)) -> io::Result<()> {
+        self.0.draw(render)?;
+        Ok(())
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        // There is nothing else to do if the terminal can't be restored.
+        let _ = execute!(io::stderr(), LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Waits up to `timeout` for a key press
+/// and determines whether it asks to stop.
+fn stop_requested(timeout: Duration) -> io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+
+    Ok(match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => true,
+            KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Runs the command on another thread, collecting its output,
+/// and shows the progress of the search and its current step until it is done.
+///
+/// Stopping the search sets the Ctrl-C flag of `context`, like Ctrl-C does.
+/// Returns the result of the command and its output.
+fn watch_search(
+    screen: &mut Screen,
+    title: &str,
+    options: Options,
+    context: &Context,
+) -> io::Result<(Result<(), Error>, Vec<u8>)> {
+    let progress = &context.progress;
+    let start = Instant::now();
+    thread::scope(|scope| {
+        let search = scope.spawn(move || {
+            let mut output = vec![];
+            let result = run(options, context, &mut output);
+            (result, output)
+        });
+
+        let mut stopping = false;
+        while !search.is_finished() {
+            progress.request_snapshot();
+            screen.draw(|frame| draw_search(frame, title, progress, start, stopping))?;
+            if !stopping && stop_requested(REFRESH_INTERVAL)? {
+                context.ctrl_c.trigger();
+                stopping = true;
+            }
+        }
+
+        Ok(search.join().expect("the search panicked"))
+    })
+}
+
+/// Draws the progress of a search and its current step.
+fn draw_search(
+    frame: &mut Frame<'_>,
+    title: &str,
+    progress: &Progress,
+    start: Instant,
+    stopping: bool,
+) {
+    let [gauge_area, step_area, help_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let (position, length) = (progress.position(), progress.length());
+    let ratio = if length == 0 {
+        0.0
+    } else {
+        (position as f64 / length as f64).min(1.0)
+    };
+    let gauge = Gauge::default()
+        .block(Block::bordered().title(format!(" {} ({:.1?}) ", title, start.elapsed())))
+        .ratio(ratio)
+        .label(format!("{} / {} steps", position, length));
+    frame.render_widget(gauge, gauge_area);
+
+    let step = Paragraph::new(progress.snapshot()).block(Block::bordered().title(" current step "));
+    frame.render_widget(step, step_area);
+
+    let help = if stopping {
+        "stopping..."
+    } else {
+        "press q to stop the search"
+    };
+    frame.render_widget(Paragraph::new(help), help_area);
+}
+
+/// Shows the state of the cards before the first flip and after each flip.
+fn show_flips(screen: &mut Screen, solution: &card::Output) -> io::Result<()> {
+    let total = solution.flips.len();
+    for (index, cards) in solution.states().enumerate() {
+        let caption = match index {
+            0 => "start".to_string(),
+            _ => format!(
+                "flip {} of {}: card {}",
+                index,
+                total,
+                solution.flips[index - 1]
+            ),
+        };
+        screen.draw(|frame| draw_cards(frame, &caption, &cards))?;
+        if stop_requested(FLIP_INTERVAL)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws a state of the cards, where `true` means the card is face down.
+fn draw_cards(frame: &mut Frame<'_>, caption: &str, cards: &[bool]) {
+    let [cards_area, help_area] =
+        Layout::vertical([Constraint::Min(4), Constraint::Length(1)]).areas(frame.area());
+
+    let faces: Vec<_> = cards
+        .iter()
+        .map(|&face_down| if face_down { "[###]" } else { "[   ]" })
+        .collect();
+    let numbers: Vec<_> = (1..=cards.len())
+        .map(|card_number| format!("{:^5}", card_number))
+        .collect();
+    let text = format!("{}\n{}", faces.join(" "), numbers.join(" "));
+    let cards = Paragraph::new(text).block(Block::bordered().title(format!(" {} ", caption)));
+    frame.render_widget(cards, cards_area);
+
+    frame.render_widget(Paragraph::new("press q to skip the flips"), help_area);
+}
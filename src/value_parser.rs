@@ -0,0 +1,11 @@
+use std::{convert::TryFrom, num::NonZeroUsize};
+
+use clap::builder::{RangedU64ValueParser, TypedValueParser};
+
+/// Parses a positive number,
+/// reporting 0 as out of range like the other numbers outside of their range.
+pub fn non_zero_usize() -> impl TypedValueParser<Value = NonZeroUsize> {
+    RangedU64ValueParser::<usize>::new()
+        .range(1..)
+        .try_map(NonZeroUsize::try_from)
+}
@@ -0,0 +1,84 @@
+//! Entry points for solving the puzzles in a web page.
+//!
+//! The library is built for `wasm32-unknown-unknown` with the `wasm` feature,
+//! for example with [`wasm-pack`](https://rustwasm.github.io/wasm-pack/):
+//!
+//! ```text
+//! wasm-pack build --target web -- --features wasm
+//! ```
+//!
+//! Each function takes the options of its puzzle as they are written on the command line
+//! and returns the solution as a JavaScript object,
+//! shaped like the `solution` of the JSON output:
+//!
+//! ```text
+//! const solution = solveScrabble(["--hand-size", "8", "--target-score", "50"]);
+//! ```
+//!
+//! Invalid options and the errors of the solvers are thrown as `Error`s.
+//! The searches run on the page's thread, so long searches block the page
+//! unless they are run in a web worker.
+
+use std::iter;
+
+use clap::Parser;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::Solver;
+
+#[cfg(feature = "puzzle-card")]
+use crate::card::Card;
+
+#[cfg(feature = "puzzle-scrabble")]
+use crate::scrabble::Scrabble;
+
+#[cfg(feature = "puzzle-spinning-table")]
+use crate::spinning_table::SpinningTable;
+
+/// Solves the spinning table puzzle with the options in `args`.
+#[cfg(feature = "puzzle-spinning-table")]
+#[wasm_bindgen(js_name = solveSpinningTable)]
+pub fn solve_spinning_table(args: Vec<String>) -> Result<JsValue, JsError> {
+    solve::<SpinningTable>(args)
+}
+
+/// Solves the Scrabble® puzzle with the options in `args`.
+#[cfg(feature = "puzzle-scrabble")]
+#[wasm_bindgen(js_name = solveScrabble)]
+pub fn solve_scrabble(args: Vec<String>) -> Result<JsValue, JsError> {
+    solve::<Scrabble>(args)
+}
+
+/// Solves the card puzzle with the options in `args`.
+#[cfg(feature = "puzzle-card")]
+#[wasm_bindgen(js_name = solveCard)]
+pub fn solve_card(args: Vec<String>) -> Result<JsValue, JsError> {
+    solve::<Card>(args)
+}
+
+/// Parses the parameters of a puzzle from `args`, solves the puzzle
+/// and converts the solution to a JavaScript value.
+///
+/// The solution goes through its JSON value,
+/// so that its maps become plain objects with string keys
+/// exactly like in the JSON output.
+#[cfg_attr(
+    not(any(
+        feature = "puzzle-spinning-table",
+        feature = "puzzle-scrabble",
+        feature = "puzzle-card"
+    )),
+    allow(dead_code)
+)]
+fn solve<S>(args: Vec<String>) -> Result<JsValue, JsError>
+where
+    S: Solver,
+    S::Parameters: Parser,
+    S::Output: Serialize,
+{
+    let parameters = S::Parameters::try_parse_from(iter::once(S::NAME.to_owned()).chain(args))?;
+    let solution = serde_json::to_value(S::solve(parameters)?)?;
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(solution.serialize(&serializer)?)
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    iter::{self, FromIterator},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use itertools::Itertools;
+
+use quick_error::quick_error;
+
+/// The letter of a blank tile in a hand, which stands for any letter.
+const BLANK: char = ' ';
+
+/// The letters that a blank tile can stand for.
+const LETTERS: RangeInclusive<char> = 'A'..='Z';
+
+/// An anagram index over a word list,
+/// to check which hands of Scrabble® tiles can form a word.
+///
+/// The index maps the letters of each word in alphabetical order
+/// to the words with these letters,
+/// so that a hand forms a word if its sorted letters are in the index.
+#[derive(Clone, Debug, Default)]
+pub struct Wordlist {
+    words_by_key: HashMap<String, Vec<String>>,
+}
+
+impl Wordlist {
+    /// Loads a word list with one word per line.
+    ///
+    /// The words are not case-sensitive, and empty lines are ignored.
+    pub fn load(path: &Path) -> Result<Self, WordlistError> {
+        Ok(fs::read_to_string(path)?.lines().collect())
+    }
+
+    /// Returns the number of distinct sets of letters in the word list.
+    pub fn len(&self) -> usize {
+        self.words_by_key.len()
+    }
+
+    /// Returns whether the word list has no words.
+    pub fn is_empty(&self) -> bool {
+        self.words_by_key.is_empty()
+    }
+
+    /// Returns an iterator over the words of the word list, in uppercase and in no particular order.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words_by_key.values().flatten().map(String::as_str)
+    }
+
+    /// Returns whether all the tiles of `hand` form a word,
+    /// or, if `shorter_words` is set, whether some of them do.
+    ///
+    /// Blank tiles (spaces) stand for any letter.
+    pub fn can_form(&self, hand: &str, shorter_words: bool) -> bool {
+        let mut letters: Vec<char> = hand.chars().filter(|&letter| letter != BLANK).collect();
+        letters.sort_unstable();
+        let blanks = hand.chars().count() - letters.len();
+
+        // Each set of the hand's letters is looked up
+        // with each set of letters that the blanks can stand for.
+        let subsets: HashSet<Vec<char>> = if shorter_words {
+            (0..=letters.len())
+                .flat_map(|len| letters.iter().cloned().combinations(len))
+                .collect()
+        } else {
+            iter::once(letters).collect()
+        };
+        let blanks_used = if shorter_words { 0 } else { blanks };
+
+        subsets.iter().any(|subset| {
+            (blanks_used..=blanks).any(|blanks| {
+                LETTERS
+                    .combinations_with_replacement(blanks)
+                    .any(|blank_letters| {
+                        let key: String = subset.iter().cloned().merge(blank_letters).collect();
+                        !key.is_empty() && self.words_by_key.contains_key(&key)
+                    })
+            })
+        })
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Wordlist {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(words: I) -> Self {
+        let mut words_by_key = HashMap::<_, Vec<_>>::new();
+        for word in words.into_iter().map(str::trim) {
+            if word.is_empty() {
+                continue;
+            }
+
+            let word = word.to_uppercase();
+            let words = words_by_key
+                .entry(word.chars().sorted().collect::<String>())
+                .or_default();
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+
+        Wordlist { words_by_key }
+    }
+}
+
+quick_error! {
+    /// An error that can be returned when loading a word list.
+    #[derive(Debug)]
+    pub enum WordlistError {
+        /// The word list could not be read.
+        Io(err: io::Error) {
+            cause(err)
+            display("could not read the word list: {}", err)
+            from()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wordlist;
+
+    #[test]
+    fn can_form() {
+        let wordlist: Wordlist = ["quiz", "Jazz", "ox", "QUIZ"].iter().cloned().collect();
+        assert_eq!(wordlist.len(), 3);
+        assert_eq!(wordlist.words().count(), 3);
+
+        assert!(wordlist.can_form("IQUZ", false));
+        assert!(!wordlist.can_form("IQUZX", false));
+        assert!(wordlist.can_form("IQUZX", true));
+        assert!(wordlist.can_form("XO", false));
+
+        // A blank tile stands for any letter.
+        assert!(wordlist.can_form("AJZ ", false));
+        assert!(wordlist.can_form(" X", false));
+        assert!(!wordlist.can_form("AJZ  ", false));
+        assert!(wordlist.can_form("AJZ  ", true));
+        assert!(!wordlist.can_form("BCD", true));
+    }
+}
@@ -0,0 +1,877 @@
+use std::{
+    io::{Read, Write},
+    process::{Command, Output, Stdio},
+};
+
+/// Returns the command that runs the program,
+/// without the user's configuration file.
+fn program() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_matt-parkers-maths-puzzles"));
+    command.env("MPMP_CONFIG", "");
+    command
+}
+
+/// Runs the program with the given arguments.
+fn run(args: &[&str]) -> Output {
+    program()
+        .args(args)
+        .output()
+        .expect("failed to run the program")
+}
+
+#[test]
+fn quiet_scrabble_count() {
+    let output = run(&["-q", "scrabble", "--output", "count"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn normal_scrabble_count() {
+    let output = run(&["scrabble", "--output", "count"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "number of hands: 138\n"
+    );
+}
+
+#[test]
+fn verbose_scrabble_count() {
+    let output = run(&["-v", "scrabble", "--output", "count"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "number of hands: 138\n"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("parameters: "));
+    assert!(stderr.contains("solved in "));
+}
+
+#[test]
+fn bash_completions() {
+    let output = run(&["completions", "bash"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.is_empty());
+    for subcommand in &["spinning-table", "scrabble", "card", "list", "completions"] {
+        assert!(stdout.contains(subcommand), "{} is missing", subcommand);
+    }
+}
+
+#[test]
+fn streamed_spinning_table() {
+    let collected = run(&["-q", "spinning-table"]);
+    let streamed = run(&["-q", "spinning-table", "--stream"]);
+
+    assert!(collected.status.success());
+    assert!(streamed.status.success());
+    let mut collected: Vec<_> = String::from_utf8(collected.stdout)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    let mut streamed: Vec<_> = String::from_utf8(streamed.stdout)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    collected.sort();
+    streamed.sort();
+    assert_eq!(streamed.len(), 19);
+    assert_eq!(streamed, collected);
+}
+
+#[test]
+fn streamed_scrabble() {
+    let collected = run(&["scrabble", "--output", "list"]);
+    let streamed = run(&["scrabble", "--output", "list", "--stream"]);
+
+    assert!(collected.status.success());
+    assert!(streamed.status.success());
+    let collected = String::from_utf8(collected.stdout).unwrap();
+    let streamed = String::from_utf8(streamed.stdout).unwrap();
+    // The hands come in the same order, followed by their number instead of after it.
+    let collected: Vec<_> = collected.lines().skip(1).collect();
+    let mut streamed: Vec<_> = streamed.lines().collect();
+    assert_eq!(streamed.pop(), Some("number of hands: 138"));
+    assert_eq!(streamed, collected);
+}
+
+#[test]
+fn closed_output() {
+    let mut child = program()
+        .args(["-q", "spinning-table", "-n", "11", "--stream"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the program");
+    // Stop reading after the first solution, like `head -n 1`.
+    let mut first = [0; 1];
+    child.stdout.take().unwrap().read_exact(&mut first).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn solve_all() {
+    let output = run(&["-q", "solve-all"]);
+
+    assert!(output.status.success());
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results["scrabble"]["count"], 138);
+    assert_eq!(results["spinning_table"]["count"], 19);
+    assert_eq!(results["card"]["flips"], 15);
+}
+
+#[test]
+fn json_scrabble() {
+    let output = run(&["--format", "json", "scrabble"]);
+
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["puzzle"], "scrabble");
+    assert_eq!(document["parameters"]["hand_size"], 7);
+    assert_eq!(document["solution"]["Count"], 138);
+}
+
+#[test]
+fn json_list() {
+    let output = run(&["--format", "json", "list"]);
+
+    assert!(output.status.success());
+    let catalog: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let scrabble = &catalog[1];
+    assert_eq!(scrabble["name"], "scrabble");
+    assert_eq!(scrabble["answer"], "138 hands");
+    let hand_size = &scrabble["parameters"][0];
+    assert_eq!(hand_size["name"], "hand_size");
+    assert_eq!(hand_size["short"], "h");
+    assert_eq!(hand_size["default"], "7");
+    let output_parameter = scrabble["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|parameter| parameter["name"] == "output")
+        .unwrap();
+    assert_eq!(
+        output_parameter["values"],
+        serde_json::json!([
+            "count",
+            "list",
+            "by-score",
+            "probability",
+            "distribution",
+            "unreachable",
+            "bingos",
+            "by-values",
+            "statistics"
+        ])
+    );
+}
+
+#[test]
+fn csv_spinning_table() {
+    let output = run(&["--format", "csv", "spinning-table", "-n", "5"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some("seat 1,seat 2,seat 3,seat 4,seat 5"));
+    assert_eq!(lines.count(), 3);
+}
+
+#[test]
+fn markdown_spinning_table() {
+    let output = run(&["--format", "markdown", "spinning-table", "-n", "5"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    let caption = lines.next().unwrap();
+    assert!(caption.starts_with("**spinning-table** — "));
+    assert!(caption.contains("number_of_seats: `5`"));
+    assert_eq!(lines.next(), Some(""));
+    assert_eq!(
+        lines.next(),
+        Some("| seat 1 | seat 2 | seat 3 | seat 4 | seat 5 |")
+    );
+    assert_eq!(lines.next(), Some("| --- | --- | --- | --- | --- |"));
+    assert_eq!(lines.next(), Some("| 1 | 3 | 5 | 2 | 4 |"));
+    assert_eq!(lines.count(), 2);
+}
+
+#[test]
+fn selftest() {
+    let output = run(&["selftest"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().last(), Some("3 passed, 0 failed"));
+}
+
+#[test]
+fn ndjson() {
+    let output = run(&["--format", "ndjson", "spinning-table", "-n", "5"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let solutions: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(solutions.len(), 3);
+    assert_eq!(solutions[0]["seats"], serde_json::json!([1, 3, 5, 2, 4]));
+
+    let output = run(&["--format", "ndjson", "scrabble", "--output", "list"]);
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout.split(|&byte| byte == b'\n').count(), 139);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let hand: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap()).unwrap();
+    assert_eq!(hand["total"], 46);
+    assert_eq!(
+        hand["tiles"][0],
+        serde_json::json!({ "letter": "A", "value": 1, "count": 1 })
+    );
+}
+
+#[test]
+fn stats() {
+    let output = run(&["--stats", "spinning-table"]);
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("solutions: 19\n"));
+    assert!(stderr.contains("nodes explored: 720\n"));
+    assert!(stderr.contains("peak memory: "));
+
+    let output = run(&["--stats", "--format", "json", "scrabble"]);
+
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(stats["solutions"], 138);
+    assert!(stats["branches_pruned"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn explain_pruning() {
+    let output = run(&["-q", "scrabble", "--explain-pruning"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("subtrees pruned: 0\n"));
+    assert!(stderr.contains("subtrees explored: "));
+    assert!(stderr.contains("pruned ratio: "));
+
+    let output = run(&["--format", "json", "scrabble", "--explain-pruning"]);
+
+    assert!(output.status.success());
+    let pruning: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(pruning["subtrees_pruned"].as_u64().unwrap() > 0);
+    assert!(pruning["pruned_ratio"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn target_percent_out_of_range() {
+    let output = run(&["scrabble", "--target-percent", "4000000000"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("--target-percent"));
+}
+
+#[test]
+fn distribution_check() {
+    let output = run(&[
+        "-q",
+        "scrabble",
+        "--distribution-check",
+        "--expected-total",
+        "98",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "total tiles: 100, total value: 187\nwarning: expected 98 tiles, but the distribution contains 100\n"
+    );
+}
+
+#[test]
+fn output_to_file() {
+    let directory = std::env::temp_dir().join(format!("mpmp-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("hands.txt");
+    let output = run(&["-q", "--output", path.to_str().unwrap(), "scrabble"]);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "138\n");
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn checkpoint_and_resume() {
+    let directory = std::env::temp_dir().join(format!("mpmp-checkpoint-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("search.json");
+    let path = path.to_str().unwrap();
+    let full = run(&["spinning-table", "-n", "9"]);
+
+    // Stop the search early, then resume it to the end.
+    let output = run(&[
+        "--time-limit",
+        "5ms",
+        "spinning-table",
+        "-n",
+        "9",
+        "--checkpoint",
+        path,
+    ]);
+
+    assert!(output.status.success());
+    let output = run(&["spinning-table", "-n", "9", "--resume", path]);
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, full.stdout);
+
+    let output = run(&["spinning-table", "-n", "7", "--resume", path]);
+
+    assert!(!output.status.success());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn completions_include_choices() {
+    for shell in &["bash", "zsh", "fish"] {
+        let output = run(&["completions", shell]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("by-score"), "{} is missing choices", shell);
+    }
+}
+
+#[test]
+fn all() {
+    let output = run(&["all"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("== Puzzle 1 - Spinning table ==\nsolutions (19):\n"));
+    assert!(stdout.contains("== Puzzle 3 - Scrabble® ==\nnumber of hands: 138\n"));
+    assert!(stdout.contains("== Puzzle 4 - Card ==\nflips (15): "));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn card_label() {
+    let output = run(&["card", "-n", "2"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "flips (3): [1, 2, 1]\n"
+    );
+
+    let output = run(&["card", "-n", "2", "--narrate"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "flips (3):\nstart: UU\nflip 1: DU\nflip 2: DD\nflip 1: UD\n"
+    );
+
+    let output = run(&["card", "-n", "2", "--flip-histogram"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "flips (3):\ncard 1: 2 flips\ncard 2: 1 flip\n"
+    );
+}
+
+#[test]
+fn json_flip_histogram() {
+    let output = run(&["--format", "json", "card", "-n", "2", "--flip-histogram"]);
+
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        document["solution"],
+        serde_json::json!({
+            "flips": [1, 2, 1],
+            "start": [false, false],
+            "flip_histogram": { "1": 2, "2": 1 },
+        })
+    );
+}
+
+#[test]
+fn progress_is_not_written_to_stdout() {
+    let output = run(&["-q", "--progress", "scrabble"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+}
+
+#[test]
+fn debug_scrabble_search() {
+    let output = run(&["-vv", "scrabble"]);
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("combinations=3432"));
+    assert!(stderr.contains("search finished matching_combinations=4"));
+    assert!(!stderr.contains("TRACE"));
+}
+
+#[test]
+fn bench_card() {
+    let output = run(&["--format", "json", "bench", "--runs", "3", "card"]);
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["puzzle"], "card");
+    assert_eq!(report["runs"], 3);
+    assert!(report["min"].as_f64().unwrap() <= report["median"].as_f64().unwrap());
+    assert!(report["allocations_per_run"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn repl() {
+    let mut child = program()
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the program");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"-q scrabble\nnot-a-puzzle\n-q card --number-of-cards 2\nquit\n-q scrabble\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "138\n[1, 2, 1]\n"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("not-a-puzzle"));
+}
+
+#[test]
+fn tui_without_terminal() {
+    let output = run(&["-q", "--tui", "card"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[1, 2, 1, 3, 1, 2, 1, 4, 1, 2, 1, 3, 1, 2, 1]\n"
+    );
+}
+
+#[test]
+fn config_file() {
+    let directory = std::env::temp_dir().join(format!("mpmp-config-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("config.toml");
+    std::fs::write(
+        &path,
+        "quiet = true\n\n[scrabble]\nhand-size = 8\ntarget-score = 50\n",
+    )
+    .unwrap();
+    let config = path.to_str().unwrap();
+
+    let output = run(&["--config", config, "scrabble"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "416\n");
+
+    // The command line takes precedence over the configuration file.
+    let output = run(&[
+        "--config",
+        config,
+        "scrabble",
+        "--hand-size",
+        "7",
+        "--target-score",
+        "46",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+
+    // The options that conflict with the command line are left out.
+    let output = run(&["--config", config, "scrabble", "--target-percent", "50"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "543419\n");
+
+    // The environment variable gives the file to read by default.
+    let output = program()
+        .env("MPMP_CONFIG", config)
+        .arg("scrabble")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "416\n");
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn wordlist() {
+    let directory = std::env::temp_dir().join(format!("mpmp-wordlist-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("words.txt");
+    std::fs::write(&path, "quiz\njazz\nzax\nox\n").unwrap();
+    let wordlist = path.to_str().unwrap();
+
+    // QUIZ is the only word among the hands of 4 tiles scoring 22.
+    let args = [
+        "-q",
+        "scrabble",
+        "-h",
+        "4",
+        "-s",
+        "22",
+        "--wordlist",
+        wordlist,
+    ];
+    let output = run(&args);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+
+    let output = run(&[&args[..], &["--output", "list"]].concat());
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "IUQZ\n");
+
+    let output = run(&[&args[..], &["--shorter-words"]].concat());
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "24\n");
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn repl_cache() {
+    let directory = std::env::temp_dir().join(format!("mpmp-repl-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("words.txt");
+    std::fs::write(&path, "quiz\njazz\nzax\nox\n").unwrap();
+    let command = format!("-q scrabble -h 4 -s 22 --wordlist '{}'\n", path.display());
+
+    let mut child = program()
+        .args(["-vv", "repl"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the program");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(command.repeat(2).as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n1\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("loaded the word list").count(), 1);
+    assert_eq!(stderr.matches("reusing the loaded file").count(), 1);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn params_json() {
+    let output = run(&[
+        "-q",
+        "scrabble",
+        "--params-json",
+        r#"{"hand_size": 8, "target_score": 50}"#,
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "416\n");
+
+    // The command line takes precedence over the JSON object.
+    let output = run(&[
+        "-q",
+        "scrabble",
+        "--params-json",
+        r#"{"hand_size": 8, "target_score": 50}"#,
+        "--hand-size",
+        "7",
+        "--target-score",
+        "46",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+
+    // The parameters of the JSON output solve the same puzzle again.
+    let output = run(&[
+        "--format",
+        "json",
+        "spinning-table",
+        "-n",
+        "5",
+        "--fix",
+        "2=3",
+    ]);
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let params = document["parameters"].to_string();
+    let again = run(&[
+        "--format",
+        "json",
+        "spinning-table",
+        "--params-json",
+        &params,
+    ]);
+    assert!(again.status.success());
+    assert_eq!(again.stdout, output.stdout);
+
+    let output = run(&["card", "--params-json", r#"{"foo": 1}"#]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn max_solutions() {
+    let output = run(&["-q", "--max-solutions", "5", "spinning-table", "--stream"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 5);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("stopped after 5 solutions"));
+
+    let output = run(&["-q", "--max-solutions", "5", "scrabble"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn shuffled_sample() {
+    let sample = |seed| {
+        let output = run(&[
+            "-q",
+            "--max-solutions",
+            "3",
+            "spinning-table",
+            "--shuffle",
+            "--seed",
+            seed,
+        ]);
+        assert!(output.status.success());
+        let mut solutions: Vec<_> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect();
+        solutions.sort();
+        solutions
+    };
+
+    let first = sample("1");
+    assert_eq!(first.len(), 3);
+    assert_ne!(first, sample("2"));
+    assert_eq!(first, sample("1"));
+}
+
+#[test]
+fn time_limit() {
+    let output = run(&["-q", "--time-limit", "1ms", "spinning-table", "-n", "12"]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("search incomplete"));
+
+    let output = run(&["--time-limit", "30", "card"]);
+
+    assert!(!output.status.success());
+
+    // The time limit of a command doesn't stop the next ones.
+    let mut child = program()
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the program");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"-q --time-limit 1ms spinning-table -n 12\n-q scrabble\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().lines().last(),
+        Some("138")
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .matches("search incomplete")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn threads() {
+    let output = run(&["-q", "--threads", "1", "scrabble"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "138\n");
+}
+
+#[test]
+fn color() {
+    let output = run(&["--color", "always", "card", "-n", "2"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "flips (3): [\x1b[31m1\x1b[0m, \x1b[32m2\x1b[0m, \x1b[31m1\x1b[0m]\n"
+    );
+
+    // The output isn't a terminal, so `auto` doesn't color it.
+    let output = run(&["spinning-table"]);
+
+    assert!(output.status.success());
+    assert!(!output.stdout.contains(&b'\x1b'));
+
+    let output = run(&[
+        "--color", "always", "--format", "json", "scrabble", "--output", "list",
+    ]);
+
+    assert!(output.status.success());
+    assert!(!output.stdout.contains(&b'\x1b'));
+}
+
+#[test]
+fn silent() {
+    let output = run(&["-qq", "spinning-table"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    // There are no solutions for an even number of seats.
+    let output = run(&["-qq", "spinning-table", "-n", "8"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    let output = run(&["-qq", "scrabble", "--tiles", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn estimate() {
+    let output = run(&["-q", "--estimate", "card", "-n", "4"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().next(), Some("15 flips"));
+
+    let output = run(&[
+        "--estimate",
+        "--format",
+        "json",
+        "card",
+        "-n",
+        "30",
+        "--round-trip",
+    ]);
+
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["solution"]["search_space"], 2 * ((1u64 << 30) - 1));
+
+    let output = run(&["--estimate", "list"]);
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn simulate() {
+    let args = ["simulate", "--seed", "7", "--samples", "1000", "scrabble"];
+    let first = run(&args);
+    let second = run(&args);
+
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+    assert!(String::from_utf8(first.stdout)
+        .unwrap()
+        .starts_with("seed: 7\nsamples: 1000\n"));
+
+    let output = run(&["--format", "json", "simulate", "--seed", "7", "card"]);
+
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["parameters"]["seed"], 7);
+    assert_eq!(document["solution"]["samples"], 10_000);
+}
+
+#[test]
+fn batch() {
+    let mut child = program()
+        .arg("batch")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run the program");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"scrabble -h 8 -s 50\n\
+              \n\
+              {\"puzzle\": \"scrabble\", \"hand_size\": 8, \"target_score\": 50}\n\
+              {\"puzzle\": \"spinning-table\", \"max_solutions\": 2}\n\
+              not-a-puzzle\n",
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let results: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["solution"]["Count"], 416);
+    assert_eq!(results[1], results[0]);
+    assert_eq!(results[2]["solution"]["number_of_solutions"], 2);
+    assert!(results[3]["error"]
+        .as_str()
+        .unwrap()
+        .contains("not-a-puzzle"));
+}
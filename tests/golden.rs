@@ -0,0 +1,104 @@
+//! Compares the output of each puzzle with its expected output
+//! in the `tests/golden` directory.
+//!
+//! To regenerate the golden files after an intentional change in the output,
+//! run the tests with the `UPDATE_GOLDEN` environment variable set to `1`:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+//!
+//! Review the changes to the golden files before committing them.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+/// Runs the program with the given arguments
+/// and compares its standard output with the given golden file.
+fn check_golden(golden_file_name: &str, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_matt-parkers-maths-puzzles"))
+        // Leave out the user's configuration file.
+        .env("MPMP_CONFIG", "")
+        .args(args)
+        .output()
+        .expect("failed to run the program");
+    assert!(output.status.success(), "the program failed: {:?}", output);
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let golden_path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "golden",
+        golden_file_name,
+    ]
+    .iter()
+    .collect();
+
+    if env::var_os("UPDATE_GOLDEN").is_some_and(|value| value == "1") {
+        fs::write(&golden_path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&golden_path).unwrap_or_else(|err| {
+        panic!(
+            "could not read {} ({}); run with UPDATE_GOLDEN=1 to create it",
+            golden_path.display(),
+            err
+        )
+    });
+
+    if let Some(diff) = diff(&expected, &actual) {
+        panic!(
+            "the output of `{}` doesn't match {}:\n{}\nrun with UPDATE_GOLDEN=1 to update the golden file",
+            args.join(" "),
+            golden_path.display(),
+            diff
+        );
+    }
+}
+
+/// Describes the differences between the expected and the actual output line by line,
+/// or returns `None` if they are identical.
+fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let expected_lines: Vec<_> = expected.lines().collect();
+    let actual_lines: Vec<_> = actual.lines().collect();
+    let mut report = String::new();
+    for line_index in 0..expected_lines.len().max(actual_lines.len()) {
+        let expected_line = expected_lines.get(line_index);
+        let actual_line = actual_lines.get(line_index);
+        if expected_line != actual_line {
+            if let Some(expected_line) = expected_line {
+                report.push_str(&format!("{:>5} - {}\n", line_index + 1, expected_line));
+            }
+
+            if let Some(actual_line) = actual_line {
+                report.push_str(&format!("{:>5} + {}\n", line_index + 1, actual_line));
+            }
+        }
+    }
+
+    if report.is_empty() {
+        // Only the line endings differ.
+        report.push_str("(the outputs differ in their line endings)\n");
+    }
+
+    Some(report)
+}
+
+#[test]
+fn spinning_table() {
+    check_golden("spinning-table.txt", &["-q", "spinning-table"]);
+}
+
+#[test]
+fn scrabble_list() {
+    check_golden("scrabble-list.txt", &["-q", "scrabble", "--output", "list"]);
+}
+
+#[test]
+fn card() {
+    check_golden("card.txt", &["-q", "card"]);
+}
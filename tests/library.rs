@@ -0,0 +1,167 @@
+//! Calls the solvers through the library crate.
+
+use matt_parkers_maths_puzzles::{
+    card::{self, Card},
+    puzzle_info,
+    scrabble::{self, Scrabble},
+    spinning_table::{self, SpinningTable},
+    Error, Solver,
+};
+
+#[cfg(feature = "clap")]
+use clap::{CommandFactory, Parser};
+
+#[cfg(feature = "clap")]
+#[test]
+fn spinning_table() {
+    let parameters = spinning_table::Parameters::parse_from(["spinning-table"]);
+    let output = spinning_table::spinning_table(parameters).unwrap();
+
+    assert_eq!(output.number_of_solutions, 19);
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn scrabble() {
+    let parameters = scrabble::Parameters::parse_from(["scrabble", "--output", "count"]);
+    let output = scrabble::scrabble(parameters).unwrap();
+
+    assert_eq!(output, scrabble::Output::Count(138));
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn card() {
+    let parameters = card::Parameters::parse_from(["card", "--number-of-cards", "3"]);
+    let output = card::card(parameters).unwrap();
+
+    assert_eq!(&output.flips[..], &[1, 2, 1, 3, 1, 2, 1]);
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn card_rle() {
+    let parameters = card::Parameters::parse_from(["card", "--output", "rle"]);
+    let output = card::card(parameters).unwrap();
+    let encoded = output.to_string();
+
+    assert_eq!(encoded, card::encode_rle(&output.flips));
+    assert_eq!(card::decode_rle(&encoded).unwrap(), output.flips);
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn command_line_definitions() {
+    spinning_table::Parameters::command().debug_assert();
+    spinning_table::DihedralCountParameters::command().debug_assert();
+    scrabble::Parameters::command().debug_assert();
+    card::Parameters::command().debug_assert();
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn default_parameters() {
+    /// Formats the parameters parsed from the name of the puzzle and from `Default`.
+    fn both<P: Parser + Default + std::fmt::Debug>(name: &str) -> (String, String) {
+        let parsed = P::parse_from([name]);
+        (format!("{:?}", parsed), format!("{:?}", P::default()))
+    }
+
+    let (parsed, default) = both::<spinning_table::Parameters>(SpinningTable::NAME);
+    assert_eq!(parsed, default);
+    let (parsed, default) = both::<spinning_table::DihedralCountParameters>("dihedral-count");
+    assert_eq!(parsed, default);
+    let (parsed, default) = both::<scrabble::Parameters>(Scrabble::NAME);
+    assert_eq!(parsed, default);
+    let (parsed, default) = both::<card::Parameters>(Card::NAME);
+    assert_eq!(parsed, default);
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn typed_values() {
+    // The choices can be given in any case, and `byscore` is still accepted.
+    for output in &["by-score", "BYSCORE"] {
+        assert!(scrabble::Parameters::try_parse_from(["scrabble", "--output", output]).is_ok());
+    }
+
+    let err = card::Parameters::try_parse_from(["card", "-n", "300"]).unwrap_err();
+    assert!(err.to_string().contains("300 is not in 0..=255"), "{}", err);
+
+    let err =
+        spinning_table::Parameters::try_parse_from(["spinning-table", "-n", "0"]).unwrap_err();
+    assert!(err.to_string().contains("0 is not in 1.."), "{}", err);
+
+    // `-h` is the hand size.
+    assert!(scrabble::Parameters::try_parse_from(["scrabble", "-h", "8", "-s", "50"]).is_ok());
+}
+
+/// Solves a puzzle with the default parameters through the `Solver` trait.
+fn solve_default<S>() -> S::Output
+where
+    S: Solver,
+    S::Parameters: Default,
+{
+    S::solve(S::Parameters::default()).unwrap()
+}
+
+#[test]
+fn solver_trait() {
+    assert_eq!(solve_default::<SpinningTable>().number_of_solutions, 19);
+    assert_eq!(solve_default::<Scrabble>(), scrabble::Output::Count(138));
+    assert_eq!(solve_default::<Card>().flips.len(), 15);
+
+    let names: Vec<_> = puzzle_info::puzzles()
+        .iter()
+        .map(|puzzle| puzzle.name)
+        .collect();
+    assert_eq!(names, [SpinningTable::NAME, Scrabble::NAME, Card::NAME]);
+}
+
+#[test]
+fn errors() {
+    /// Builds the parameters and solves the spinning table puzzle,
+    /// failing with the crate's error type at either step.
+    fn solve(builder: spinning_table::ParametersBuilder) -> Result<spinning_table::Output, Error> {
+        SpinningTable::solve(builder.build()?)
+    }
+
+    let result = solve(spinning_table::Parameters::builder().names(vec!["Alice"]));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "1 names were given for 7 seats"
+    );
+
+    let result = solve(spinning_table::Parameters::builder().seed(1));
+    assert_eq!(result.unwrap_err().to_string(), "`seed` requires `shuffle`");
+
+    let parameters = card::Parameters::builder()
+        .narrate(true)
+        .start("UUU".parse().unwrap())
+        .build()
+        .unwrap();
+    assert!(matches!(Card::solve(parameters), Err(Error::StartState(_))));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let spinning_table = solve_default::<SpinningTable>();
+    let json = serde_json::to_string(&spinning_table).unwrap();
+    let deserialized: spinning_table::Output = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.to_string(), spinning_table.to_string());
+
+    let scrabble = solve_default::<Scrabble>();
+    assert_eq!(
+        serde_json::to_string(&scrabble).unwrap(),
+        r#"{"Count":138}"#
+    );
+    let deserialized: scrabble::Output = serde_json::from_str(r#"{"Count":138}"#).unwrap();
+    assert_eq!(deserialized, scrabble);
+
+    let card = solve_default::<Card>();
+    let json = serde_json::to_string(&card).unwrap();
+    let deserialized: card::Output = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.flips, card.flips);
+    assert_eq!(deserialized.to_string(), card.to_string());
+}